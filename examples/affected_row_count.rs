@@ -1,10 +1,8 @@
 //! Shows affected row count
 
 extern crate odbc;
-extern crate odbc_safe;
 
 use odbc::*;
-use odbc_safe::AutocommitOn;
 
 fn main() {
     let env = create_environment_v3().unwrap();
@@ -13,7 +11,7 @@ fn main() {
     exec(&conn, "DELETE FROM movies WHERE title = 'TEST movie'");
 }
 
-fn exec(conn: &Connection<AutocommitOn>, sql: &str) {
+fn exec(conn: &Connection, sql: &str) {
     let stmt = Statement::with_parent(conn).unwrap();
     let rs = stmt.exec_direct(sql).unwrap();
     match rs {
@@ -0,0 +1,146 @@
+//! # ODBC derive
+//! Implements `#[derive(FromRow)]` for the [odbc](https://crates.io/crates/odbc) crate. Enable
+//! the `derive` feature of `odbc` instead of depending on this crate directly. See
+//! `odbc::FromRow` for documentation.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments,
+    Type,
+};
+
+/// Implements `odbc::FromRow`, retrieving each field from the column of the same name
+#[proc_macro_derive(FromRow, attributes(odbc))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// A field of the struct, together with the options given by its `#[odbc(..)]` attributes
+struct Field {
+    ident: syn::Ident,
+    ty: Type,
+    column: String,
+    skip: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let named = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(unsupported(&input)),
+        },
+        _ => return Err(unsupported(&input)),
+    };
+    let fields = named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field");
+            let mut parsed = Field {
+                column: ident.to_string(),
+                ident,
+                ty: field.ty.clone(),
+                skip: false,
+            };
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("odbc")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        parsed.column = meta.value()?.parse::<LitStr>()?.value();
+                        Ok(())
+                    } else if meta.path.is_ident("skip") {
+                        parsed.skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `rename = \"...\"` or `skip`"))
+                    }
+                })?;
+            }
+            Ok(parsed)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let retrieved: Vec<_> = fields.iter().filter(|f| !f.skip).collect();
+    let num_retrieved = retrieved.len();
+    let cols: Vec<_> = (0..num_retrieved).map(|i| format_ident!("__col_{}", i)).collect();
+    let vals: Vec<_> = (0..num_retrieved).map(|i| format_ident!("__val_{}", i)).collect();
+    let indices: Vec<_> = (0..num_retrieved).collect();
+    let names: Vec<_> = retrieved.iter().map(|f| &f.column).collect();
+    let get_types: Vec<_> = retrieved
+        .iter()
+        .map(|f| option_inner(&f.ty).unwrap_or(&f.ty))
+        .collect();
+
+    let mut retrieved_iter = retrieved.iter().zip(&vals);
+    let inits = fields.iter().map(|field| {
+        let ident = &field.ident;
+        if field.skip {
+            return quote! { #ident: ::std::default::Default::default() };
+        }
+        let (field, val) = retrieved_iter.next().expect("one value per retrieved field");
+        let column = &field.column;
+        if option_inner(&field.ty).is_some() {
+            quote! { #ident: #val.unwrap() }
+        } else {
+            quote! { #ident: ::odbc::__derive::required(#val.unwrap(), #column)? }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::odbc::FromRow for #name #ty_generics #where_clause {
+            fn from_cursor<'__s, '__a, '__b, __S, __AC: ::odbc::safe::AutocommitMode>(
+                cursor: &mut ::odbc::Cursor<'__s, '__a, '__b, __S, __AC>,
+            ) -> ::odbc::Result<Self> {
+                let __columns = ::odbc::__derive::column_names(cursor)?;
+                #( let #cols = ::odbc::__derive::column_number(&__columns, #names)?; )*
+                #( let mut #vals = ::std::option::Option::None; )*
+                // Many drivers only support retrieving columns in ascending order
+                let mut __order: [(u16, usize); #num_retrieved] = [#( (#cols, #indices) ),*];
+                __order.sort_unstable();
+                for &(__col, __field) in __order.iter() {
+                    match __field {
+                        #( #indices => #vals = ::std::option::Option::Some(
+                            cursor.get_data::<#get_types>(__col)?
+                        ), )*
+                        _ => unreachable!(),
+                    }
+                }
+                ::std::result::Result::Ok(#name { #( #inits ),* })
+            }
+        }
+    })
+}
+
+fn unsupported(input: &DeriveInput) -> Error {
+    Error::new_spanned(
+        &input.ident,
+        "FromRow can only be derived for structs with named fields",
+    )
+}
+
+/// `T` if `ty` is `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match *ty {
+        Type::Path(ref path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => {
+            match args.args[0] {
+                GenericArgument::Type(ref inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
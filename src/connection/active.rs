@@ -0,0 +1,78 @@
+//! Counting open connections in the gauge `sql.connections.active`, if the `metrics` feature is
+//! enabled
+
+/// Held by every `Connection`. Counts as an active connection from creation until dropped,
+/// regardless of changes to the autocommit mode.
+#[derive(Debug)]
+pub(super) struct ActiveConnection(());
+
+impl ActiveConnection {
+    /// Called once a connection has been established
+    pub(super) fn new() -> Self {
+        #[cfg(feature = "metrics")]
+        ::metrics::gauge!("sql.connections.active").increment(1.0);
+        ActiveConnection(())
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        ::metrics::gauge!("sql.connections.active").decrement(1.0);
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod test {
+    use super::*;
+    use metrics::{
+        Counter, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use std::sync::{Arc, Mutex};
+
+    struct Value(Mutex<f64>);
+
+    impl GaugeFn for Value {
+        fn increment(&self, value: f64) {
+            *self.0.lock().unwrap() += value;
+        }
+        fn decrement(&self, value: f64) {
+            *self.0.lock().unwrap() -= value;
+        }
+        fn set(&self, value: f64) {
+            *self.0.lock().unwrap() = value;
+        }
+    }
+
+    /// Records every gauge into the same value
+    struct GaugeRecorder(Arc<Value>);
+
+    impl Recorder for GaugeRecorder {
+        fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        fn register_counter(&self, _key: &Key, _metadata: &Metadata) -> Counter {
+            Counter::noop()
+        }
+        fn register_gauge(&self, _key: &Key, _metadata: &Metadata) -> Gauge {
+            Gauge::from_arc(self.0.clone())
+        }
+        fn register_histogram(&self, _key: &Key, _metadata: &Metadata) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn counts_active_connections() {
+        let value = Arc::new(Value(Mutex::new(0.0)));
+        let recorder = GaugeRecorder(value.clone());
+        metrics::with_local_recorder(&recorder, || {
+            let first = ActiveConnection::new();
+            let second = ActiveConnection::new();
+            assert_eq!(*value.0.lock().unwrap(), 2.0);
+            drop(first);
+            drop(second);
+        });
+        assert_eq!(*value.0.lock().unwrap(), 0.0);
+    }
+}
@@ -0,0 +1,359 @@
+//! Connection attributes, set with `SQLSetConnectAttr` and queried with `SQLGetConnectAttr`
+use super::Connection;
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::ptr::null_mut;
+use result::with_growing_buffer;
+use {ffi, Handle, Result, Return};
+
+/// A connection attribute together with its value. Used by `Connection::set_attr` and
+/// `Connection::get_attr`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectAttr {
+    /// Number of seconds to wait for a login request to complete. `0` means no timeout. Only has
+    /// an effect on connections established after setting it.
+    LoginTimeout(u32),
+    /// Number of seconds to wait for any request on the connection to complete, other than query
+    /// execution and login. `0` means no timeout.
+    ConnectionTimeout(u32),
+    /// Name of the catalog used by the data source
+    CurrentCatalog(String),
+    /// Network packet size in bytes
+    PacketSize(u32),
+    /// `true` if the driver manager traces ODBC function calls
+    Trace(bool),
+    /// Name of the file the driver manager writes its trace to
+    TraceFile(String),
+    /// Whether the connection is used for read-only access. Drivers use this as a hint only.
+    AccessMode(AccessMode),
+}
+
+/// Identifies a connection attribute without a value. Passed to `Connection::get_attr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectAttrKey {
+    /// `SQL_ATTR_LOGIN_TIMEOUT`
+    LoginTimeout,
+    /// `SQL_ATTR_CONNECTION_TIMEOUT`
+    ConnectionTimeout,
+    /// `SQL_ATTR_CURRENT_CATALOG`
+    CurrentCatalog,
+    /// `SQL_ATTR_PACKET_SIZE`
+    PacketSize,
+    /// `SQL_ATTR_TRACE`
+    Trace,
+    /// `SQL_ATTR_TRACEFILE`
+    TraceFile,
+    /// `SQL_ATTR_ACCESS_MODE`
+    AccessMode,
+}
+
+impl fmt::Display for ConnectAttrKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ConnectAttrKey::LoginTimeout => "SQL_ATTR_LOGIN_TIMEOUT",
+            ConnectAttrKey::ConnectionTimeout => "SQL_ATTR_CONNECTION_TIMEOUT",
+            ConnectAttrKey::CurrentCatalog => "SQL_ATTR_CURRENT_CATALOG",
+            ConnectAttrKey::PacketSize => "SQL_ATTR_PACKET_SIZE",
+            ConnectAttrKey::Trace => "SQL_ATTR_TRACE",
+            ConnectAttrKey::TraceFile => "SQL_ATTR_TRACEFILE",
+            ConnectAttrKey::AccessMode => "SQL_ATTR_ACCESS_MODE",
+        })
+    }
+}
+
+/// Value of the `SQL_ATTR_ACCESS_MODE` connection attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessMode {
+    /// `SQL_MODE_READ_WRITE`
+    ReadWrite,
+    /// `SQL_MODE_READ_ONLY`
+    ReadOnly,
+}
+
+impl fmt::Display for AccessMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            AccessMode::ReadWrite => "SQL_MODE_READ_WRITE",
+            AccessMode::ReadOnly => "SQL_MODE_READ_ONLY",
+        })
+    }
+}
+
+impl ConnectAttr {
+    /// The key identifying this attribute
+    pub fn key(&self) -> ConnectAttrKey {
+        match *self {
+            ConnectAttr::LoginTimeout(_) => ConnectAttrKey::LoginTimeout,
+            ConnectAttr::ConnectionTimeout(_) => ConnectAttrKey::ConnectionTimeout,
+            ConnectAttr::CurrentCatalog(_) => ConnectAttrKey::CurrentCatalog,
+            ConnectAttr::PacketSize(_) => ConnectAttrKey::PacketSize,
+            ConnectAttr::Trace(_) => ConnectAttrKey::Trace,
+            ConnectAttr::TraceFile(_) => ConnectAttrKey::TraceFile,
+            ConnectAttr::AccessMode(_) => ConnectAttrKey::AccessMode,
+        }
+    }
+}
+
+impl ConnectAttrKey {
+    fn attribute(&self) -> ffi::SqlConnectionAttribute {
+        match *self {
+            ConnectAttrKey::LoginTimeout => ffi::SQL_ATTR_LOGIN_TIMEOUT,
+            ConnectAttrKey::ConnectionTimeout => ffi::SQL_ATTR_CONNECTION_TIMEOUT,
+            ConnectAttrKey::CurrentCatalog => ffi::SQL_ATTR_CURRENT_CATALOG,
+            ConnectAttrKey::PacketSize => ffi::SQL_ATTR_PACKET_SIZE,
+            ConnectAttrKey::Trace => ffi::SQL_ATTR_TRACE,
+            ConnectAttrKey::TraceFile => ffi::SQL_ATTR_TRACEFILE,
+            ConnectAttrKey::AccessMode => ffi::SQL_ATTR_ACCESS_MODE,
+        }
+    }
+}
+
+impl<'env, AC: AutocommitMode> Connection<'env, AC> {
+    /// Sets a connection attribute. Wraps `SQLSetConnectAttr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let mut conn = env.connect("TestDataSource", "", "")?;
+    /// conn.set_attr(ConnectAttr::ConnectionTimeout(10))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_attr(&mut self, attr: ConnectAttr) -> Result<()> {
+        set_attr(self, &attr).into_result(self)
+    }
+
+    /// Sets the number of seconds to wait for any request on the connection to complete, other
+    /// than query execution and login. `0` means no timeout. Shorthand for `set_attr` with
+    /// `ConnectAttr::ConnectionTimeout`.
+    ///
+    /// To limit the time spent on login use `Environment::connect_with_login_timeout`, for query
+    /// execution use `Statement::with_query_timeout`.
+    pub fn with_connection_timeout(mut self, seconds: u32) -> Result<Self> {
+        self.set_attr(ConnectAttr::ConnectionTimeout(seconds))?;
+        Ok(self)
+    }
+
+    /// Returns the current value of a connection attribute. Wraps `SQLGetConnectAttr`.
+    ///
+    /// `None` if the attribute has no value (`SQL_NO_DATA`), e.g. `CurrentCatalog` for a data
+    /// source without catalogs, or `TraceFile` if tracing has never been configured.
+    pub fn get_attr(&self, key: ConnectAttrKey) -> Result<Option<ConnectAttr>> {
+        let attribute = key.attribute();
+        let ret = match key {
+            ConnectAttrKey::LoginTimeout => self
+                .get_integer_attr(attribute)
+                .map(|v| v.map(ConnectAttr::LoginTimeout)),
+            ConnectAttrKey::ConnectionTimeout => self
+                .get_integer_attr(attribute)
+                .map(|v| v.map(ConnectAttr::ConnectionTimeout)),
+            ConnectAttrKey::PacketSize => self
+                .get_integer_attr(attribute)
+                .map(|v| v.map(ConnectAttr::PacketSize)),
+            ConnectAttrKey::Trace => self
+                .get_integer_attr(attribute)
+                .map(|v| v.map(|v| ConnectAttr::Trace(v != ffi::SQL_OPT_TRACE_OFF))),
+            ConnectAttrKey::AccessMode => self.get_integer_attr(attribute).map(|v| {
+                v.map(|v| {
+                    ConnectAttr::AccessMode(if v == ffi::SQL_MODE_READ_ONLY {
+                        AccessMode::ReadOnly
+                    } else {
+                        AccessMode::ReadWrite
+                    })
+                })
+            }),
+            ConnectAttrKey::CurrentCatalog => self.get_string_attr(attribute).map(|bytes| {
+                bytes.map(|bytes| {
+                    let catalog = unsafe { ::environment::DB_ENCODING }.decode(&bytes).0;
+                    ConnectAttr::CurrentCatalog(catalog.into_owned())
+                })
+            }),
+            ConnectAttrKey::TraceFile => self.get_string_attr(attribute).map(|bytes| {
+                bytes.map(|bytes| {
+                    let path = unsafe { ::environment::OS_ENCODING }.decode(&bytes).0;
+                    ConnectAttr::TraceFile(path.into_owned())
+                })
+            }),
+        };
+        ret.into_result(self)
+    }
+
+    /// Name of the catalog (database) currently used by the connection. Shorthand for `get_attr`
+    /// with `ConnectAttrKey::CurrentCatalog`. `None` if the driver reports no catalog.
+    pub fn current_catalog(&self) -> Result<Option<String>> {
+        match self.get_attr(ConnectAttrKey::CurrentCatalog)? {
+            Some(ConnectAttr::CurrentCatalog(catalog)) => Ok(Some(catalog)),
+            None => Ok(None),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Switches the connection to another catalog (database). Shorthand for `set_attr` with
+    /// `ConnectAttr::CurrentCatalog`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let mut conn = env.connect("TestDataSource", "", "")?;
+    /// let previous = conn.current_catalog()?;
+    /// conn.set_current_catalog("tenant_42")?;
+    /// // ...
+    /// if let Some(previous) = previous {
+    ///     conn.set_current_catalog(&previous)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_current_catalog(&mut self, catalog: &str) -> Result<()> {
+        self.set_attr(ConnectAttr::CurrentCatalog(catalog.to_owned()))
+    }
+
+    /// `true` if the connection to the server has been lost. Queries the read-only
+    /// `SQL_ATTR_CONNECTION_DEAD` attribute, which is cheap, since it does not involve a round
+    /// trip to the server. It only reflects the state of the connection at the time of the last
+    /// request, though.
+    pub fn is_dead(&self) -> Result<bool> {
+        self.get_integer_attr(ffi::SQL_ATTR_CONNECTION_DEAD)
+            .map(|v| v == Some(ffi::SQL_CD_TRUE))
+            .into_result(self)
+    }
+
+    /// Network packet size in bytes, as negotiated with the server while connecting. Shorthand
+    /// for `get_attr` with `ConnectAttrKey::PacketSize`.
+    ///
+    /// Drivers which do not use network packets, or do not report their size, return either an
+    /// error for which `is_unsupported` is `true`, or `None`.
+    pub fn packet_size(&self) -> Result<Option<u32>> {
+        match self.get_attr(ConnectAttrKey::PacketSize)? {
+            Some(ConnectAttr::PacketSize(size)) => Ok(Some(size)),
+            None => Ok(None),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_integer_attr(
+        &self,
+        attribute: ffi::SqlConnectionAttribute,
+    ) -> Return<Option<ffi::SQLUINTEGER>> {
+        let mut value: ffi::SQLUINTEGER = 0;
+        get_connect_attr(
+            self,
+            attribute,
+            &mut value as *mut ffi::SQLUINTEGER as ffi::SQLPOINTER,
+            0,
+            null_mut(),
+        )
+        .map(|has_value| if has_value { Some(value) } else { None })
+    }
+
+    fn get_string_attr(&self, attribute: ffi::SqlConnectionAttribute) -> Return<Option<Vec<u8>>> {
+        with_growing_buffer(256, |buffer| {
+            let mut length: ffi::SQLINTEGER = 0;
+            let ret = get_connect_attr(
+                self,
+                attribute,
+                buffer.as_mut_ptr() as ffi::SQLPOINTER,
+                buffer.len() as ffi::SQLINTEGER,
+                &mut length,
+            );
+            (ret, length.max(0) as usize)
+        })
+        // `false` if the attribute has no value
+        .map(|(has_value, value)| if has_value { Some(value) } else { None })
+    }
+}
+
+/// Sets a connection attribute on any connection handle, connected or not
+pub(super) fn set_attr<C: Handle<To = ffi::Dbc>>(conn: &mut C, attr: &ConnectAttr) -> Return<()> {
+    let attribute = attr.key().attribute();
+    match *attr {
+        ConnectAttr::LoginTimeout(v)
+        | ConnectAttr::ConnectionTimeout(v)
+        | ConnectAttr::PacketSize(v) => set_integer_attr(conn, attribute, v),
+        ConnectAttr::Trace(on) => set_integer_attr(
+            conn,
+            attribute,
+            if on {
+                ffi::SQL_OPT_TRACE_ON
+            } else {
+                ffi::SQL_OPT_TRACE_OFF
+            },
+        ),
+        ConnectAttr::AccessMode(mode) => set_integer_attr(
+            conn,
+            attribute,
+            match mode {
+                AccessMode::ReadWrite => ffi::SQL_MODE_READ_WRITE,
+                AccessMode::ReadOnly => ffi::SQL_MODE_READ_ONLY,
+            },
+        ),
+        ConnectAttr::CurrentCatalog(ref catalog) => {
+            let bytes = unsafe { ::environment::DB_ENCODING }.encode(catalog).0;
+            set_string_attr(conn, attribute, &bytes)
+        }
+        ConnectAttr::TraceFile(ref path) => {
+            let bytes = unsafe { ::environment::OS_ENCODING }.encode(path).0;
+            set_string_attr(conn, attribute, &bytes)
+        }
+    }
+}
+
+fn set_integer_attr<C: Handle<To = ffi::Dbc>>(
+    conn: &mut C,
+    attribute: ffi::SqlConnectionAttribute,
+    value: ffi::SQLUINTEGER,
+) -> Return<()> {
+    set_connect_attr(conn, attribute, value as usize as ffi::SQLPOINTER, 0)
+}
+
+fn set_string_attr<C: Handle<To = ffi::Dbc>>(
+    conn: &mut C,
+    attribute: ffi::SqlConnectionAttribute,
+    value: &[u8],
+) -> Return<()> {
+    set_connect_attr(
+        conn,
+        attribute,
+        value.as_ptr() as ffi::SQLPOINTER,
+        value.len() as ffi::SQLINTEGER,
+    )
+}
+
+fn set_connect_attr<C: Handle<To = ffi::Dbc>>(
+    conn: &mut C,
+    attribute: ffi::SqlConnectionAttribute,
+    value: ffi::SQLPOINTER,
+    string_length: ffi::SQLINTEGER,
+) -> Return<()> {
+    match unsafe { ffi::SQLSetConnectAttr(conn.handle(), attribute, value, string_length) } {
+        ffi::SQL_SUCCESS => Return::Success(()),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+        ffi::SQL_ERROR => Return::Error,
+        r => panic!("SQLSetConnectAttr returned unexpected result: {:?}", r),
+    }
+}
+
+fn get_connect_attr<C: Handle<To = ffi::Dbc>>(
+    conn: &C,
+    attribute: ffi::SqlConnectionAttribute,
+    value: ffi::SQLPOINTER,
+    buffer_length: ffi::SQLINTEGER,
+    string_length: *mut ffi::SQLINTEGER,
+) -> Return<bool> {
+    match unsafe {
+        ffi::SQLGetConnectAttr(conn.handle(), attribute, value, buffer_length, string_length)
+    } {
+        ffi::SQL_SUCCESS => Return::Success(true),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
+        ffi::SQL_ERROR => Return::Error,
+        // The attribute has no value
+        ffi::SQL_NO_DATA => Return::Success(false),
+        r => panic!("SQLGetConnectAttr returned unexpected result: {:?}", r),
+    }
+}
@@ -0,0 +1,128 @@
+//! Fluent construction of connections, see `ConnectionBuilder`
+use super::redact::redact_password;
+use super::{AccessMode, ConnectAttr, Connection, DisconnectedConnection};
+use odbc_safe::AutocommitOn;
+use std::fmt;
+use {safe, Environment, Result, Version3};
+
+/// Collects everything needed to connect to a data source, including connection attributes which
+/// must be set before connecting, like `ConnectAttr::PacketSize` or `ConnectAttr::LoginTimeout`
+///
+/// Attributes are set in the order they have been specified, right after allocating the
+/// connection handle. Connects using `SQLConnect` with the data source name, user and password,
+/// or using `SQLDriverConnect` if a connection string has been specified.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = ConnectionBuilder::new(&env)
+///     .dsn("TestDataSource")
+///     .login_timeout(5)
+///     .access_mode(AccessMode::ReadOnly)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ConnectionBuilder<'env, V: 'env = Version3> {
+    env: &'env Environment<V>,
+    dsn: String,
+    uid: String,
+    pwd: String,
+    connection_string: Option<String>,
+    attrs: Vec<ConnectAttr>,
+}
+
+impl<'env, V: safe::Version> ConnectionBuilder<'env, V> {
+    /// A builder for a connection allocated in `env`, without any attributes
+    pub fn new(env: &'env Environment<V>) -> Self {
+        ConnectionBuilder {
+            env,
+            dsn: String::new(),
+            uid: String::new(),
+            pwd: String::new(),
+            connection_string: None,
+            attrs: Vec::new(),
+        }
+    }
+
+    /// Data source name configured in the `odbc.ini` file
+    pub fn dsn(mut self, dsn: &str) -> Self {
+        self.dsn = dsn.to_owned();
+        self
+    }
+
+    /// User identifier
+    pub fn uid(mut self, uid: &str) -> Self {
+        self.uid = uid.to_owned();
+        self
+    }
+
+    /// Authentication (usually password)
+    pub fn pwd(mut self, pwd: &str) -> Self {
+        self.pwd = pwd.to_owned();
+        self
+    }
+
+    /// Connects with a connection string instead of data source name, user and password. See
+    /// `Environment::connect_with_connection_string`.
+    pub fn connection_string(mut self, connection_str: &str) -> Self {
+        self.connection_string = Some(connection_str.to_owned());
+        self
+    }
+
+    /// Sets an arbitrary connection attribute before connecting
+    pub fn attr(mut self, attr: ConnectAttr) -> Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Number of seconds to wait for the login to complete. `0` means no timeout.
+    pub fn login_timeout(self, seconds: u32) -> Self {
+        self.attr(ConnectAttr::LoginTimeout(seconds))
+    }
+
+    /// Number of seconds to wait for any request on the connection to complete, other than query
+    /// execution and login. `0` means no timeout.
+    pub fn connection_timeout(self, seconds: u32) -> Self {
+        self.attr(ConnectAttr::ConnectionTimeout(seconds))
+    }
+
+    /// Network packet size in bytes. Most drivers only support setting it before connecting.
+    pub fn packet_size(self, bytes: u32) -> Self {
+        self.attr(ConnectAttr::PacketSize(bytes))
+    }
+
+    /// Whether the connection is used for read-only access
+    pub fn access_mode(self, mode: AccessMode) -> Self {
+        self.attr(ConnectAttr::AccessMode(mode))
+    }
+
+    /// Allocates the connection handle, sets all attributes and connects to the data source
+    pub fn build(self) -> Result<Connection<'env, AutocommitOn>> {
+        let disconnected = DisconnectedConnection::with_parent_and_attrs(self.env, &self.attrs)?;
+        match self.connection_string {
+            Some(ref connection_str) => disconnected.connect_with_connection_string(connection_str),
+            None => disconnected.connect(&self.dsn, &self.uid, &self.pwd),
+        }
+    }
+}
+
+/// Shows the password as `***`, including a password within the connection string
+impl<'env, V: fmt::Debug> fmt::Debug for ConnectionBuilder<'env, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConnectionBuilder")
+            .field("env", &self.env)
+            .field("dsn", &self.dsn)
+            .field("uid", &self.uid)
+            .field("pwd", &"***")
+            .field(
+                "connection_string",
+                &self.connection_string.as_ref().map(|s| redact_password(s)),
+            )
+            .field("attrs", &self.attrs)
+            .finish()
+    }
+}
@@ -0,0 +1,262 @@
+//! Information about driver and data source, queried with `SQLGetInfo`
+use super::Connection;
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::ptr::null_mut;
+use result::with_growing_buffer;
+use {ffi, Handle, Result, Return};
+
+/// Information which can be queried using `Connection::get_info`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InfoType {
+    /// Data source name used during connection (`SQL_DATA_SOURCE_NAME`). `String`
+    DataSourceName,
+    /// `"Y"` if the data source is read-only, `"N"` otherwise (`SQL_DATA_SOURCE_READ_ONLY`).
+    /// `String`
+    DataSourceReadOnly,
+    /// Name of the DBMS product accessed by the driver (`SQL_DBMS_NAME`). `String`
+    DbmsName,
+    /// Version of the DBMS product accessed by the driver (`SQL_DBMS_VER`). `String`
+    DbmsVersion,
+    /// File name of the driver (`SQL_DRIVER_NAME`). `String`
+    DriverName,
+    /// Version of the driver (`SQL_DRIVER_VER`). `String`
+    DriverVersion,
+    /// Version of ODBC the driver supports, e.g. `"03.80"` (`SQL_DRIVER_ODBC_VER`). `String`
+    DriverOdbcVersion,
+    /// Actual data source specific server name (`SQL_SERVER_NAME`). `String`
+    ServerName,
+    /// Name used in a particular database (`SQL_USER_NAME`). `String`
+    UserName,
+    /// Character used to quote identifiers, or `" "` if quoting is not supported
+    /// (`SQL_IDENTIFIER_QUOTE_CHAR`). `String`
+    IdentifierQuoteChar,
+    /// Character used to escape wildcards in search patterns (`SQL_SEARCH_PATTERN_ESCAPE`).
+    /// `String`
+    SearchPatternEscape,
+    /// Maximum number of active connections, `0` if unknown (`SQL_MAX_DRIVER_CONNECTIONS`). `U16`
+    MaxDriverConnections,
+    /// Maximum number of active statements per connection, `0` if unknown
+    /// (`SQL_MAX_CONCURRENT_ACTIVITIES`). `U16`
+    MaxConcurrentActivities,
+    /// Maximum length of a catalog name, `0` if unknown (`SQL_MAX_CATALOG_NAME_LEN`). `U16`
+    MaxCatalogNameLength,
+    /// Maximum length of a schema name, `0` if unknown (`SQL_MAX_SCHEMA_NAME_LEN`). `U16`
+    MaxSchemaNameLength,
+    /// Maximum length of a table name, `0` if unknown (`SQL_MAX_TABLE_NAME_LEN`). `U16`
+    MaxTableNameLength,
+    /// Maximum length of a column name, `0` if unknown (`SQL_MAX_COLUMN_NAME_LEN`). `U16`
+    MaxColumnNameLength,
+    /// Maximum length of an SQL statement, `0` if unknown (`SQL_MAX_STATEMENT_LEN`). `U32`
+    MaxStatementLength,
+}
+
+impl fmt::Display for InfoType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            InfoType::DataSourceName => "SQL_DATA_SOURCE_NAME",
+            InfoType::DataSourceReadOnly => "SQL_DATA_SOURCE_READ_ONLY",
+            InfoType::DbmsName => "SQL_DBMS_NAME",
+            InfoType::DbmsVersion => "SQL_DBMS_VER",
+            InfoType::DriverName => "SQL_DRIVER_NAME",
+            InfoType::DriverVersion => "SQL_DRIVER_VER",
+            InfoType::DriverOdbcVersion => "SQL_DRIVER_ODBC_VER",
+            InfoType::ServerName => "SQL_SERVER_NAME",
+            InfoType::UserName => "SQL_USER_NAME",
+            InfoType::IdentifierQuoteChar => "SQL_IDENTIFIER_QUOTE_CHAR",
+            InfoType::SearchPatternEscape => "SQL_SEARCH_PATTERN_ESCAPE",
+            InfoType::MaxDriverConnections => "SQL_MAX_DRIVER_CONNECTIONS",
+            InfoType::MaxConcurrentActivities => "SQL_MAX_CONCURRENT_ACTIVITIES",
+            InfoType::MaxCatalogNameLength => "SQL_MAX_CATALOG_NAME_LEN",
+            InfoType::MaxSchemaNameLength => "SQL_MAX_SCHEMA_NAME_LEN",
+            InfoType::MaxTableNameLength => "SQL_MAX_TABLE_NAME_LEN",
+            InfoType::MaxColumnNameLength => "SQL_MAX_COLUMN_NAME_LEN",
+            InfoType::MaxStatementLength => "SQL_MAX_STATEMENT_LEN",
+        })
+    }
+}
+
+/// Value returned by `Connection::get_info`. Which variant is returned depends on the
+/// `InfoType` and is stated in its documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InfoValue {
+    /// Character string information
+    String(String),
+    /// `SQLUSMALLINT` information
+    U16(u16),
+    /// `SQLUINTEGER` information
+    U32(u32),
+}
+
+enum ValueKind {
+    String,
+    U16,
+    U32,
+}
+
+impl InfoType {
+    fn info_type(&self) -> ffi::SQLUSMALLINT {
+        match *self {
+            InfoType::DataSourceName => ffi::SQL_DATA_SOURCE_NAME as ffi::SQLUSMALLINT,
+            InfoType::DataSourceReadOnly => ffi::SQL_DATA_SOURCE_READ_ONLY as ffi::SQLUSMALLINT,
+            InfoType::DbmsName => ffi::SQL_DBMS_NAME as ffi::SQLUSMALLINT,
+            InfoType::DbmsVersion => ffi::SQL_DBMS_VER as ffi::SQLUSMALLINT,
+            InfoType::DriverName => ffi::SQL_DRIVER_NAME,
+            InfoType::DriverVersion => ffi::SQL_DRIVER_VER,
+            InfoType::DriverOdbcVersion => ffi::SQL_DRIVER_ODBC_VER,
+            InfoType::ServerName => ffi::SQL_SERVER_NAME as ffi::SQLUSMALLINT,
+            InfoType::UserName => ffi::SQL_USER_NAME as ffi::SQLUSMALLINT,
+            InfoType::IdentifierQuoteChar => ffi::SQL_IDENTIFIER_QUOTE_CHAR as ffi::SQLUSMALLINT,
+            InfoType::SearchPatternEscape => ffi::SQL_SEARCH_PATTERN_ESCAPE as ffi::SQLUSMALLINT,
+            InfoType::MaxDriverConnections => ffi::SQL_MAX_DRIVER_CONNECTIONS as ffi::SQLUSMALLINT,
+            InfoType::MaxConcurrentActivities => {
+                ffi::SQL_MAX_CONCURRENT_ACTIVITIES as ffi::SQLUSMALLINT
+            }
+            InfoType::MaxCatalogNameLength => ffi::SQL_MAX_CATALOG_NAME_LEN as ffi::SQLUSMALLINT,
+            InfoType::MaxSchemaNameLength => ffi::SQL_MAX_SCHEMA_NAME_LEN as ffi::SQLUSMALLINT,
+            InfoType::MaxTableNameLength => ffi::SQL_MAX_TABLE_NAME_LEN as ffi::SQLUSMALLINT,
+            InfoType::MaxColumnNameLength => ffi::SQL_MAX_COLUMN_NAME_LEN as ffi::SQLUSMALLINT,
+            InfoType::MaxStatementLength => ffi::SQL_MAX_STATEMENT_LEN as ffi::SQLUSMALLINT,
+        }
+    }
+
+    fn value_kind(&self) -> ValueKind {
+        match *self {
+            InfoType::DataSourceName
+            | InfoType::DataSourceReadOnly
+            | InfoType::DbmsName
+            | InfoType::DbmsVersion
+            | InfoType::DriverName
+            | InfoType::DriverVersion
+            | InfoType::DriverOdbcVersion
+            | InfoType::ServerName
+            | InfoType::UserName
+            | InfoType::IdentifierQuoteChar
+            | InfoType::SearchPatternEscape => ValueKind::String,
+            InfoType::MaxDriverConnections
+            | InfoType::MaxConcurrentActivities
+            | InfoType::MaxCatalogNameLength
+            | InfoType::MaxSchemaNameLength
+            | InfoType::MaxTableNameLength
+            | InfoType::MaxColumnNameLength => ValueKind::U16,
+            InfoType::MaxStatementLength => ValueKind::U32,
+        }
+    }
+}
+
+impl<'env, AC: AutocommitMode> Connection<'env, AC> {
+    /// Returns general information about the driver and data source. Wraps `SQLGetInfo`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// if let InfoValue::String(name) = conn.get_info(InfoType::DbmsName)? {
+    ///     println!("Connected to {}", name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_info(&self, info_type: InfoType) -> Result<InfoValue> {
+        let it = info_type.info_type();
+        let ret = match info_type.value_kind() {
+            ValueKind::String => self.get_string_info(it).map(|bytes| {
+                let value = unsafe { ::environment::DB_ENCODING }.decode(&bytes).0;
+                InfoValue::String(value.into_owned())
+            }),
+            ValueKind::U16 => {
+                let mut value: ffi::SQLUSMALLINT = 0;
+                get_info(self, it, &mut value as *mut _ as ffi::SQLPOINTER, 0, null_mut())
+                    .map(|()| InfoValue::U16(value))
+            }
+            ValueKind::U32 => {
+                let mut value: ffi::SQLUINTEGER = 0;
+                get_info(self, it, &mut value as *mut _ as ffi::SQLPOINTER, 0, null_mut())
+                    .map(|()| InfoValue::U32(value))
+            }
+        };
+        ret.into_result(self)
+    }
+
+    /// Name of the server the connection has been established to (`SQL_SERVER_NAME`)
+    pub fn server_name(&self) -> Result<String> {
+        self.string_info(InfoType::ServerName)
+    }
+
+    /// Name of the user in the database, which may differ from the login name
+    /// (`SQL_USER_NAME`)
+    pub fn user_name(&self) -> Result<String> {
+        self.string_info(InfoType::UserName)
+    }
+
+    /// Name of the DBMS product, e.g. `"Microsoft SQL Server"` or `"PostgreSQL"`
+    /// (`SQL_DBMS_NAME`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// println!("Connected to {} {}", conn.dbms_name()?, conn.dbms_version()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dbms_name(&self) -> Result<String> {
+        self.string_info(InfoType::DbmsName)
+    }
+
+    /// Version of the DBMS product, in the form `##.##.####` optionally followed by a product
+    /// specific description (`SQL_DBMS_VER`)
+    pub fn dbms_version(&self) -> Result<String> {
+        self.string_info(InfoType::DbmsVersion)
+    }
+
+    /// File name of the driver (`SQL_DRIVER_NAME`)
+    pub fn driver_name(&self) -> Result<String> {
+        self.string_info(InfoType::DriverName)
+    }
+
+    fn string_info(&self, info_type: InfoType) -> Result<String> {
+        match self.get_info(info_type)? {
+            InfoValue::String(value) => Ok(value),
+            other => panic!("{} returned {:?} instead of a string", info_type, other),
+        }
+    }
+
+    fn get_string_info(&self, info_type: ffi::SQLUSMALLINT) -> Return<Vec<u8>> {
+        with_growing_buffer(256, |buffer| {
+            let mut length: ffi::SQLSMALLINT = 0;
+            let ret = get_info(
+                self,
+                info_type,
+                buffer.as_mut_ptr() as ffi::SQLPOINTER,
+                buffer.len() as ffi::SQLSMALLINT,
+                &mut length,
+            );
+            (ret, length.max(0) as usize)
+        })
+        .map(|((), value)| value)
+    }
+}
+
+fn get_info<C: Handle<To = ffi::Dbc>>(
+    conn: &C,
+    info_type: ffi::SQLUSMALLINT,
+    value: ffi::SQLPOINTER,
+    buffer_length: ffi::SQLSMALLINT,
+    string_length: *mut ffi::SQLSMALLINT,
+) -> Return<()> {
+    match unsafe {
+        ffi::SQLGetInfoInteger(conn.handle(), info_type, value, buffer_length, string_length)
+    } {
+        ffi::SQL_SUCCESS => Return::Success(()),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+        ffi::SQL_ERROR => Return::Error,
+        r => panic!("SQLGetInfo returned unexpected result: {:?}", r),
+    }
+}
@@ -0,0 +1,460 @@
+//! Holds implementation of odbc connection
+use super::{ffi, safe, DiagnosticRecord, Environment, Handle, Result, Return};
+use super::result::{error_record, into_result, into_result_with};
+use odbc_safe::{AutocommitMode, AutocommitOn, AutocommitOff};
+use std::ptr::null_mut;
+
+mod active;
+mod attribute;
+mod builder;
+mod info;
+mod native_sql;
+mod redact;
+use self::active::ActiveConnection;
+pub use self::attribute::{AccessMode, ConnectAttr, ConnectAttrKey};
+pub use self::builder::ConnectionBuilder;
+pub use self::info::{InfoType, InfoValue};
+pub(crate) use self::native_sql::native_sql;
+#[cfg(feature = "log")]
+use self::redact::redact_password;
+
+/// Represents a connection to an ODBC data source
+///
+/// The autocommit mode `AC` defaults to `AutocommitOn`, the mode of every new connection, so
+/// `Connection<'env>` names a connection as returned by `Environment::connect`. See
+/// `disable_autocommit`.
+///
+/// Statements borrow the connection they have been allocated on, so it can not be dropped while
+/// any of its statements is still alive:
+///
+/// ```compile_fail
+/// # use odbc::*;
+/// let env = create_environment_v3().unwrap();
+/// let conn = env.connect("TestDataSource", "", "").unwrap();
+/// let stmt = Statement::with_parent(&conn).unwrap();
+/// drop(conn);
+/// drop(stmt);
+/// ```
+#[derive(Debug)]
+pub struct Connection<'env, AC: AutocommitMode = AutocommitOn> {
+    safe: safe::Connection<'env, AC>,
+    active: ActiveConnection,
+}
+
+/// A connection handle, which is not connected to a data source. Either freshly allocated using
+/// `with_parent_and_attrs`, or disconnected from its data source using `Connection::disconnect`.
+///
+/// The handle stays allocated and can be connected again, to the same or another data source,
+/// without allocating a new one. It is freed once dropped.
+#[derive(Debug)]
+pub struct DisconnectedConnection<'env> {
+    safe: safe::DataSource<'env, safe::Unconnected<'env>>,
+}
+
+impl<'env, AC: AutocommitMode> Handle for Connection<'env, AC> {
+    type To = ffi::Dbc;
+    unsafe fn handle(&self) -> ffi::SQLHDBC {
+        self.safe.as_raw()
+    }
+}
+
+// Place Constructors into environment, to make them easier to discover
+impl<V: safe::Version> Environment<V> {
+    /// Connects to an ODBC data source
+    ///
+    /// # Arguments
+    /// * `dsn` - Data source name configured in the `odbc.ini` file
+    /// * `usr` - User identifier
+    /// * `pwd` - Authentication (usually password)
+    pub fn connect<'env>(&'env self, dsn: &str, usr: &str, pwd: &str) -> Result<Connection<'env, AutocommitOn>> {
+        let safe = into_result_with(self, safe::DataSource::with_parent(self.as_safe()))?;
+        let safe = into_result(safe.connect(dsn, usr, pwd))?;
+        Ok(Connection { safe, active: ActiveConnection::new() })
+    }
+
+    /// Connects to an ODBC data source, waiting at most `seconds` for the login to complete
+    ///
+    /// Like `connect`, but sets `SQL_ATTR_LOGIN_TIMEOUT` before connecting, as the login timeout
+    /// has no effect on connections which are already established. `0` means no timeout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env
+    ///     .connect_with_login_timeout("TestDataSource", "", "", 5)?
+    ///     .with_connection_timeout(10)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect_with_login_timeout<'env>(
+        &'env self,
+        dsn: &str,
+        usr: &str,
+        pwd: &str,
+        seconds: u32,
+    ) -> Result<Connection<'env, AutocommitOn>> {
+        let data_source = into_result_with(self, safe::DataSource::with_parent(self.as_safe()))?;
+        let ret = match unsafe {
+            ffi::SQLSetConnectAttr(
+                data_source.as_raw(),
+                ffi::SQL_ATTR_LOGIN_TIMEOUT,
+                seconds as usize as ffi::SQLPOINTER,
+                0,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLSetConnectAttr returned unexpected result: {:?}", r),
+        };
+        ret.into_result(&data_source)?;
+        let safe = into_result(data_source.connect(dsn, usr, pwd))?;
+        Ok(Connection { safe, active: ActiveConnection::new() })
+    }
+
+    /// Connects to an ODBC data source using a connection string
+    ///
+    /// See [SQLDriverConnect][1] for the syntax.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqldriverconnect-function
+    pub fn connect_with_connection_string<'env>(
+        &'env self,
+        connection_str: &str,
+    ) -> Result<Connection<'env, AutocommitOn>> {
+        #[cfg(feature = "log")]
+        debug!("connecting with {}", redact_password(connection_str));
+        let safe = into_result_with(self, safe::DataSource::with_parent(self.as_safe()))?;
+        let safe = into_result(safe.connect_with_connection_string(connection_str))?;
+        Ok(Connection { safe, active: ActiveConnection::new() })
+    }
+
+    /// Connects to an ODBC data source using a connection string, by calling `SQLDriverConnect`
+    /// with `SQL_DRIVER_NOPROMPT`.
+    ///
+    /// Like `connect_with_connection_string`, but also returns the completed connection string
+    /// reported by the driver, which may contain additional attributes, e.g. defaults for options
+    /// missing in `connection_str`. It can be stored and used to reconnect later on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let (conn, completed) = env.driver_connect("DSN=TestDataSource;")?;
+    /// println!("Connected using: {}", completed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn driver_connect<'env>(
+        &'env self,
+        connection_str: &str,
+    ) -> Result<(Connection<'env, AutocommitOn>, String)> {
+        self.driver_connect_impl(connection_str, ffi::SQL_DRIVER_NOPROMPT)
+    }
+
+    /// Connects to an ODBC data source using a connection string, by calling `SQLDriverConnect`
+    /// with `SQL_DRIVER_COMPLETE`.
+    ///
+    /// If `connection_str` lacks information required to connect, the driver may show a dialog
+    /// prompting the user for it. Only use this in environments where a UI is acceptable. Returns
+    /// the connection together with the completed connection string, see `driver_connect`. If the
+    /// user cancels the dialog an error is returned.
+    pub fn driver_connect_with_prompt<'env>(
+        &'env self,
+        connection_str: &str,
+    ) -> Result<(Connection<'env, AutocommitOn>, String)> {
+        self.driver_connect_impl(connection_str, ffi::SQL_DRIVER_COMPLETE)
+    }
+
+    fn driver_connect_impl<'env>(
+        &'env self,
+        connection_str: &str,
+        driver_completion: ffi::SqlDriverConnectOption,
+    ) -> Result<(Connection<'env, AutocommitOn>, String)> {
+        #[cfg(feature = "log")]
+        debug!("connecting with {}", redact_password(connection_str));
+        let data_source = into_result_with(self, safe::DataSource::with_parent(self.as_safe()))?;
+        let connection_str = unsafe { ::environment::DB_ENCODING }.encode(connection_str).0;
+        // The ODBC reference recommends a buffer of at least 1024 characters
+        let mut out_connection_str = vec![0u8; 1024];
+        loop {
+            let mut out_len: ffi::SQLSMALLINT = 0;
+            let ret = match unsafe {
+                ffi::SQLDriverConnect(
+                    data_source.as_raw(),
+                    null_mut(),
+                    connection_str.as_ptr(),
+                    connection_str.len() as ffi::SQLSMALLINT,
+                    out_connection_str.as_mut_ptr(),
+                    out_connection_str.len() as ffi::SQLSMALLINT,
+                    &mut out_len,
+                    driver_completion,
+                )
+            } {
+                ffi::SQL_SUCCESS => Return::Success(()),
+                ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+                // `SQL_NO_DATA` means the user canceled the dialog
+                ffi::SQL_ERROR | ffi::SQL_NO_DATA => Return::Error,
+                r => panic!("SQLDriverConnect returned unexpected result: {:?}", r),
+            };
+            // On error `data_source` is dropped here, freeing the connection handle
+            ret.into_result(&data_source)?;
+            // `out_len` excludes the terminating zero, which has to fit as well
+            let out_len = out_len.max(0) as usize;
+            let max_len = ffi::SQLSMALLINT::MAX as usize;
+            if out_len < out_connection_str.len() || out_connection_str.len() == max_len {
+                out_connection_str.truncate(out_len.min(out_connection_str.len() - 1));
+                break;
+            }
+            // The completed connection string has been truncated. The driver returns it only
+            // while connecting, so connect again with a buffer large enough to hold it.
+            match unsafe { ffi::SQLDisconnect(data_source.as_raw()) } {
+                ffi::SQL_SUCCESS | ffi::SQL_SUCCESS_WITH_INFO => (),
+                _ => return Err(error_record(&data_source)),
+            }
+            out_connection_str = vec![0u8; (out_len + 1).min(max_len)];
+        }
+        let safe = unsafe { safe::Connection::from_raw(data_source.into_raw()) };
+        let out_connection_str = unsafe { ::environment::DB_ENCODING }
+            .decode(&out_connection_str)
+            .0
+            .into_owned();
+        Ok((Connection { safe, active: ActiveConnection::new() }, out_connection_str))
+    }
+}
+
+impl <'env> Connection<'env, AutocommitOn> {
+    /// Disables autocommit mode, by setting `SQL_ATTR_AUTOCOMMIT` to `SQL_AUTOCOMMIT_OFF`.
+    ///
+    /// From now on every statement executed on this connection implicitly becomes part of a
+    /// transaction, which must be ended explicitly using `commit` or `rollback`. In case of an
+    /// error the connection is handed back unchanged.
+    pub fn disable_autocommit(mut self) -> std::result::Result<Connection<'env, AutocommitOff>, Self> {
+        let ret = self.safe.disable_autocommit();
+        match ret {
+            safe::Return::Success(value) => Ok(Connection { safe: value, active: self.active }),
+            safe::Return::Info(value) => Ok(Connection { safe: value, active: self.active }),
+            safe::Return::Error(value) => Err(Connection { safe: value, active: self.active })
+        }
+    }
+}
+
+impl <'env> Connection<'env, AutocommitOff> {
+    /// Enables autocommit mode, by setting `SQL_ATTR_AUTOCOMMIT` to `SQL_AUTOCOMMIT_ON`.
+    ///
+    /// According to the ODBC standard this commits any open transaction. In case of an error the
+    /// connection is handed back unchanged.
+    pub fn enable_autocommit(mut self) -> std::result::Result<Connection<'env, AutocommitOn>, Self> {
+        let ret = self.safe.enable_autocommit();
+        match ret {
+            safe::Return::Success(value) => Ok(Connection { safe: value, active: self.active }),
+            safe::Return::Info(value) => Ok(Connection { safe: value, active: self.active }),
+            safe::Return::Error(value) => Err(Connection { safe: value, active: self.active })
+        }
+    }
+
+    /// Commits the current transaction, by calling `SQLEndTran` with `SQL_COMMIT`.
+    ///
+    /// Makes all changes since the last `commit` or `rollback` permanent. Do not drop or
+    /// disconnect a connection with an open transaction: Most drivers refuse to disconnect in that
+    /// case, rather than rolling back silently, which makes the implicit disconnect in `drop()`
+    /// panic. End the transaction with either `commit` or `rollback` first.
+    pub fn commit(&mut self) -> Result<()> {
+        let ret = self.safe.commit();
+        into_result_with(&self.safe, ret)
+    }
+
+    /// Rolls back the current transaction, by calling `SQLEndTran` with `SQL_ROLLBACK`.
+    ///
+    /// Discards all changes made since the last `commit` or `rollback`.
+    pub fn rollback(&mut self) -> Result<()> {
+        let ret = self.safe.rollback();
+        into_result_with(&self.safe, ret)
+    }
+}
+
+
+impl<'env, AC: AutocommitMode> Connection<'env, AC> {
+    /// `true` if the data source is set to READ ONLY mode, `false` otherwise.
+    ///
+    /// This characteristic pertains only to the data source itself; it is not characteristic of
+    /// the driver that enables access to the data source. A driver that is read/write can be used
+    /// with a data source that is read-only. If a driver is read-only, all of its data sources
+    /// must be read-only.
+    pub fn is_read_only(&mut self) -> Result<bool> {
+        // The mutability on is_read_only is really an eyesore. Not only to clippy. But we would
+        // have to introduce a cell around `self.safe`, and be careful not to change essential
+        // state in the error path. For now the trouble does not seem worth it.
+        let ret = self.safe.is_read_only();
+        into_result_with(&self.safe, ret)
+    }
+
+    /// Closes the connection to the data source, by calling `SQLDisconnect`. If not called
+    /// explicitly the disconnect will be invoked implicitly by `drop()`.
+    ///
+    /// Returns the disconnected handle, which can be used to connect again. Unlike the implicit
+    /// disconnect, which panics if it fails, an error is reported, e.g. if a transaction is still
+    /// open. In that case the connection is handed back together with the error, still
+    /// connected, so the cause can be dealt with before disconnecting again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let disconnected = match conn.disconnect() {
+    ///     Ok(disconnected) => disconnected,
+    ///     Err((conn, error)) => {
+    ///         println!("{}", error);
+    ///         // ...
+    ///         conn.disconnect().map_err(|(_, error)| error)?
+    ///     }
+    /// };
+    /// let conn = disconnected.connect("TestDataSource", "", "")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn disconnect(
+        self,
+    ) -> std::result::Result<DisconnectedConnection<'env>, (Self, DiagnosticRecord)> {
+        match self.safe.disconnect() {
+            safe::Return::Error(safe) => {
+                let error = error_record(&safe);
+                Err((Connection { safe, active: self.active }, error))
+            }
+            ret => {
+                let safe = into_result(ret).expect("errors are handled above");
+                Ok(DisconnectedConnection { safe })
+            }
+        }
+    }
+
+    /// Closes the connection and opens it again using `connection_str`, reusing the connection
+    /// handle. See `disconnect` and `Environment::connect_with_connection_string`.
+    ///
+    /// Use it together with `is_dead` to recover from network failures. Like any new connection,
+    /// the returned one is in autocommit mode and all statements of the old connection must have
+    /// been dropped.
+    ///
+    /// If disconnecting fails the still connected connection is handed back together with the
+    /// error, as by `disconnect`. If connecting again fails there is no connection left and the
+    /// error comes with `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let mut conn = env.connect_with_connection_string("DSN=TestDataSource;")?;
+    /// // ...
+    /// if conn.is_dead()? {
+    ///     conn = match conn.reconnect("DSN=TestDataSource;") {
+    ///         Ok(conn) => conn,
+    ///         Err((Some(conn), error)) => {
+    ///             println!("Failed to disconnect: {}", error);
+    ///             conn
+    ///         }
+    ///         Err((None, error)) => return Err(error),
+    ///     };
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reconnect(
+        self,
+        connection_str: &str,
+    ) -> std::result::Result<Connection<'env, AutocommitOn>, (Option<Self>, DiagnosticRecord)> {
+        let disconnected = self
+            .disconnect()
+            .map_err(|(conn, error)| (Some(conn), error))?;
+        disconnected
+            .connect_with_connection_string(connection_str)
+            .map_err(|error| (None, error))
+    }
+}
+
+impl<'env> Handle for DisconnectedConnection<'env> {
+    type To = ffi::Dbc;
+    unsafe fn handle(&self) -> ffi::SQLHDBC {
+        self.safe.as_raw()
+    }
+}
+
+impl<'env> DisconnectedConnection<'env> {
+    /// Allocates a connection handle in `env` and sets `attrs` on it in the given order.
+    ///
+    /// Use it for attributes which must be set before connecting, like `ConnectAttr::PacketSize`
+    /// or `ConnectAttr::LoginTimeout`. See also `ConnectionBuilder`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let attrs = [ConnectAttr::PacketSize(8192), ConnectAttr::LoginTimeout(5)];
+    /// let conn = DisconnectedConnection::with_parent_and_attrs(&env, &attrs)?
+    ///     .connect("TestDataSource", "", "")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_parent_and_attrs<V: safe::Version>(
+        env: &'env Environment<V>,
+        attrs: &[ConnectAttr],
+    ) -> Result<Self> {
+        let safe = into_result_with(env, safe::DataSource::with_parent(env.as_safe()))?;
+        let mut disconnected = DisconnectedConnection { safe };
+        for attr in attrs {
+            attribute::set_attr(&mut disconnected, attr).into_result(&disconnected.safe)?;
+        }
+        Ok(disconnected)
+    }
+
+    /// Connects the handle to an ODBC data source. See `Environment::connect`.
+    pub fn connect(
+        self,
+        dsn: &str,
+        usr: &str,
+        pwd: &str,
+    ) -> Result<Connection<'env, AutocommitOn>> {
+        let safe = into_result(self.safe.connect(dsn, usr, pwd))?;
+        Ok(Connection { safe, active: ActiveConnection::new() })
+    }
+
+    /// Connects the handle to an ODBC data source, using a connection string. See
+    /// `Environment::connect_with_connection_string`.
+    pub fn connect_with_connection_string(
+        self,
+        connection_str: &str,
+    ) -> Result<Connection<'env, AutocommitOn>> {
+        #[cfg(feature = "log")]
+        debug!("connecting with {}", redact_password(connection_str));
+        let safe = into_result(self.safe.connect_with_connection_string(connection_str))?;
+        Ok(Connection { safe, active: ActiveConnection::new() })
+    }
+}
+
+/// `Connection` is `Send`, so it can be moved to and used from another thread, e.g. to open a
+/// connection and hand it to a worker, but it is not `Sync`.
+///
+/// ODBC allows moving a handle between threads, even for drivers which are not thread safe. What
+/// must be avoided is calling the same handle from several threads at the same time, which the
+/// borrow checker prevents, as long as the raw handle is not shared.
+unsafe impl<'env, AC: AutocommitMode> Send for Connection<'env, AC> {}
+
+/// `DisconnectedConnection` is `Send` for the same reasons as `Connection`
+unsafe impl<'env> Send for DisconnectedConnection<'env> {}
+
+unsafe impl<'env, AC: AutocommitMode> safe::Handle for Connection<'env, AC> {
+    const HANDLE_TYPE: ffi::HandleType = ffi::SQL_HANDLE_DBC;
+
+    fn handle(&self) -> ffi::SQLHANDLE {
+        self.safe.as_raw() as ffi::SQLHANDLE
+    }
+}
@@ -0,0 +1,55 @@
+//! Translation of SQL into the dialect of the data source, using `SQLNativeSql`
+use super::Connection;
+use odbc_safe::AutocommitMode;
+use result::with_growing_buffer;
+use {ffi, Handle, Result, Return};
+
+impl<'env, AC: AutocommitMode> Connection<'env, AC> {
+    /// Returns `sql` as the driver would send it to the data source. Wraps `SQLNativeSql`.
+    ///
+    /// Drivers translate ODBC escape sequences, like `{fn UCASE(...)}`, `{d '2024-01-01'}` or
+    /// `{call proc(?)}`, into the native SQL dialect of the data source. The statement is not
+    /// executed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// println!("{}", conn.native_sql("SELECT {fn UCASE(TITLE)} FROM MOVIES")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn native_sql(&self, sql: &str) -> Result<String> {
+        let encoding = unsafe { ::environment::DB_ENCODING };
+        let bytes = encoding.encode(sql).0;
+        let ret = native_sql(self, &bytes).map(|native| encoding.decode(&native).0.into_owned());
+        ret.into_result(self)
+    }
+}
+
+pub(crate) fn native_sql<C: Handle<To = ffi::Dbc>>(conn: &C, sql: &[u8]) -> Return<Vec<u8>> {
+    // Translated statements are usually about as long as the original one
+    with_growing_buffer(sql.len() + 256, |buffer| {
+        let mut length: ffi::SQLINTEGER = 0;
+        let ret = match unsafe {
+            ffi::SQLNativeSql(
+                conn.handle(),
+                sql.as_ptr(),
+                sql.len() as ffi::SQLINTEGER,
+                buffer.as_mut_ptr(),
+                buffer.len() as ffi::SQLINTEGER,
+                &mut length,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLNativeSql returned unexpected result: {:?}", r),
+        };
+        (ret, length.max(0) as usize)
+    })
+    .map(|((), text)| text)
+}
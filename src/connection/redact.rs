@@ -0,0 +1,81 @@
+//! Removing secrets from connection strings, before they are logged
+
+/// Replaces the values of the `PWD` and `PASSWORD` attributes of a connection string with `***`.
+/// Keys are matched case insensitively, values enclosed in braces may contain `;`.
+pub(crate) fn redact_password(connection_str: &str) -> String {
+    let mut redacted = String::with_capacity(connection_str.len());
+    let mut rest = connection_str;
+    while let Some(eq) = rest.find('=') {
+        // An attribute without value, e.g. an empty one between two `;`, is copied as is
+        if let Some(semicolon) = rest[..eq].find(';') {
+            redacted.push_str(&rest[..=semicolon]);
+            rest = &rest[semicolon + 1..];
+            continue;
+        }
+        let (key, value) = (&rest[..eq], &rest[eq + 1..]);
+        let len = value_len(value);
+        redacted.push_str(key);
+        redacted.push('=');
+        let key = key.trim();
+        if key.eq_ignore_ascii_case("PWD") || key.eq_ignore_ascii_case("PASSWORD") {
+            redacted.push_str("***");
+        } else {
+            redacted.push_str(&value[..len]);
+        }
+        rest = &value[len..];
+        if rest.starts_with(';') {
+            redacted.push(';');
+            rest = &rest[1..];
+        }
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Length of the attribute value at the start of `value`, up to the next `;` outside of braces
+fn value_len(value: &str) -> usize {
+    let mut end = 0;
+    if value.trim_start().starts_with('{') {
+        let start = value.find('{').unwrap() + 1;
+        // Unterminated braces extend to the end of the string
+        end = value.len();
+        let mut chars = value[start..].char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '}' {
+                // `}}` is an escaped brace within the value
+                if chars.peek().map(|&(_, c)| c) == Some('}') {
+                    chars.next();
+                } else {
+                    end = start + i + 1;
+                    break;
+                }
+            }
+        }
+    }
+    end + value[end..].find(';').unwrap_or(value.len() - end)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn redacts_password() {
+        assert_eq!(
+            redact_password("DSN=TestDataSource;UID=user;PWD=secret;"),
+            "DSN=TestDataSource;UID=user;PWD=***;"
+        );
+        assert_eq!(redact_password("Driver={SQLite3};password=secret"), "Driver={SQLite3};password=***");
+    }
+
+    #[test]
+    fn redacts_password_in_braces() {
+        assert_eq!(redact_password("pwd={a;b}}c};UID=user"), "pwd=***;UID=user");
+    }
+
+    #[test]
+    fn keeps_connection_string_without_password() {
+        let connection_str = "DSN=TestDataSource;;Database={C:\\data;1}";
+        assert_eq!(redact_password(connection_str), connection_str);
+    }
+}
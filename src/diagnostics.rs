@@ -1,4 +1,5 @@
 use super::{ffi, safe};
+use std::cell::RefCell;
 use std::{fmt, cmp};
 use std::ffi::CStr;
 use std::error::Error;
@@ -9,6 +10,12 @@ pub const MAX_DIAGNOSTIC_MESSAGE_SIZE: usize = 1024;
 ///
 /// The `description` method of the `std::error::Error` trait only returns the message. Use
 /// `std::fmt::Display` to retrieve status code and other information.
+///
+/// `DiagnosticRecord` is `Send`, `Sync` and `'static`, so it can be wrapped by error handling
+/// libraries or boxed as `Box<dyn Error + Send + Sync>`. The record is reported by the driver or
+/// driver manager itself, so there is no underlying error and `source` returns `None`. Further
+/// records reported by the same function call are available via `get_additional_records`.
+#[derive(Clone)]
 pub struct DiagnosticRecord {
     // All elements but the last one, may not be null. The last one must be null.
     state: [ffi::SQLCHAR; ffi::SQL_SQLSTATE_SIZE + 1],
@@ -18,6 +25,10 @@ pub struct DiagnosticRecord {
     message_length: ffi::SQLSMALLINT,
     native_error: ffi::SQLINTEGER,
     message_string: String,
+    // Records reported after this one by the same function call
+    additional_records: Vec<DiagnosticRecord>,
+    // Created for a function returning `SQL_NEED_DATA`, see `is_need_data`
+    need_data: bool,
 }
 
 impl DiagnosticRecord {
@@ -33,20 +44,64 @@ impl DiagnosticRecord {
     pub fn get_native_error(&self) -> i32 {
         self.native_error
     }
+    /// get the diagnostic records reported in addition to this one. Drivers may report more than
+    /// one record for a failing function call, e.g. warnings alongside the actual error.
+    pub fn get_additional_records(&self) -> &[DiagnosticRecord] {
+        &self.additional_records
+    }
+    /// `true` if the record reports a function or feature not supported by the driver, i.e. its
+    /// state is `HYC00` (optional feature not implemented) or `IM001` (driver does not support
+    /// this function).
+    pub fn is_unsupported(&self) -> bool {
+        let state = &self.state[..ffi::SQL_SQLSTATE_SIZE];
+        state == b"HYC00" || state == b"IM001"
+    }
+    /// `true` if the record has been created for a function returning `SQL_NEED_DATA`, rather
+    /// than reported by the driver. This happens if a statement with parameters bound by
+    /// `bind_parameter_at_exec` is executed with `exec_direct` or `execute`. Use
+    /// `exec_direct_at_exec` or `execute_at_exec` instead, in order to send the data.
+    pub fn is_need_data(&self) -> bool {
+        self.need_data
+    }
     /// constructs an empty diagnostics message.
     /// this is needed for errors where the driver doesn't return any diagnostics info.
     pub fn empty() -> DiagnosticRecord {
-        let message = b"No SQL-driver error information available.";
+        DiagnosticRecord::with_message(b"HY000", "No SQL-driver error information available.")
+    }
+}
+
+impl DiagnosticRecord {
+    /// Record for errors detected by this crate rather than reported by the driver
+    pub(crate) fn with_message(state: &[u8; 5], message: &str) -> DiagnosticRecord {
+        // Keep the terminating zero
+        let length = cmp::min(message.len(), MAX_DIAGNOSTIC_MESSAGE_SIZE - 1);
         let mut rec = DiagnosticRecord {
-            state: b"HY000\0".clone(),
+            state: [0u8; ffi::SQL_SQLSTATE_SIZE + 1],
             message: [0u8; MAX_DIAGNOSTIC_MESSAGE_SIZE],
             native_error: -1,
-            message_length: message.len() as ffi::SQLSMALLINT,
-            message_string: String::from(""),
+            message_length: length as ffi::SQLSMALLINT,
+            message_string: message.to_owned(),
+            additional_records: Vec::new(),
+            need_data: false,
         };
-        rec.message[..message.len()].copy_from_slice(message);
+        rec.state[..ffi::SQL_SQLSTATE_SIZE].copy_from_slice(state);
+        rec.message[..length].copy_from_slice(&message.as_bytes()[..length]);
         rec
     }
+
+    /// Record for functions returning `SQL_NEED_DATA`, where no data at execution is expected
+    pub(crate) fn need_data() -> DiagnosticRecord {
+        let mut rec = DiagnosticRecord::with_message(
+            b"HY000",
+            "The statement needs data for parameters bound at execution time.",
+        );
+        rec.need_data = true;
+        rec
+    }
+
+    pub(crate) fn push_additional_record(&mut self, record: DiagnosticRecord) {
+        self.additional_records.push(record);
+    }
 }
 
 impl fmt::Display for DiagnosticRecord {
@@ -74,11 +129,63 @@ impl Error for DiagnosticRecord {
     fn description(&self) -> &str {
         &self.message_string
     }
-    fn cause(&self) -> Option<& dyn Error> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
     }
 }
 
+/// Collects the diagnostic records of function calls which succeeded with
+/// `SQL_SUCCESS_WITH_INFO`, e.g. to report truncated strings or changed option values.
+///
+/// These records are logged as warnings, but otherwise lost with the next function call on the
+/// same handle. Every `Statement` holds a context, see `Statement::take_warnings`. Records are
+/// kept until taken, so take them regularly if a statement is used for many calls.
+#[derive(Debug, Default)]
+pub struct DiagnosticContext {
+    warnings: RefCell<Vec<DiagnosticRecord>>,
+}
+
+impl DiagnosticContext {
+    /// A context without any warnings
+    pub fn new() -> DiagnosticContext {
+        DiagnosticContext::default()
+    }
+
+    /// Adds a warning to the context
+    pub fn push_warning(&self, record: DiagnosticRecord) {
+        self.warnings.borrow_mut().push(record);
+    }
+
+    /// Returns the warnings collected so far, in the order they have been reported, and removes
+    /// them from the context
+    pub fn take_warnings(&self) -> Vec<DiagnosticRecord> {
+        self.warnings.replace(Vec::new())
+    }
+}
+
+/// Diagnostics of a handle, which add the warnings reported by its function calls to a
+/// `DiagnosticContext`. Passed to `Return::into_result` in place of the handle.
+pub(crate) struct CollectWarnings<'d, D: 'd> {
+    handle: &'d D,
+    context: &'d DiagnosticContext,
+}
+
+impl<'d, D: GetDiagRec> CollectWarnings<'d, D> {
+    pub(crate) fn new(handle: &'d D, context: &'d DiagnosticContext) -> Self {
+        CollectWarnings { handle, context }
+    }
+}
+
+impl<'d, D: GetDiagRec> GetDiagRec for CollectWarnings<'d, D> {
+    fn get_diag_rec(&self, record_number: i16) -> Option<DiagnosticRecord> {
+        self.handle.get_diag_rec(record_number)
+    }
+
+    fn record_warning(&self, record: DiagnosticRecord) {
+        self.context.push_warning(record);
+    }
+}
+
 /// Allows retrieving a diagnostic record, describing errors (or lack thereof) during the last
 /// operation.
 pub trait GetDiagRec {
@@ -87,6 +194,21 @@ pub trait GetDiagRec {
     /// `record_number` - Record numbers start at one. If you pass an number < 1 the function will
     /// panic. If no record is available for the number specified none is returned.
     fn get_diag_rec(&self, record_number: i16) -> Option<DiagnosticRecord>;
+
+    /// Retrieves all diagnostic records available, starting with record number one
+    fn get_diag_recs(&self) -> Vec<DiagnosticRecord> {
+        let mut records = Vec::new();
+        let mut i = 1;
+        while let Some(rec) = self.get_diag_rec(i) {
+            records.push(rec);
+            i += 1;
+        }
+        records
+    }
+
+    /// Called with each record of a function call which succeeded with `SQL_SUCCESS_WITH_INFO`,
+    /// after it has been logged. Does nothing by default.
+    fn record_warning(&self, _record: DiagnosticRecord) {}
 }
 
 impl<D> GetDiagRec for D
@@ -112,6 +234,8 @@ where
                     message_string: unsafe {
                         ::environment::OS_ENCODING.decode(&message[0..message_length as usize]).0.into_owned()
                     },
+                    additional_records: Vec::new(),
+                    need_data: false,
                 })
             }
             NoData(()) => None,
@@ -133,10 +257,94 @@ mod test {
                 native_error: 0,
                 message_length: 0,
                 message_string: String::from(""),
+                additional_records: Vec::new(),
+                need_data: false,
+            }
+        }
+    }
+
+    struct ThreeRecords;
+
+    impl GetDiagRec for ThreeRecords {
+        fn get_diag_rec(&self, record_number: i16) -> Option<DiagnosticRecord> {
+            if record_number > 3 {
+                return None;
             }
+            let mut rec = DiagnosticRecord::new();
+            rec.native_error = record_number as ffi::SQLINTEGER;
+            Some(rec)
         }
     }
 
+    #[test]
+    fn all_records() {
+        let native_errors: Vec<_> = ThreeRecords
+            .get_diag_recs()
+            .iter()
+            .map(DiagnosticRecord::get_native_error)
+            .collect();
+        assert_eq!(native_errors, [1, 2, 3]);
+    }
+
+    #[test]
+    fn additional_records_of_error() {
+        let rec = ::result::error_record(&ThreeRecords);
+        assert_eq!(rec.get_native_error(), 1);
+        let additional: Vec<_> = rec
+            .get_additional_records()
+            .iter()
+            .map(DiagnosticRecord::get_native_error)
+            .collect();
+        assert_eq!(additional, [2, 3]);
+    }
+
+    #[test]
+    fn warnings_of_success_with_info() {
+        let context = DiagnosticContext::new();
+        ::result::Return::SuccessWithInfo(())
+            .into_result(&CollectWarnings::new(&ThreeRecords, &context))
+            .unwrap();
+        let native_errors: Vec<_> = context
+            .take_warnings()
+            .iter()
+            .map(DiagnosticRecord::get_native_error)
+            .collect();
+        assert_eq!(native_errors, [1, 2, 3]);
+        assert!(context.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn unsupported_record() {
+        let mut rec = DiagnosticRecord::new();
+        assert!(!rec.is_unsupported());
+        rec.state = *b"HYC00\0";
+        assert!(rec.is_unsupported());
+        rec.state = *b"IM001\0";
+        assert!(rec.is_unsupported());
+        assert!(!DiagnosticRecord::empty().is_unsupported());
+    }
+
+    #[test]
+    fn need_data_record() {
+        assert!(DiagnosticRecord::need_data().is_need_data());
+        assert!(!DiagnosticRecord::empty().is_need_data());
+    }
+
+    #[test]
+    fn empty_record() {
+        assert_eq!(
+            format!("{}", DiagnosticRecord::empty()),
+            "State: HY000, Native error: -1, Message: No SQL-driver error information available."
+        );
+    }
+
+    #[test]
+    fn box_as_error() {
+        let error: Box<dyn Error + Send + Sync> = Box::new(DiagnosticRecord::empty());
+        assert!(error.source().is_none());
+        assert!(error.to_string().starts_with("State: HY000"));
+    }
+
     #[test]
     fn formatting() {
 
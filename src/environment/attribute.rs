@@ -0,0 +1,140 @@
+//! Environment attributes, set with `SQLSetEnvAttr`
+use super::{Environment, OUTPUT_NTS};
+use odbc_safe as safe;
+use std::fmt;
+use std::ptr::null_mut;
+use std::sync::atomic::Ordering;
+use {ffi, DiagnosticRecord, Handle, Result, Return};
+
+/// Value of the `SQL_ATTR_CONNECTION_POOLING` environment attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionPooling {
+    /// Connection pooling is turned off (`SQL_CP_OFF`)
+    Off,
+    /// One pool of connections for each driver (`SQL_CP_ONE_PER_DRIVER`)
+    OnePerDriver,
+    /// One pool of connections for each environment (`SQL_CP_ONE_PER_HENV`)
+    OnePerHenv,
+}
+
+impl fmt::Display for ConnectionPooling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ConnectionPooling::Off => "SQL_CP_OFF",
+            ConnectionPooling::OnePerDriver => "SQL_CP_ONE_PER_DRIVER",
+            ConnectionPooling::OnePerHenv => "SQL_CP_ONE_PER_HENV",
+        })
+    }
+}
+
+/// Value of the `SQL_ATTR_CP_MATCH` environment attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CpMatch {
+    /// Only connections exactly matching the connection options and attributes are reused
+    /// (`SQL_CP_STRICT_MATCH`)
+    Strict,
+    /// Connections with matching connection string keywords are reused, even if other attributes
+    /// differ (`SQL_CP_RELAXED_MATCH`)
+    Relaxed,
+}
+
+impl fmt::Display for CpMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CpMatch::Strict => "SQL_CP_STRICT_MATCH",
+            CpMatch::Relaxed => "SQL_CP_RELAXED_MATCH",
+        })
+    }
+}
+
+/// Enables or disables connection pooling by the driver manager for the whole process. Wraps
+/// `SQLSetEnvAttr` with `SQL_ATTR_CONNECTION_POOLING` and a null environment handle.
+///
+/// Only environments allocated afterwards pool their connections, so this must be called before
+/// `create_environment_v3` (or any other function creating an `Environment`). Unlike `Pool`,
+/// pooling by the driver manager is transparent to the application: disconnected connections are
+/// returned to the driver manager's pool and reused by later calls to `connect`.
+///
+/// Without an environment the driver manager can not provide diagnostics, so a failure is
+/// reported by a record with SQLSTATE `HY000` and a generic message.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// set_connection_pooling(ConnectionPooling::OnePerHenv)?;
+/// let env = create_environment_v3()
+///     .map_err(|e| e.unwrap())?
+///     .set_cp_match(CpMatch::Relaxed)?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_connection_pooling(mode: ConnectionPooling) -> Result<()> {
+    let value = match mode {
+        ConnectionPooling::Off => ffi::SQL_CP_OFF,
+        ConnectionPooling::OnePerDriver => ffi::SQL_CP_ONE_PER_DRIVER,
+        ConnectionPooling::OnePerHenv => ffi::SQL_CP_ONE_PER_HENV,
+    };
+    let ret = unsafe {
+        ffi::SQLSetEnvAttr(
+            null_mut(),
+            ffi::SQL_ATTR_CONNECTION_POOLING,
+            value as usize as ffi::SQLPOINTER,
+            0,
+        )
+    };
+    match ret {
+        ffi::SQL_SUCCESS | ffi::SQL_SUCCESS_WITH_INFO => Ok(()),
+        ffi::SQL_ERROR => Err(DiagnosticRecord::with_message(
+            b"HY000",
+            &format!("SQLSetEnvAttr failed to set SQL_ATTR_CONNECTION_POOLING to {}", mode),
+        )),
+        r => panic!("SQLSetEnvAttr returned unexpected result: {:?}", r),
+    }
+}
+
+impl<V: safe::Version> Environment<V> {
+    /// Determines how a connection is chosen from the driver manager's pool. Wraps
+    /// `SQLSetEnvAttr` with `SQL_ATTR_CP_MATCH`. See `set_connection_pooling`.
+    pub fn set_cp_match(self, mode: CpMatch) -> Result<Self> {
+        let value = match mode {
+            CpMatch::Strict => ffi::SQL_CP_STRICT_MATCH,
+            CpMatch::Relaxed => ffi::SQL_CP_RELAXED_MATCH,
+        };
+        set_attr(&self, ffi::SQL_ATTR_CP_MATCH, value).into_result(&self)?;
+        Ok(self)
+    }
+
+    /// Determines whether the driver null-terminates string data it returns. Wraps
+    /// `SQLSetEnvAttr` with `SQL_ATTR_OUTPUT_NTS`, which is `true` by default.
+    ///
+    /// Without null termination, the length of the data is taken from the indicator alone, which
+    /// `Cursor::get_data` accounts for when it retrieves a truncated string in several parts.
+    /// Like the database encoding, the setting is tracked for the whole process, so it should be
+    /// the same for all environments.
+    ///
+    /// Many driver managers only support `true`, in which case an error is returned.
+    pub fn set_output_nts(self, nts: bool) -> Result<Self> {
+        let value = if nts { ffi::SQL_TRUE } else { ffi::SQL_FALSE };
+        set_attr(&self, ffi::SQL_ATTR_OUTPUT_NTS, value).into_result(&self)?;
+        OUTPUT_NTS.store(nts, Ordering::SeqCst);
+        Ok(self)
+    }
+}
+
+fn set_attr<E: Handle<To = ffi::Env>>(
+    env: &E,
+    attribute: ffi::EnvironmentAttribute,
+    value: ffi::SQLUINTEGER,
+) -> Return<()> {
+    match unsafe {
+        ffi::SQLSetEnvAttr(env.handle(), attribute, value as usize as ffi::SQLPOINTER, 0)
+    } {
+        ffi::SQL_SUCCESS => Return::Success(()),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+        ffi::SQL_ERROR => Return::Error,
+        r => panic!("SQLSetEnvAttr returned unexpected result: {:?}", r),
+    }
+}
@@ -1,7 +1,8 @@
-use super::{safe, try_into_option, Environment, DiagnosticRecord, GetDiagRec, Result, Version3};
+use super::{safe, try_into_option, Environment, Result, Version3};
+use result::error_record;
 use ffi;
 use std::collections::HashMap;
-use std::cmp::max;
+use std::cmp::{max, min};
 
 /// Holds name and description of a datasource
 ///
@@ -25,30 +26,103 @@ pub struct DriverInfo {
     pub attributes: HashMap<String, String>,
 }
 
-type SqlInfoMethod = fn(&mut safe::Environment<safe::Odbc3>,
+/// Selects which data sources are listed by `Environment::data_source_iter`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DataSourceDirection {
+    /// User data sources only, like `Environment::user_data_sources`
+    User,
+    /// System data sources only, like `Environment::system_data_sources`
+    System,
+    /// Both user and system data sources, like `Environment::data_sources`
+    All,
+}
+
+impl DataSourceDirection {
+    fn fetch_first(self) -> ffi::FetchOrientation {
+        match self {
+            DataSourceDirection::User => ffi::SQL_FETCH_FIRST_USER,
+            DataSourceDirection::System => ffi::SQL_FETCH_FIRST_SYSTEM,
+            DataSourceDirection::All => ffi::SQL_FETCH_FIRST,
+        }
+    }
+}
+
+/// Iterates lazily over the data sources known to the driver manager
+///
+/// Can be obtained via `Environment::data_source_iter`. Each call to `next` calls
+/// `SQLDataSources` once. Unlike `Environment::data_sources` there is no prior pass to determine
+/// the required buffer sizes, so descriptions longer than 1024 bytes are truncated. After an
+/// error the iterator is exhausted.
+pub struct DataSourceIterator<'env, V: 'env = Version3> {
+    env: &'env mut Environment<V>,
+    direction: ffi::FetchOrientation,
+    name_buffer: Vec<u8>,
+    description_buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<'env, V: safe::Version> Iterator for DataSourceIterator<'env, V> {
+    type Item = Result<DataSourceInfo>;
+
+    fn next(&mut self) -> Option<Result<DataSourceInfo>> {
+        if self.done {
+            return None;
+        }
+        let direction = self.direction;
+        // Only the first call uses one of the `SQL_FETCH_FIRST` variants
+        self.direction = ffi::SQL_FETCH_NEXT;
+        match self.env.get_info(
+            safe::Environment::data_sources,
+            direction,
+            &mut self.name_buffer,
+            &mut self.description_buffer,
+        ) {
+            Ok(Some((name, desc))) => Some(Ok(DataSourceInfo {
+                server_name: name.into_owned(),
+                driver: desc.into_owned(),
+            })),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+type SqlInfoMethod<V> = fn(&mut safe::Environment<V>,
                         ffi::FetchOrientation,
                         &mut [u8],
                         &mut [u8])
                         -> safe::ReturnOption<(i16, i16)>;
 
-impl Environment<Version3> {
+impl<V: safe::Version> Environment<V> {
     /// Called by drivers to pares list of attributes
     ///
-    /// Key value pairs are separated by `\0`. Key and value are separated by `=`
+    /// Key value pairs are separated by `\0`. Key and value are separated by the first `=`, so
+    /// values may contain `=` themselves. A pair without `=` is treated as a key with an empty
+    /// value.
     fn parse_attributes(attributes: &str) -> HashMap<String, String> {
         attributes
             .split('\0')
-            .take_while(|kv_str| *kv_str != String::new())
+            .take_while(|kv_str| !kv_str.is_empty())
             .map(|kv_str| {
-                let mut iter = kv_str.split('=');
+                let mut iter = kv_str.splitn(2, '=');
                 let key = iter.next().unwrap();
-                let value = iter.next().unwrap();
+                let value = iter.next().unwrap_or("");
                 (key.to_string(), value.to_string())
             })
             .collect()
     }
 
-    /// Stores all driver description and attributes in a Vec
+    /// Stores all driver descriptions and attributes in a Vec
+    ///
+    /// Calls `SQLDrivers` with `SQL_FETCH_FIRST` and then `SQL_FETCH_NEXT` until there are no more
+    /// drivers. The attributes are parsed from the `key=value` pairs reported by the driver
+    /// manager, e.g. `Driver` or `Setup`.
     pub fn drivers(&mut self) -> Result<Vec<DriverInfo>> {
         // Iterate twice, once for reading the maximum required buffer lengths so we can read
         // everything without truncating and a second time for actually storing the values
@@ -96,6 +170,32 @@ impl Environment<Version3> {
         self.data_sources_impl(ffi::SQL_FETCH_FIRST_USER)
     }
 
+    /// Returns an iterator over the user, system or all data sources, which calls
+    /// `SQLDataSources` lazily.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let mut env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// for source in env.data_source_iter(DataSourceDirection::All) {
+    ///     println!("{}", source?.server_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn data_source_iter(&mut self, direction: DataSourceDirection) -> DataSourceIterator<'_, V> {
+        DataSourceIterator {
+            env: self,
+            direction: direction.fetch_first(),
+            // Data source names are at most `SQL_MAX_DSN_LENGTH` (32) characters long
+            name_buffer: vec![0; 256],
+            description_buffer: vec![0; 1024],
+            done: false,
+        }
+    }
+
     /// Use SQL_FETCH_FIRST, SQL_FETCH_FIRST_USER or SQL_FETCH_FIRST_SYSTEM, to get all, user or
     /// system data sources
     fn data_sources_impl(
@@ -154,7 +254,7 @@ impl Environment<Version3> {
     /// into a `(&str,&str)`
     fn get_info<'a, 'b>(
         &mut self,
-        f: SqlInfoMethod,
+        f: SqlInfoMethod<V>,
         direction: ffi::FetchOrientation,
         buf1: &'a mut [u8],
         buf2: &'b mut [u8],
@@ -162,9 +262,13 @@ impl Environment<Version3> {
         let result = f(&mut self.safe, direction, buf1, buf2);
         match try_into_option(result, self)? {
             Some((len1, len2)) => unsafe {
+                // In case of truncation the lengths exceed the buffers, which also hold the
+                // terminating zero
+                let len1 = min(len1 as usize, buf1.len().saturating_sub(1));
+                let len2 = min(len2 as usize, buf2.len().saturating_sub(1));
                 Ok(Some((
-                    ::environment::DB_ENCODING.decode(&buf1[0..(len1 as usize)]).0,
-                    ::environment::DB_ENCODING.decode(&buf2[0..(len2 as usize)]).0,
+                    ::environment::DB_ENCODING.decode(&buf1[0..len1]).0,
+                    ::environment::DB_ENCODING.decode(&buf2[0..len2]).0,
                 )))
             }
             None => Ok(None),
@@ -174,7 +278,7 @@ impl Environment<Version3> {
     /// Finds the maximum size required for description buffers
     fn alloc_info(
         &mut self,
-        f: SqlInfoMethod,
+        f: SqlInfoMethod<V>,
         direction: ffi::FetchOrientation,
     ) -> Result<(ffi::SQLSMALLINT, ffi::SQLSMALLINT, usize)> {
         // In theory, we should use zero-length buffers here
@@ -205,11 +309,7 @@ impl Environment<Version3> {
                     max2 = max(max2, buf2_length_out);
                 }
                 safe::ReturnOption::NoData(()) => break,
-                safe::ReturnOption::Error(()) => {
-                    let diag = self.get_diag_rec(1).unwrap_or_else(DiagnosticRecord::empty);
-                    error!("{}", diag);
-                    return Err(diag);
-                }
+                safe::ReturnOption::Error(()) => return Err(error_record(self)),
             }
 
             result = f(
@@ -233,7 +333,7 @@ mod test {
     fn parse_attributes() {
         let buffer = "APILevel=2\0ConnectFunctions=YYY\0CPTimeout=60\0DriverODBCVer=03.\
                       50\0FileUsage=0\0SQLLevel=1\0UsageCount=1\0\0";
-        let attributes = Environment::parse_attributes(buffer);
+        let attributes = Environment::<Version3>::parse_attributes(buffer);
         assert_eq!(attributes["APILevel"], "2");
         assert_eq!(attributes["ConnectFunctions"], "YYY");
         assert_eq!(attributes["CPTimeout"], "60");
@@ -242,4 +342,14 @@ mod test {
         assert_eq!(attributes["SQLLevel"], "1");
         assert_eq!(attributes["UsageCount"], "1");
     }
+
+    #[test]
+    fn parse_attributes_with_equals_sign_in_value() {
+        let buffer = "Driver=/usr/lib/libsqlite3odbc.so\0Options=a=b;c=d\0NoValue\0\0";
+        let attributes = Environment::<Version3>::parse_attributes(buffer);
+        assert_eq!(attributes["Driver"], "/usr/lib/libsqlite3odbc.so");
+        assert_eq!(attributes["Options"], "a=b;c=d");
+        assert_eq!(attributes["NoValue"], "");
+        assert_eq!(attributes.len(), 3);
+    }
 }
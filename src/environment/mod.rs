@@ -1,19 +1,42 @@
 //! Implements the ODBC Environment
+mod attribute;
 mod list_data_sources;
-pub use self::list_data_sources::{DataSourceInfo, DriverInfo};
+mod transaction;
+pub use self::attribute::{set_connection_pooling, ConnectionPooling, CpMatch};
+pub use self::list_data_sources::{DataSourceDirection, DataSourceInfo, DataSourceIterator,
+                                   DriverInfo};
 use super::{ffi, into_result, safe, try_into_option, DiagnosticRecord, GetDiagRec, Handle, Result};
 use std;
+use std::sync::atomic::AtomicBool;
 
 /// Environment state used to represent that environment has been set to odbc version 3
 pub type Version3 = safe::Odbc3;
 
+/// Environment state used to represent that environment has been set to odbc version 3.8
+///
+/// Some drivers only enable features introduced with ODBC 3.8, like asynchronous notification
+/// or connection resiliency, if the application declares this version.
+pub type Version3m8 = safe::Odbc3m8;
+
 pub static mut OS_ENCODING: &encoding_rs::Encoding = encoding_rs::UTF_8;
 pub static mut DB_ENCODING: &encoding_rs::Encoding = encoding_rs::UTF_8;
 
+/// Whether drivers null-terminate output strings, see `Environment::set_output_nts`
+pub(crate) static OUTPUT_NTS: AtomicBool = AtomicBool::new(true);
+
 /// Handle to an ODBC Environment
 ///
 /// Creating an instance of this type is the first thing you do then using ODBC. The environment
-/// must outlive all connections created with it.
+/// must outlive all connections created with it. Connections borrow the environment, so the
+/// compiler rejects code freeing the environment handle first:
+///
+/// ```compile_fail
+/// # use odbc::*;
+/// let env = create_environment_v3().unwrap();
+/// let conn = env.connect("TestDataSource", "", "").unwrap();
+/// drop(env);
+/// drop(conn);
+/// ```
 #[derive(Debug)]
 pub struct Environment<V> {
     safe: safe::Environment<V>,
@@ -47,7 +70,6 @@ impl<V: safe::Version> Environment<V> {
     /// environment, at least its allocation has to be successful to obtain one. If the allocation
     /// fails it is sadly not possible to receive further Diagnostics. Setting an unsupported version
     /// may however result in an ordinary `Some(DiagnosticRecord)`.
-    /// ```
     pub fn new() -> std::result::Result<Environment<V>, Option<DiagnosticRecord>> {
         let safe = match safe::Environment::new() {
             safe::Success(v) => v,
@@ -66,6 +88,45 @@ impl<V: safe::Version> Environment<V> {
     }
 }
 
+impl Environment<Version3> {
+    /// Creates an ODBC Environment and declares specification of version 3.0 are used. Same as
+    /// `create_environment_v3()`, but does not require the type to be annotated like `new()`.
+    ///
+    /// Only versions 3.0 and 3.8 are supported (by `odbc-safe`), so there is no constructor for
+    /// version 2.0 environments. See `new()` for the error type.
+    ///
+    /// # Example
+    /// ```
+    /// use odbc::*;
+    /// fn do_database_stuff() -> std::result::Result<(), Option<DiagnosticRecord>> {
+    ///     let env = Environment::new_v3()?; // first thing to do
+    ///     // ...
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new_v3() -> std::result::Result<Environment<Version3>, Option<DiagnosticRecord>> {
+        Environment::new()
+    }
+}
+
+impl Environment<Version3m8> {
+    /// Creates an ODBC Environment and declares specification of version 3.8 are used. Same as
+    /// `create_environment_v3_8()`. See `new()` for the error type.
+    ///
+    /// The driver manager maps the behaviour for drivers, which only support version 3.0.
+    pub fn new_v3_8() -> std::result::Result<Environment<Version3m8>, Option<DiagnosticRecord>> {
+        Environment::new()
+    }
+}
+
+/// `Environment` is `Send` and `Sync`, so connections can be opened from several threads at once,
+/// e.g. by a `Pool`.
+///
+/// The driver manager synchronizes calls on environment handles. Methods which change state of
+/// the environment, like iterating over data sources, require `&mut self`.
+unsafe impl<V> Send for Environment<V> {}
+unsafe impl<V> Sync for Environment<V> {}
+
 unsafe impl<V> safe::Handle for Environment<V> {
     const HANDLE_TYPE : ffi::HandleType = ffi::SQL_HANDLE_ENV;
 
@@ -100,6 +161,26 @@ pub fn create_environment_v3()
 }
 
 
+/// Creates an ODBC Environment and declares specification of version 3.8 are used
+///
+/// # Example
+/// ```
+/// use odbc::*;
+/// fn do_database_stuff() -> std::result::Result<(), Option<DiagnosticRecord>> {
+///     let env = create_environment_v3_8()?; // first thing to do
+///     let conn = env.connect("TestDataSource", "", "").map_err(Some)?;
+///     // ...
+///     Ok(())
+/// }
+/// ```
+///
+/// See `create_environment_v3()` for the error type.
+pub fn create_environment_v3_8()
+    -> std::result::Result<Environment<Version3m8>, Option<DiagnosticRecord>>
+{
+    Environment::new()
+}
+
 pub fn create_environment_v3_with_os_db_encoding(os_encoding: &str, db_encoding: &str)
     -> std::result::Result<Environment<Version3>, Option<DiagnosticRecord>>
 {
@@ -0,0 +1,46 @@
+//! Ending the transactions of all connections of an environment at once, using `SQLEndTran`
+use super::Environment;
+use odbc_safe as safe;
+use {ffi, Handle, Result, Return};
+
+impl<V: safe::Version> Environment<V> {
+    /// Commits the open transactions of all connections allocated on this environment. Calls
+    /// `SQLEndTran` with the environment handle and `SQL_COMMIT`.
+    ///
+    /// Connections in autocommit mode are not affected. The driver manager commits each
+    /// connection in turn, so this is no two-phase commit: If committing one of the transactions
+    /// fails, others may already have been committed. An error is returned in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let movies = env.connect("TestDataSource", "", "")?.disable_autocommit().ok().unwrap();
+    /// let archive = env.connect("TestDataSource", "", "")?.disable_autocommit().ok().unwrap();
+    /// Statement::with_parent(&movies)?.exec_direct("DELETE FROM MOVIES WHERE YEAR < 1970")?;
+    /// Statement::with_parent(&archive)?.exec_direct("DELETE FROM MOVIES WHERE YEAR > 1990")?;
+    /// env.commit_all()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commit_all(&self) -> Result<()> {
+        end_tran(self, ffi::SQL_COMMIT).into_result(self)
+    }
+
+    /// Rolls back the open transactions of all connections allocated on this environment. Calls
+    /// `SQLEndTran` with the environment handle and `SQL_ROLLBACK`. See `commit_all`.
+    pub fn rollback_all(&self) -> Result<()> {
+        end_tran(self, ffi::SQL_ROLLBACK).into_result(self)
+    }
+}
+
+fn end_tran<E: Handle<To = ffi::Env>>(env: &E, completion: ffi::SqlCompletionType) -> Return<()> {
+    match unsafe { ffi::SQLEndTran(ffi::SQL_HANDLE_ENV, env.handle() as ffi::SQLHANDLE, completion) } {
+        ffi::SQL_SUCCESS => Return::Success(()),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+        ffi::SQL_ERROR => Return::Error,
+        r => panic!("SQLEndTran returned unexpected result: {:?}", r),
+    }
+}
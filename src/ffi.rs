@@ -1,3 +1,396 @@
 //! Reexport odbc-sys as ffi
 extern crate odbc_sys;
 pub use self::odbc_sys::*;
+
+// Constants not declared by odbc-sys (yet)
+pub const SQL_INDEX_UNIQUE: SQLUSMALLINT = 0;
+pub const SQL_INDEX_ALL: SQLUSMALLINT = 1;
+pub const SQL_QUICK: SQLUSMALLINT = 0;
+pub const SQL_ENSURE: SQLUSMALLINT = 1;
+pub const SQL_BEST_ROWID: SQLUSMALLINT = 1;
+pub const SQL_ROWVER: SQLUSMALLINT = 2;
+pub const SQL_SCOPE_CURROW: SQLUSMALLINT = 0;
+pub const SQL_SCOPE_TRANSACTION: SQLUSMALLINT = 1;
+pub const SQL_SCOPE_SESSION: SQLUSMALLINT = 2;
+
+// Statement attributes missing in `SqlStatementAttribute`, or needed as plain integers
+pub const SQL_ATTR_QUERY_TIMEOUT: SQLINTEGER = 0;
+pub const SQL_ATTR_MAX_ROWS: SQLINTEGER = 1;
+pub const SQL_ATTR_MAX_LENGTH: SQLINTEGER = 3;
+pub const SQL_ATTR_CURSOR_TYPE: SQLINTEGER = 6;
+pub const SQL_ATTR_CONCURRENCY: SQLINTEGER = 7;
+pub const SQL_ATTR_KEYSET_SIZE: SQLINTEGER = 8;
+pub const SQL_ATTR_USE_BOOKMARKS: SQLINTEGER = 12;
+pub const SQL_ATTR_ROW_NUMBER: SQLINTEGER = 14;
+pub const SQL_ATTR_PARAM_STATUS_PTR: SQLINTEGER = 20;
+pub const SQL_ATTR_PARAMS_PROCESSED_PTR: SQLINTEGER = 21;
+pub const SQL_ATTR_CURSOR_SCROLLABLE: SQLINTEGER = -1;
+pub const SQL_ATTR_CURSOR_SENSITIVITY: SQLINTEGER = -2;
+pub const SQL_ATTR_ROW_STATUS_PTR: SQLINTEGER = 25;
+
+// Values of the parameter status array
+pub const SQL_PARAM_SUCCESS: SQLUSMALLINT = 0;
+pub const SQL_PARAM_DIAG_UNAVAILABLE: SQLUSMALLINT = 1;
+pub const SQL_PARAM_ERROR: SQLUSMALLINT = 5;
+pub const SQL_PARAM_SUCCESS_WITH_INFO: SQLUSMALLINT = 6;
+pub const SQL_PARAM_UNUSED: SQLUSMALLINT = 7;
+
+// Values of the row status array
+pub const SQL_ROW_SUCCESS: SQLUSMALLINT = 0;
+pub const SQL_ROW_DELETED: SQLUSMALLINT = 1;
+pub const SQL_ROW_UPDATED: SQLUSMALLINT = 2;
+pub const SQL_ROW_NOROW: SQLUSMALLINT = 3;
+pub const SQL_ROW_ADDED: SQLUSMALLINT = 4;
+pub const SQL_ROW_ERROR: SQLUSMALLINT = 5;
+pub const SQL_ROW_SUCCESS_WITH_INFO: SQLUSMALLINT = 6;
+
+// Operations of `SQLSetPos`. `SQL_ADD` is a `SqlBulkOperation` of odbc-sys, so the operation of
+// the same value is prefixed.
+pub const SQL_POSITION: SQLUSMALLINT = 0;
+pub const SQL_REFRESH: SQLUSMALLINT = 1;
+pub const SQL_UPDATE: SQLUSMALLINT = 2;
+pub const SQL_DELETE: SQLUSMALLINT = 3;
+pub const SQL_SETPOS_ADD: SQLUSMALLINT = 4;
+
+// Lock types of `SQLSetPos`
+pub const SQL_LOCK_NO_CHANGE: SQLUSMALLINT = 0;
+pub const SQL_LOCK_EXCLUSIVE: SQLUSMALLINT = 1;
+pub const SQL_LOCK_UNLOCK: SQLUSMALLINT = 2;
+
+// Length or indicator of a parameter, whose data is sent at execution time using `SQLPutData`.
+// `SQL_LEN_DATA_AT_EXEC(length)` is `SQL_LEN_DATA_AT_EXEC_OFFSET - length`.
+pub const SQL_DATA_AT_EXEC: SQLLEN = -2;
+pub const SQL_LEN_DATA_AT_EXEC_OFFSET: SQLLEN = -100;
+
+// Values of `SQL_ATTR_CURSOR_SCROLLABLE`
+pub const SQL_NONSCROLLABLE: SQLULEN = 0;
+pub const SQL_SCROLLABLE: SQLULEN = 1;
+
+// Values of `SQL_ATTR_ASYNC_ENABLE`
+pub const SQL_ASYNC_ENABLE_OFF: SQLULEN = 0;
+pub const SQL_ASYNC_ENABLE_ON: SQLULEN = 1;
+
+// Values of `SQL_ATTR_CURSOR_SENSITIVITY`
+pub const SQL_UNSPECIFIED: SQLULEN = 0;
+pub const SQL_INSENSITIVE: SQLULEN = 1;
+pub const SQL_SENSITIVE: SQLULEN = 2;
+
+// Values of `SQL_ATTR_CONCURRENCY`
+pub const SQL_CONCUR_READ_ONLY: SQLULEN = 1;
+pub const SQL_CONCUR_LOCK: SQLULEN = 2;
+pub const SQL_CONCUR_ROWVER: SQLULEN = 3;
+pub const SQL_CONCUR_VALUES: SQLULEN = 4;
+
+// Values of `SQL_ATTR_CURSOR_TYPE`
+pub const SQL_CURSOR_FORWARD_ONLY: SQLULEN = 0;
+pub const SQL_CURSOR_KEYSET_DRIVEN: SQLULEN = 1;
+pub const SQL_CURSOR_DYNAMIC: SQLULEN = 2;
+pub const SQL_CURSOR_STATIC: SQLULEN = 3;
+
+// Values of `SQL_ATTR_USE_BOOKMARKS`
+pub const SQL_UB_OFF: SQLULEN = 0;
+pub const SQL_UB_VARIABLE: SQLULEN = 2;
+
+// Values of `SQL_ATTR_ACCESS_MODE`
+pub const SQL_MODE_READ_WRITE: SQLUINTEGER = 0;
+pub const SQL_MODE_READ_ONLY: SQLUINTEGER = 1;
+
+// Values of `SQL_ATTR_TRACE`
+pub const SQL_OPT_TRACE_OFF: SQLUINTEGER = 0;
+pub const SQL_OPT_TRACE_ON: SQLUINTEGER = 1;
+
+// Values of `SQL_ATTR_CONNECTION_DEAD`
+pub const SQL_CD_TRUE: SQLUINTEGER = 1;
+pub const SQL_CD_FALSE: SQLUINTEGER = 0;
+
+// Fields of the implementation row descriptor, queried with `SQLColAttribute`
+pub const SQL_DESC_AUTO_UNIQUE_VALUE: SQLUSMALLINT = 11;
+pub const SQL_DESC_CASE_SENSITIVE: SQLUSMALLINT = 12;
+pub const SQL_DESC_SEARCHABLE: SQLUSMALLINT = 13;
+pub const SQL_DESC_TYPE_NAME: SQLUSMALLINT = 14;
+pub const SQL_DESC_SCHEMA_NAME: SQLUSMALLINT = 16;
+pub const SQL_DESC_CATALOG_NAME: SQLUSMALLINT = 17;
+pub const SQL_DESC_LABEL: SQLUSMALLINT = 18;
+pub const SQL_DESC_DISPLAY_SIZE: SQLUSMALLINT = 6;
+pub const SQL_DESC_UPDATABLE: SQLUSMALLINT = 10;
+pub const SQL_DESC_BASE_COLUMN_NAME: SQLUSMALLINT = 22;
+pub const SQL_DESC_BASE_TABLE_NAME: SQLUSMALLINT = 23;
+pub const SQL_DESC_NULLABLE: SQLUSMALLINT = 1008;
+pub const SQL_DESC_OCTET_LENGTH: SQLUSMALLINT = 1013;
+
+// Values of `SQL_DESC_UPDATABLE`
+pub const SQL_ATTR_READONLY: SQLLEN = 0;
+pub const SQL_ATTR_WRITE: SQLLEN = 1;
+
+// Values of `SQL_ATTR_CONNECTION_POOLING`
+pub const SQL_CP_OFF: SQLUINTEGER = 0;
+pub const SQL_CP_ONE_PER_DRIVER: SQLUINTEGER = 1;
+pub const SQL_CP_ONE_PER_HENV: SQLUINTEGER = 2;
+
+// Values of `SQL_ATTR_CP_MATCH`
+pub const SQL_CP_STRICT_MATCH: SQLUINTEGER = 0;
+pub const SQL_CP_RELAXED_MATCH: SQLUINTEGER = 1;
+
+// Values of `SQL_ATTR_OUTPUT_NTS`
+pub const SQL_TRUE: SQLUINTEGER = 1;
+pub const SQL_FALSE: SQLUINTEGER = 0;
+
+// Information types missing in `InfoType`
+pub const SQL_DRIVER_NAME: SQLUSMALLINT = 6;
+pub const SQL_DRIVER_VER: SQLUSMALLINT = 7;
+pub const SQL_DRIVER_ODBC_VER: SQLUSMALLINT = 77;
+
+// Descriptors, attributes of a statement
+pub const SQL_ATTR_APP_ROW_DESC: SQLINTEGER = 10010;
+pub const SQL_ATTR_APP_PARAM_DESC: SQLINTEGER = 10011;
+pub const SQL_ATTR_IMP_ROW_DESC: SQLINTEGER = 10012;
+pub const SQL_ATTR_IMP_PARAM_DESC: SQLINTEGER = 10013;
+
+// Descriptor fields
+pub const SQL_DESC_TYPE: SQLSMALLINT = 1002;
+pub const SQL_DESC_PRECISION: SQLSMALLINT = 1005;
+pub const SQL_DESC_SCALE: SQLSMALLINT = 1006;
+
+// Target type of `SQLGetData`, which takes the type from the application row descriptor
+pub const SQL_ARD_TYPE: SQLSMALLINT = -99;
+
+pub const SQL_MAX_NUMERIC_LEN: usize = 16;
+
+/// Value of a `SQL_C_NUMERIC` buffer. `val` holds the unscaled value as little endian integer.
+/// `sign` is `1` for positive and `0` for negative numbers.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub struct SQL_NUMERIC_STRUCT {
+    pub precision: SQLCHAR,
+    pub scale: i8,
+    pub sign: SQLCHAR,
+    pub val: [SQLCHAR; SQL_MAX_NUMERIC_LEN],
+}
+
+// Functions not declared by odbc-sys (yet)
+#[cfg_attr(windows, link(name = "odbc32"))]
+#[cfg_attr(not(windows), link(name = "odbc"))]
+extern "system" {
+    pub fn SQLColumns(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+        column_name: *const SQLCHAR,
+        column_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLPrimaryKeys(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLForeignKeys(
+        statement_handle: SQLHSTMT,
+        pk_catalog_name: *const SQLCHAR,
+        pk_catalog_name_length: SQLSMALLINT,
+        pk_schema_name: *const SQLCHAR,
+        pk_schema_name_length: SQLSMALLINT,
+        pk_table_name: *const SQLCHAR,
+        pk_table_name_length: SQLSMALLINT,
+        fk_catalog_name: *const SQLCHAR,
+        fk_catalog_name_length: SQLSMALLINT,
+        fk_schema_name: *const SQLCHAR,
+        fk_schema_name_length: SQLSMALLINT,
+        fk_table_name: *const SQLCHAR,
+        fk_table_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLStatistics(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+        unique: SQLUSMALLINT,
+        reserved: SQLUSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLTablePrivileges(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLColumnPrivileges(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+        column_name: *const SQLCHAR,
+        column_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLSpecialColumns(
+        statement_handle: SQLHSTMT,
+        identifier_type: SQLUSMALLINT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        table_name: *const SQLCHAR,
+        table_name_length: SQLSMALLINT,
+        scope: SQLUSMALLINT,
+        nullable: SQLUSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLProcedures(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        proc_name: *const SQLCHAR,
+        proc_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLProcedureColumns(
+        statement_handle: SQLHSTMT,
+        catalog_name: *const SQLCHAR,
+        catalog_name_length: SQLSMALLINT,
+        schema_name: *const SQLCHAR,
+        schema_name_length: SQLSMALLINT,
+        proc_name: *const SQLCHAR,
+        proc_name_length: SQLSMALLINT,
+        column_name: *const SQLCHAR,
+        column_name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLParamData(statement_handle: SQLHSTMT, value: *mut SQLPOINTER) -> SQLRETURN;
+
+    pub fn SQLPutData(
+        statement_handle: SQLHSTMT,
+        data: SQLPOINTER,
+        str_len_or_ind: SQLLEN,
+    ) -> SQLRETURN;
+
+    // `SQLSetStmtAttr` taking the attribute as plain integer, since `SqlStatementAttribute` lacks
+    // most of them
+    #[link_name = "SQLSetStmtAttr"]
+    pub fn SQLSetStmtAttrInteger(
+        statement_handle: SQLHSTMT,
+        attribute: SQLINTEGER,
+        value: SQLPOINTER,
+        string_length: SQLINTEGER,
+    ) -> SQLRETURN;
+
+    pub fn SQLGetStmtAttr(
+        statement_handle: SQLHSTMT,
+        attribute: SQLINTEGER,
+        value: SQLPOINTER,
+        buffer_length: SQLINTEGER,
+        string_length: *mut SQLINTEGER,
+    ) -> SQLRETURN;
+
+    pub fn SQLGetConnectAttr(
+        connection_handle: SQLHDBC,
+        attribute: SqlConnectionAttribute,
+        value: SQLPOINTER,
+        buffer_length: SQLINTEGER,
+        string_length: *mut SQLINTEGER,
+    ) -> SQLRETURN;
+
+    // `SQLGetInfo` taking the information type as plain integer, since `InfoType` lacks some of
+    // them
+    #[link_name = "SQLGetInfo"]
+    pub fn SQLGetInfoInteger(
+        connection_handle: SQLHDBC,
+        info_type: SQLUSMALLINT,
+        info_value: SQLPOINTER,
+        buffer_length: SQLSMALLINT,
+        string_length: *mut SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLSetDescField(
+        descriptor_handle: SQLHDESC,
+        rec_number: SQLSMALLINT,
+        field_identifier: SQLSMALLINT,
+        value: SQLPOINTER,
+        buffer_length: SQLINTEGER,
+    ) -> SQLRETURN;
+
+    // `SQLGetData` taking the target type as plain integer, so `SQL_ARD_TYPE` can be passed
+    #[link_name = "SQLGetData"]
+    pub fn SQLGetDataArdType(
+        statement_handle: SQLHSTMT,
+        col_or_param_num: SQLUSMALLINT,
+        target_type: SQLSMALLINT,
+        target_value_ptr: SQLPOINTER,
+        buffer_length: SQLLEN,
+        str_len_or_ind_ptr: *mut SQLLEN,
+    ) -> SQLRETURN;
+
+    pub fn SQLSetPos(
+        statement_handle: SQLHSTMT,
+        row_number: SQLULEN,
+        operation: SQLUSMALLINT,
+        lock_type: SQLUSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLNativeSql(
+        connection_handle: SQLHDBC,
+        in_statement_text: *const SQLCHAR,
+        text_length_1: SQLINTEGER,
+        out_statement_text: *mut SQLCHAR,
+        buffer_length: SQLINTEGER,
+        text_length_2: *mut SQLINTEGER,
+    ) -> SQLRETURN;
+
+    pub fn SQLNumParams(statement_handle: SQLHSTMT, parameter_count: *mut SQLSMALLINT) -> SQLRETURN;
+
+    pub fn SQLColAttribute(
+        statement_handle: SQLHSTMT,
+        column_number: SQLUSMALLINT,
+        field_identifier: SQLUSMALLINT,
+        character_attribute_ptr: SQLPOINTER,
+        buffer_length: SQLSMALLINT,
+        string_length_ptr: *mut SQLSMALLINT,
+        numeric_attribute_ptr: *mut SQLLEN,
+    ) -> SQLRETURN;
+
+    pub fn SQLSetCursorName(
+        statement_handle: SQLHSTMT,
+        cursor_name: *const SQLCHAR,
+        name_length: SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLGetCursorName(
+        statement_handle: SQLHSTMT,
+        cursor_name: *mut SQLCHAR,
+        buffer_length: SQLSMALLINT,
+        name_length_ptr: *mut SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLGetDiagField(
+        handle_type: HandleType,
+        handle: SQLHANDLE,
+        record_number: SQLSMALLINT,
+        diag_identifier: SQLSMALLINT,
+        diag_info_ptr: SQLPOINTER,
+        buffer_length: SQLSMALLINT,
+        string_length_ptr: *mut SQLSMALLINT,
+    ) -> SQLRETURN;
+}
@@ -28,14 +28,37 @@
 extern crate log;
 pub extern crate odbc_safe;
 extern crate encoding_rs;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "uuid")]
+extern crate uuid;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "metrics")]
+extern crate metrics;
+#[cfg(feature = "derive")]
+extern crate odbc_derive;
 
 pub mod ffi;
 
-pub use diagnostics::{DiagnosticRecord, GetDiagRec};
+pub use diagnostics::{DiagnosticContext, DiagnosticRecord, GetDiagRec};
 pub use result::Result;
 pub use environment::*;
-pub use connection::Connection;
+pub use connection::{
+    AccessMode, ConnectAttr, ConnectAttrKey, Connection, ConnectionBuilder,
+    DisconnectedConnection, InfoType, InfoValue,
+};
 pub use statement::*;
+pub use pool::{Pool, PooledConnection, Validation};
+pub use migration::Migration;
+#[cfg(feature = "derive")]
+pub use odbc_derive::FromRow;
 
 use odbc_object::OdbcObject;
 use raii::Raii;
@@ -49,6 +72,8 @@ mod result;
 mod environment;
 mod connection;
 mod statement;
+mod pool;
+mod migration;
 
 
 /// Reflects the ability of a type to expose a valid handle
@@ -0,0 +1,126 @@
+//! Applies schema migrations in order, keeping track of the applied versions in the data source
+use odbc_safe::AutocommitOff;
+use std::collections::BTreeSet;
+use {Connection, Data, Result, Statement};
+
+const CREATE_TABLE: &str = "CREATE TABLE __schema_migrations (VERSION INTEGER NOT NULL PRIMARY KEY)";
+const SELECT_VERSIONS: &str = "SELECT VERSION FROM __schema_migrations";
+const INSERT_VERSION: &str = "INSERT INTO __schema_migrations (VERSION) VALUES (?)";
+
+/// Applies SQL statements, usually DDL, which have not been applied to the data source yet.
+///
+/// Every migration is identified by its version. Applied versions are recorded in the table
+/// `__schema_migrations`, which is created on the first run. `run` applies the pending migrations
+/// in ascending order of their version, each one in its own transaction together with the record
+/// of its version. If a migration fails, its transaction is rolled back and no further migrations
+/// are applied.
+///
+/// Note that some data sources (e.g. MySQL or Oracle) implicitly commit DDL statements, so a
+/// failing migration may leave some of its changes behind.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// let mut conn = conn.disable_autocommit().ok().expect("autocommit could not be disabled");
+/// let version = Migration::new(&mut conn)
+///     .add(1, "CREATE TABLE AUTHORS (NAME TEXT)")
+///     .add(2, "ALTER TABLE AUTHORS ADD COLUMN BORN INTEGER")
+///     .run()?;
+/// assert_eq!(version, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Migration<'c, 'env: 'c> {
+    conn: &'c mut Connection<'env, AutocommitOff>,
+    migrations: Vec<(u32, String)>,
+}
+
+impl<'c, 'env> Migration<'c, 'env> {
+    /// Migrations to be applied using `conn`, without any migrations yet
+    pub fn new(conn: &'c mut Connection<'env, AutocommitOff>) -> Self {
+        Migration {
+            conn,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Adds a migration executing `sql`. Versions do not need to be added in order.
+    ///
+    /// # Panics
+    ///
+    /// If a migration with the same version has already been added
+    pub fn add(mut self, version: u32, sql: &str) -> Self {
+        assert!(
+            self.migrations.iter().all(|&(v, _)| v != version),
+            "migration {} has been added twice",
+            version
+        );
+        self.migrations.push((version, sql.to_owned()));
+        self
+    }
+
+    /// Applies all migrations which have not been applied yet. Returns the highest applied
+    /// version, including those applied by earlier runs, or `0` if there are none.
+    pub fn run(mut self) -> Result<u32> {
+        let mut applied = self.applied_versions()?;
+        self.migrations.sort_by_key(|&(version, _)| version);
+        for &(version, ref sql) in &self.migrations {
+            if applied.contains(&version) {
+                continue;
+            }
+            if let Err(e) = apply(self.conn, version, sql) {
+                // Report the failing migration, rather than a failing rollback
+                let _ = self.conn.rollback();
+                return Err(e);
+            }
+            self.conn.commit()?;
+            applied.insert(version);
+        }
+        Ok(applied.iter().next_back().cloned().unwrap_or(0))
+    }
+
+    /// Versions recorded in `__schema_migrations`. Creates the table, if it does not exist.
+    fn applied_versions(&mut self) -> Result<BTreeSet<u32>> {
+        match select_versions(self.conn) {
+            Ok(versions) => {
+                self.conn.commit()?;
+                Ok(versions)
+            }
+            // There is no portable way to tell whether the table is missing from the error, so
+            // attempt to create it. Should the error have had another cause, creation fails too.
+            Err(_) => {
+                self.conn.rollback()?;
+                Statement::with_parent(&*self.conn)?.exec_direct(CREATE_TABLE)?;
+                self.conn.commit()?;
+                Ok(BTreeSet::new())
+            }
+        }
+    }
+}
+
+fn select_versions(conn: &Connection<AutocommitOff>) -> Result<BTreeSet<u32>> {
+    let mut versions = BTreeSet::new();
+    if let Data(mut stmt) = Statement::with_parent(conn)?.exec_direct(SELECT_VERSIONS)? {
+        while let Some(mut cursor) = stmt.fetch()? {
+            if let Some(version) = cursor.get_data::<u32>(1)? {
+                versions.insert(version);
+            }
+        }
+    }
+    Ok(versions)
+}
+
+fn apply(conn: &Connection<AutocommitOff>, version: u32, sql: &str) -> Result<()> {
+    if let Data(stmt) = Statement::with_parent(conn)?.exec_direct(sql)? {
+        stmt.close_cursor()?;
+    }
+    let stmt = Statement::with_parent(conn)?.prepare(INSERT_VERSION)?;
+    if let Data(stmt) = stmt.bind_parameter(1, &version)?.execute()? {
+        stmt.close_cursor()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! A simple pool, reusing open connections
+use odbc_safe::AutocommitOn;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use {Connection, Result, Statement};
+
+/// How connections are checked, before `Pool::get` hands them out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Validation {
+    /// Connections are not checked
+    None,
+    /// Checks the `SQL_ATTR_CONNECTION_DEAD` attribute, see `Connection::is_dead`
+    ConnectionDead,
+    /// Executes the query, e.g. `"SELECT 1"`. A connection is considered dead if execution fails.
+    Query(String),
+}
+
+/// Maintains a set of open connections, which are reused by handing them out with `get`.
+///
+/// Connections are created on demand using the factory, up to a maximum number. If all of them
+/// are in use, `get` blocks until one is returned to the pool. Returned connections are checked
+/// out again as they are, so do not change any state of a connection (like its attributes) which
+/// a later user would not expect.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let pool = Pool::new(|| env.connect("TestDataSource", "", ""), 4);
+/// {
+///     let conn = pool.get()?;
+///     Statement::with_parent(&*conn)?.exec_direct("SELECT * FROM MOVIES")?;
+/// } // `conn` is returned to the pool here
+/// let conn = pool.get()?; // Reuses the connection
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pool<'env, F> {
+    factory: F,
+    max_size: usize,
+    validation: Validation,
+    state: Mutex<State<'env>>,
+    // Signaled each time a connection is returned, or could be created again
+    available: Condvar,
+}
+
+struct State<'env> {
+    idle: Vec<Connection<'env, AutocommitOn>>,
+    // Number of connections in use, plus idle ones, plus connections currently being created
+    size: usize,
+}
+
+impl<'env, F> Pool<'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    /// Creates an empty pool, which opens at most `max_size` connections using `factory`.
+    /// Connections are validated using `Validation::ConnectionDead`.
+    ///
+    /// # Panics
+    ///
+    /// If `max_size` is `0`
+    pub fn new(factory: F, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size of a connection pool must not be 0");
+        Pool {
+            factory,
+            max_size,
+            validation: Validation::ConnectionDead,
+            state: Mutex::new(State {
+                idle: Vec::new(),
+                size: 0,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Changes how idle connections are validated on checkout
+    pub fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Checks out a connection. It is returned to the pool, once the `PooledConnection` is
+    /// dropped.
+    ///
+    /// An idle connection is reused if possible. Idle connections failing validation are dropped.
+    /// If there is no idle connection a new one is created, unless there are already `max_size`
+    /// connections, in which case this blocks until one is returned. Errors of the factory are
+    /// passed through.
+    pub fn get(&self) -> Result<PooledConnection<'_, 'env, F>> {
+        let mut state = self.lock();
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                // Do not block other threads, while talking to the data source
+                drop(state);
+                if self.is_valid(&conn) {
+                    return Ok(PooledConnection::new(self, conn));
+                }
+                drop(conn);
+                state = self.lock();
+                state.size -= 1;
+            } else if state.size < self.max_size {
+                state.size += 1;
+                drop(state);
+                return match (self.factory)() {
+                    Ok(conn) => Ok(PooledConnection::new(self, conn)),
+                    Err(e) => {
+                        self.lock().size -= 1;
+                        self.available.notify_one();
+                        Err(e)
+                    }
+                };
+            } else {
+                state = self
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+        }
+    }
+
+    /// Number of connections currently opened by the pool, whether in use or idle
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    /// Number of idle connections, waiting to be checked out
+    pub fn idle(&self) -> usize {
+        self.lock().idle.len()
+    }
+
+    fn is_valid(&self, conn: &Connection<'env, AutocommitOn>) -> bool {
+        match self.validation {
+            Validation::None => true,
+            Validation::ConnectionDead => conn.is_dead().map(|dead| !dead).unwrap_or(false),
+            Validation::Query(ref query) => match Statement::with_parent(conn) {
+                Ok(stmt) => stmt.exec_direct(query).is_ok(),
+                Err(_) => false,
+            },
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<'env>> {
+        // The state is consistent at any point a panic could occur, so poison can be ignored.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<'env, F> fmt::Debug for Pool<'env, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("max_size", &self.max_size)
+            .field("validation", &self.validation)
+            .finish()
+    }
+}
+
+/// A connection checked out of a `Pool`. Derefs to `Connection` and returns it to the pool once
+/// dropped.
+pub struct PooledConnection<'p, 'env: 'p, F: 'p>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    pool: &'p Pool<'env, F>,
+    // Only `None` during `drop`
+    conn: Option<Connection<'env, AutocommitOn>>,
+}
+
+impl<'p, 'env, F> PooledConnection<'p, 'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    fn new(pool: &'p Pool<'env, F>, conn: Connection<'env, AutocommitOn>) -> Self {
+        PooledConnection {
+            pool,
+            conn: Some(conn),
+        }
+    }
+}
+
+impl<'p, 'env, F> Deref for PooledConnection<'p, 'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    type Target = Connection<'env, AutocommitOn>;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl<'p, 'env, F> DerefMut for PooledConnection<'p, 'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl<'p, 'env, F> Drop for PooledConnection<'p, 'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.lock().idle.push(conn);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+impl<'p, 'env, F> fmt::Debug for PooledConnection<'p, 'env, F>
+where
+    F: Fn() -> Result<Connection<'env, AutocommitOn>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PooledConnection").field(&self.conn).finish()
+    }
+}
@@ -1,4 +1,6 @@
-use super::{ffi, safe, DiagnosticRecord, GetDiagRec, Handle, OdbcObject, Return};
+use super::{ffi, safe, DiagnosticRecord, GetDiagRec, Handle, OdbcObject, Result, Return};
+use result::error_record;
+use std::mem::forget;
 use std::ptr::null_mut;
 use std::marker::PhantomData;
 
@@ -43,6 +45,21 @@ impl<'p, T: OdbcObject> Drop for Raii<'p, T> {
 }
 
 impl<'p, T: OdbcObject> Raii<'p, T> {
+    /// Frees the handle immediately, rather than on drop. If `SQLFreeHandle` fails the handle is
+    /// still consumed. Freeing it is attempted once more on drop, after the diagnostics have been
+    /// read.
+    pub fn free(self) -> Result<()> {
+        match unsafe { ffi::SQLFreeHandle(T::HANDLE_TYPE, self.handle() as ffi::SQLHANDLE) } {
+            ffi::SQL_SUCCESS => {
+                // Already freed, so `drop` must not free it again
+                forget(self);
+                Ok(())
+            }
+            ffi::SQL_ERROR => Err(error_record(&self)),
+            r => panic!("SQLFreeHandle returned unexpected result: {:?}", r),
+        }
+    }
+
     pub fn with_parent<P>(parent: &'p P) -> Return<Self>
     where
         P: Handle<To = T::Parent>,
@@ -11,9 +11,47 @@ pub enum Return<T> {
     Success(T),
     SuccessWithInfo(T),
     Error,
+    /// The function returned `SQL_NEED_DATA`, although no data at execution has been expected
+    NeedData,
+    /// An error detected by this crate rather than the driver, e.g. a value returned by the
+    /// driver which the requested type can not represent
+    Failed(Box<DiagnosticRecord>),
 }
 
 impl<T> Return<T> {
+    pub fn map<F, U>(self, f: F) -> Return<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Return::Success(value) => Return::Success(f(value)),
+            Return::SuccessWithInfo(value) => Return::SuccessWithInfo(f(value)),
+            Return::Error => Return::Error,
+            Return::NeedData => Return::NeedData,
+            Return::Failed(record) => Return::Failed(record),
+        }
+    }
+
+    /// Like `map`, but `f` may fail, turning a successful call into `Failed`
+    pub fn try_map<F, U>(self, f: F) -> Return<U>
+    where
+        F: FnOnce(T) -> Result<U>,
+    {
+        match self {
+            Return::Success(value) => match f(value) {
+                Ok(value) => Return::Success(value),
+                Err(record) => Return::Failed(Box::new(record)),
+            },
+            Return::SuccessWithInfo(value) => match f(value) {
+                Ok(value) => Return::SuccessWithInfo(value),
+                Err(record) => Return::Failed(Box::new(record)),
+            },
+            Return::Error => Return::Error,
+            Return::NeedData => Return::NeedData,
+            Return::Failed(record) => Return::Failed(record),
+        }
+    }
+
     pub fn into_result<O: GetDiagRec>(self, odbc_object: &O) -> Result<T> {
         match self {
             Return::Success(value) => Ok(value),
@@ -21,26 +59,73 @@ impl<T> Return<T> {
                 let mut i = 1;
                 while let Some(diag) = odbc_object.get_diag_rec(i) {
                     warn!("{}", diag);
+                    odbc_object.record_warning(diag);
                     i += 1;
                 }
                 Ok(value)
             }
-            Return::Error => {
-                // Return the first record
-                let diag = odbc_object.get_diag_rec(1).unwrap_or_else(DiagnosticRecord::empty);
-                error!("{}", diag);
-                let mut i = 2;
-                // log the rest
-                while let Some(diag) = odbc_object.get_diag_rec(i) {
-                    error!("{}", diag);
-                    i += 1;
-                }
-                Err(diag)
+            Return::Error => Err(error_record(odbc_object)),
+            Return::NeedData => Err(DiagnosticRecord::need_data()),
+            Return::Failed(record) => {
+                error!("{}", record);
+                Err(*record)
             }
         }
     }
 }
 
+/// Logs all diagnostic records of a failed function call and returns the first one, holding the
+/// others as additional records.
+pub fn error_record<D: GetDiagRec>(diag: &D) -> DiagnosticRecord {
+    let first = collect_records(diag);
+    error!("{}", first);
+    for rec in first.get_additional_records() {
+        error!("{}", rec);
+    }
+    first
+}
+
+/// `Failed` with the diagnostic records of `diag`, for functions called on another handle than
+/// the one `into_result` reads the records from, e.g. a descriptor of a statement
+pub(crate) fn failed_on<D: GetDiagRec, T>(diag: &D) -> Return<T> {
+    Return::Failed(Box::new(collect_records(diag)))
+}
+
+/// The first diagnostic record of `diag`, holding the others as additional records
+fn collect_records<D: GetDiagRec>(diag: &D) -> DiagnosticRecord {
+    let mut records = diag.get_diag_recs().into_iter();
+    let mut first = records.next().unwrap_or_else(DiagnosticRecord::empty);
+    for rec in records {
+        first.push_additional_record(rec);
+    }
+    first
+}
+
+/// Retrieves a string of unknown length. `f` is called with a buffer of `initial_len` bytes and
+/// returns the result of the ODBC function together with the length of the string, which drivers
+/// report even if the string has been truncated. As long as the string plus terminating zero does
+/// not fit, `f` is called again with a larger buffer. Yields the string without terminating zero.
+pub(crate) fn with_growing_buffer<T, F>(initial_len: usize, mut f: F) -> Return<(T, Vec<u8>)>
+where
+    F: FnMut(&mut [u8]) -> (Return<T>, usize),
+{
+    let mut buffer = vec![0u8; initial_len];
+    loop {
+        let (ret, length) = f(&mut buffer);
+        let truncated = match ret {
+            Return::Success(_) | Return::SuccessWithInfo(_) => length >= buffer.len(),
+            _ => false,
+        };
+        if truncated {
+            buffer.resize(length + 1, 0);
+            continue;
+        }
+        return ret.map(|value| {
+            buffer.truncate(length);
+            (value, buffer)
+        });
+    }
+}
 
 // temporary glue code to odbc-safe
 pub fn try_into_option<T, E, D>(ret: safe::ReturnOption<T, E>, handle: &D) -> Result<Option<T>>
@@ -53,23 +138,13 @@ where
             let mut i = 1;
             while let Some(diag) = handle.get_diag_rec(i) {
                 warn!("{}", diag);
+                handle.record_warning(diag);
                 i += 1;
             }
             Ok(Some(value))
         }
         safe::ReturnOption::NoData(_) => Ok(None),
-        safe::ReturnOption::Error(_) => {
-            // Return the first record
-            let diag = handle.get_diag_rec(1).unwrap_or_else(DiagnosticRecord::empty);
-            error!("{}", diag);
-            let mut i = 2;
-            // log the rest
-            while let Some(diag) = handle.get_diag_rec(i) {
-                error!("{}", diag);
-                i += 1;
-            }
-            Err(diag)
-        }
+        safe::ReturnOption::Error(_) => Err(error_record(handle)),
     }
 }
 
@@ -89,18 +164,7 @@ where
             }
             Ok(value)
         }
-        safe::Return::Error(value) => {
-            // Return the first record
-            let diag = value.get_diag_rec(1).unwrap_or_else(DiagnosticRecord::empty);
-            error!("{}", diag);
-            let mut i = 2;
-            // log the rest
-            while let Some(diag) = value.get_diag_rec(i) {
-                error!("{}", diag);
-                i += 1;
-            }
-            Err(diag)
-        }
+        safe::Return::Error(value) => Err(error_record(&value)),
     }
 }
 
@@ -119,17 +183,28 @@ where
             }
             Ok(value)
         }
-        safe::Return::Error(_) => {
-            // Return the first record
-            let diag_rec = diag.get_diag_rec(1).unwrap_or_else(DiagnosticRecord::empty);
-            error!("{}", diag_rec);
-            let mut i = 2;
-            // log the rest
-            while let Some(diag_rec) = diag.get_diag_rec(i) {
-                error!("{}", diag_rec);
-                i += 1;
-            }
-            Err(diag_rec)
+        safe::Return::Error(_) => Err(error_record(diag)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn growing_buffer_retries_truncated_string() {
+        let text = b"a string longer than the initial buffer";
+        let mut calls = 0;
+        let ret = with_growing_buffer(8, |buffer| {
+            calls += 1;
+            let len = text.len().min(buffer.len() - 1);
+            buffer[..len].copy_from_slice(&text[..len]);
+            (Return::Success(()), text.len())
+        });
+        match ret {
+            Return::Success(((), value)) => assert_eq!(value, text.to_vec()),
+            _ => panic!("expected success"),
         }
+        assert_eq!(calls, 2);
     }
 }
@@ -0,0 +1,414 @@
+//! Statement attributes, set with `SQLSetStmtAttr` and queried with `SQLGetStmtAttr`
+use super::{HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::ptr::null_mut;
+use {ffi, Handle, Raii, Result, Return};
+
+/// A statement attribute together with its value. Used by `Statement::set_attr` and
+/// `Statement::get_attr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatementAttr {
+    /// Number of seconds to wait for an SQL statement to execute. `0` means no timeout.
+    QueryTimeout(u32),
+    /// Maximum number of rows to return to the application for a `SELECT` statement. `0` means
+    /// all rows are returned.
+    MaxRows(u32),
+    /// Maximum amount of data the driver returns from a character or binary column. `0` means
+    /// all available data is returned.
+    MaxLength(u32),
+    /// `true` if the cursor supports scrolling, i.e. fetch orientations other than next.
+    CursorScrollable(bool),
+    /// Whether cursors on the statement make visible the changes made to a result set by
+    /// another cursor.
+    CursorSensitivity(CursorSensitivity),
+    /// Cursor concurrency
+    Concurrency(Concurrency),
+    /// Cursor type
+    CursorType(CursorType),
+    /// Number of rows in the keyset of a `CursorType::KeysetDriven` cursor. `0` means the keyset
+    /// covers the whole result set.
+    KeysetSize(u32),
+    /// `true` if functions executing the statement or fetching its results are executed
+    /// asynchronously. The methods of `Statement` still block, polling the driver until the
+    /// function has completed, but it can be canceled from another thread using `CancelHandle`.
+    AsyncEnable(bool),
+    /// `true` if the cursor provides bookmarks in column `0`, identifying rows for
+    /// `Statement::bulk_operations`. Has to be set before the statement is executed. Bookmarks
+    /// are of variable length (`SQL_UB_VARIABLE`).
+    UseBookmarks(bool),
+}
+
+/// Identifies a statement attribute without a value. Passed to `Statement::get_attr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatementAttrKey {
+    /// `SQL_ATTR_QUERY_TIMEOUT`
+    QueryTimeout,
+    /// `SQL_ATTR_MAX_ROWS`
+    MaxRows,
+    /// `SQL_ATTR_MAX_LENGTH`
+    MaxLength,
+    /// `SQL_ATTR_CURSOR_SCROLLABLE`
+    CursorScrollable,
+    /// `SQL_ATTR_CURSOR_SENSITIVITY`
+    CursorSensitivity,
+    /// `SQL_ATTR_CONCURRENCY`
+    Concurrency,
+    /// `SQL_ATTR_CURSOR_TYPE`
+    CursorType,
+    /// `SQL_ATTR_KEYSET_SIZE`
+    KeysetSize,
+    /// `SQL_ATTR_ASYNC_ENABLE`
+    AsyncEnable,
+    /// `SQL_ATTR_USE_BOOKMARKS`
+    UseBookmarks,
+}
+
+impl fmt::Display for StatementAttrKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            StatementAttrKey::QueryTimeout => "SQL_ATTR_QUERY_TIMEOUT",
+            StatementAttrKey::MaxRows => "SQL_ATTR_MAX_ROWS",
+            StatementAttrKey::MaxLength => "SQL_ATTR_MAX_LENGTH",
+            StatementAttrKey::CursorScrollable => "SQL_ATTR_CURSOR_SCROLLABLE",
+            StatementAttrKey::CursorSensitivity => "SQL_ATTR_CURSOR_SENSITIVITY",
+            StatementAttrKey::Concurrency => "SQL_ATTR_CONCURRENCY",
+            StatementAttrKey::CursorType => "SQL_ATTR_CURSOR_TYPE",
+            StatementAttrKey::KeysetSize => "SQL_ATTR_KEYSET_SIZE",
+            StatementAttrKey::AsyncEnable => "SQL_ATTR_ASYNC_ENABLE",
+            StatementAttrKey::UseBookmarks => "SQL_ATTR_USE_BOOKMARKS",
+        })
+    }
+}
+
+/// Value of the `SQL_ATTR_CURSOR_SENSITIVITY` statement attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CursorSensitivity {
+    /// It is unspecified whether changes made by other cursors are visible (`SQL_UNSPECIFIED`)
+    Unspecified,
+    /// Changes made by other cursors are not visible (`SQL_INSENSITIVE`)
+    Insensitive,
+    /// Changes made by other cursors are visible (`SQL_SENSITIVE`)
+    Sensitive,
+    /// A value not defined by the ODBC standard, e.g. specific to the driver
+    Other(u32),
+}
+
+impl fmt::Display for CursorSensitivity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CursorSensitivity::Other(value) => return write!(f, "cursor sensitivity {}", value),
+            CursorSensitivity::Unspecified => "SQL_UNSPECIFIED",
+            CursorSensitivity::Insensitive => "SQL_INSENSITIVE",
+            CursorSensitivity::Sensitive => "SQL_SENSITIVE",
+        })
+    }
+}
+
+/// Value of the `SQL_ATTR_CONCURRENCY` statement attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Concurrency {
+    /// Cursor is read-only, updates are not allowed (`SQL_CONCUR_READ_ONLY`)
+    ReadOnly,
+    /// Uses the lowest level of locking sufficient to ensure that the row can be updated
+    /// (`SQL_CONCUR_LOCK`)
+    Lock,
+    /// Optimistic concurrency control comparing row versions (`SQL_CONCUR_ROWVER`)
+    RowVersion,
+    /// Optimistic concurrency control comparing values (`SQL_CONCUR_VALUES`)
+    Values,
+    /// A value not defined by the ODBC standard, e.g. specific to the driver
+    Other(u32),
+}
+
+impl fmt::Display for Concurrency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Concurrency::Other(value) => return write!(f, "concurrency {}", value),
+            Concurrency::ReadOnly => "SQL_CONCUR_READ_ONLY",
+            Concurrency::Lock => "SQL_CONCUR_LOCK",
+            Concurrency::RowVersion => "SQL_CONCUR_ROWVER",
+            Concurrency::Values => "SQL_CONCUR_VALUES",
+        })
+    }
+}
+
+/// Value of the `SQL_ATTR_CURSOR_TYPE` statement attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CursorType {
+    /// The cursor only scrolls forward (`SQL_CURSOR_FORWARD_ONLY`)
+    ForwardOnly,
+    /// The driver saves and uses the keys of the rows in the result set
+    /// (`SQL_CURSOR_KEYSET_DRIVEN`)
+    KeysetDriven,
+    /// The driver saves and uses only the keys of the rows in the rowset (`SQL_CURSOR_DYNAMIC`)
+    Dynamic,
+    /// The data in the result set is static (`SQL_CURSOR_STATIC`)
+    Static,
+    /// A value not defined by the ODBC standard, e.g. specific to the driver
+    Other(u32),
+}
+
+impl fmt::Display for CursorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CursorType::Other(value) => return write!(f, "cursor type {}", value),
+            CursorType::ForwardOnly => "SQL_CURSOR_FORWARD_ONLY",
+            CursorType::KeysetDriven => "SQL_CURSOR_KEYSET_DRIVEN",
+            CursorType::Dynamic => "SQL_CURSOR_DYNAMIC",
+            CursorType::Static => "SQL_CURSOR_STATIC",
+        })
+    }
+}
+
+impl StatementAttr {
+    /// The key identifying this attribute
+    pub fn key(&self) -> StatementAttrKey {
+        match *self {
+            StatementAttr::QueryTimeout(_) => StatementAttrKey::QueryTimeout,
+            StatementAttr::MaxRows(_) => StatementAttrKey::MaxRows,
+            StatementAttr::MaxLength(_) => StatementAttrKey::MaxLength,
+            StatementAttr::CursorScrollable(_) => StatementAttrKey::CursorScrollable,
+            StatementAttr::CursorSensitivity(_) => StatementAttrKey::CursorSensitivity,
+            StatementAttr::Concurrency(_) => StatementAttrKey::Concurrency,
+            StatementAttr::CursorType(_) => StatementAttrKey::CursorType,
+            StatementAttr::KeysetSize(_) => StatementAttrKey::KeysetSize,
+            StatementAttr::AsyncEnable(_) => StatementAttrKey::AsyncEnable,
+            StatementAttr::UseBookmarks(_) => StatementAttrKey::UseBookmarks,
+        }
+    }
+
+    fn value(&self) -> ffi::SQLULEN {
+        match *self {
+            StatementAttr::QueryTimeout(v)
+            | StatementAttr::MaxRows(v)
+            | StatementAttr::MaxLength(v)
+            | StatementAttr::KeysetSize(v) => v as ffi::SQLULEN,
+            StatementAttr::CursorScrollable(false) => ffi::SQL_NONSCROLLABLE,
+            StatementAttr::CursorScrollable(true) => ffi::SQL_SCROLLABLE,
+            StatementAttr::AsyncEnable(false) => ffi::SQL_ASYNC_ENABLE_OFF,
+            StatementAttr::AsyncEnable(true) => ffi::SQL_ASYNC_ENABLE_ON,
+            StatementAttr::UseBookmarks(false) => ffi::SQL_UB_OFF,
+            StatementAttr::UseBookmarks(true) => ffi::SQL_UB_VARIABLE,
+            StatementAttr::CursorSensitivity(v) => match v {
+                CursorSensitivity::Unspecified => ffi::SQL_UNSPECIFIED,
+                CursorSensitivity::Insensitive => ffi::SQL_INSENSITIVE,
+                CursorSensitivity::Sensitive => ffi::SQL_SENSITIVE,
+                CursorSensitivity::Other(v) => v as ffi::SQLULEN,
+            },
+            StatementAttr::Concurrency(v) => match v {
+                Concurrency::ReadOnly => ffi::SQL_CONCUR_READ_ONLY,
+                Concurrency::Lock => ffi::SQL_CONCUR_LOCK,
+                Concurrency::RowVersion => ffi::SQL_CONCUR_ROWVER,
+                Concurrency::Values => ffi::SQL_CONCUR_VALUES,
+                Concurrency::Other(v) => v as ffi::SQLULEN,
+            },
+            StatementAttr::CursorType(v) => match v {
+                CursorType::ForwardOnly => ffi::SQL_CURSOR_FORWARD_ONLY,
+                CursorType::KeysetDriven => ffi::SQL_CURSOR_KEYSET_DRIVEN,
+                CursorType::Dynamic => ffi::SQL_CURSOR_DYNAMIC,
+                CursorType::Static => ffi::SQL_CURSOR_STATIC,
+                CursorType::Other(v) => v as ffi::SQLULEN,
+            },
+        }
+    }
+
+    fn from_value(key: StatementAttrKey, value: ffi::SQLULEN) -> StatementAttr {
+        match key {
+            StatementAttrKey::QueryTimeout => StatementAttr::QueryTimeout(value as u32),
+            StatementAttrKey::MaxRows => StatementAttr::MaxRows(value as u32),
+            StatementAttrKey::MaxLength => StatementAttr::MaxLength(value as u32),
+            StatementAttrKey::KeysetSize => StatementAttr::KeysetSize(value as u32),
+            StatementAttrKey::CursorScrollable => {
+                StatementAttr::CursorScrollable(value != ffi::SQL_NONSCROLLABLE)
+            }
+            StatementAttrKey::AsyncEnable => {
+                StatementAttr::AsyncEnable(value != ffi::SQL_ASYNC_ENABLE_OFF)
+            }
+            StatementAttrKey::UseBookmarks => StatementAttr::UseBookmarks(value != ffi::SQL_UB_OFF),
+            StatementAttrKey::CursorSensitivity => StatementAttr::CursorSensitivity(match value {
+                ffi::SQL_UNSPECIFIED => CursorSensitivity::Unspecified,
+                ffi::SQL_INSENSITIVE => CursorSensitivity::Insensitive,
+                ffi::SQL_SENSITIVE => CursorSensitivity::Sensitive,
+                v => CursorSensitivity::Other(v as u32),
+            }),
+            StatementAttrKey::Concurrency => StatementAttr::Concurrency(match value {
+                ffi::SQL_CONCUR_READ_ONLY => Concurrency::ReadOnly,
+                ffi::SQL_CONCUR_LOCK => Concurrency::Lock,
+                ffi::SQL_CONCUR_ROWVER => Concurrency::RowVersion,
+                ffi::SQL_CONCUR_VALUES => Concurrency::Values,
+                v => Concurrency::Other(v as u32),
+            }),
+            StatementAttrKey::CursorType => StatementAttr::CursorType(match value {
+                ffi::SQL_CURSOR_FORWARD_ONLY => CursorType::ForwardOnly,
+                ffi::SQL_CURSOR_KEYSET_DRIVEN => CursorType::KeysetDriven,
+                ffi::SQL_CURSOR_DYNAMIC => CursorType::Dynamic,
+                ffi::SQL_CURSOR_STATIC => CursorType::Static,
+                v => CursorType::Other(v as u32),
+            }),
+        }
+    }
+}
+
+impl StatementAttrKey {
+    fn attribute(&self) -> ffi::SQLINTEGER {
+        match *self {
+            StatementAttrKey::QueryTimeout => ffi::SQL_ATTR_QUERY_TIMEOUT,
+            StatementAttrKey::MaxRows => ffi::SQL_ATTR_MAX_ROWS,
+            StatementAttrKey::MaxLength => ffi::SQL_ATTR_MAX_LENGTH,
+            StatementAttrKey::CursorScrollable => ffi::SQL_ATTR_CURSOR_SCROLLABLE,
+            StatementAttrKey::CursorSensitivity => ffi::SQL_ATTR_CURSOR_SENSITIVITY,
+            StatementAttrKey::Concurrency => ffi::SQL_ATTR_CONCURRENCY,
+            StatementAttrKey::CursorType => ffi::SQL_ATTR_CURSOR_TYPE,
+            StatementAttrKey::KeysetSize => ffi::SQL_ATTR_KEYSET_SIZE,
+            StatementAttrKey::AsyncEnable => {
+                ffi::SqlStatementAttribute::SQL_ATTR_ASYNC_ENABLE as ffi::SQLINTEGER
+            }
+            StatementAttrKey::UseBookmarks => ffi::SQL_ATTR_USE_BOOKMARKS,
+        }
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Sets a statement attribute. Wraps `SQLSetStmtAttr`.
+    ///
+    /// Most attributes affecting the cursor, like `CursorType`, need to be set before the
+    /// statement is executed. Drivers may substitute a similar value if the requested one is not
+    /// supported, in which case the actual value can be queried using `get_attr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_attr(StatementAttr::QueryTimeout(30))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_attr(&mut self, attr: StatementAttr) -> Result<()> {
+        self.raii
+            .set_attr(attr.key().attribute(), attr.value())
+            .into_result(&self.diag())
+    }
+
+    /// Sets the number of seconds to wait for an SQL statement to execute, before returning to
+    /// the application. `0` means no timeout. Shorthand for `set_attr` with
+    /// `StatementAttr::QueryTimeout`, which can be chained before executing the statement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?.with_query_timeout(30)?;
+    /// stmt.exec_direct("SELECT * FROM MOVIES")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_query_timeout(mut self, seconds: u32) -> Result<Self> {
+        self.set_attr(StatementAttr::QueryTimeout(seconds))?;
+        Ok(self)
+    }
+
+    /// Sets the type of the cursor opened by executing the statement. Shorthand for `set_attr`
+    /// with `StatementAttr::CursorType`.
+    ///
+    /// Scrolling with `fetch_scroll` and positioned operations with `set_pos` require a cursor
+    /// type other than `CursorType::ForwardOnly`, which is the default. Must be called before the
+    /// statement is executed. The size of the keyset of a `CursorType::KeysetDriven` cursor can be
+    /// set using `StatementAttr::KeysetSize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_cursor_type(CursorType::KeysetDriven)?;
+    /// stmt.set_attr(StatementAttr::KeysetSize(100))?;
+    /// stmt.set_concurrency(Concurrency::RowVersion)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cursor_type(&mut self, cursor_type: CursorType) -> Result<()> {
+        self.set_attr(StatementAttr::CursorType(cursor_type))
+    }
+
+    /// Sets the concurrency control of the cursor opened by executing the statement. Shorthand
+    /// for `set_attr` with `StatementAttr::Concurrency`.
+    ///
+    /// Updating or deleting rows using `set_pos` requires a concurrency other than
+    /// `Concurrency::ReadOnly`, which is the default. Must be called before the statement is
+    /// executed.
+    pub fn set_concurrency(&mut self, concurrency: Concurrency) -> Result<()> {
+        self.set_attr(StatementAttr::Concurrency(concurrency))
+    }
+
+    /// Returns the current value of a statement attribute. Wraps `SQLGetStmtAttr`.
+    pub fn get_attr(&self, key: StatementAttrKey) -> Result<StatementAttr> {
+        self.raii
+            .get_attr(key.attribute())
+            .map(|value| StatementAttr::from_value(key, value))
+            .into_result(&self.diag())
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Number of the current row within the whole result set, starting at `1`. Reads the
+    /// read-only `SQL_ATTR_ROW_NUMBER` statement attribute.
+    ///
+    /// `None` if the row number can not be determined, e.g. because no row has been fetched yet,
+    /// or if the driver does not support this attribute. Many drivers only know the row number
+    /// for scrollable cursors, see `Statement::set_cursor_type`.
+    pub fn row_number(&self) -> Result<Option<usize>> {
+        match self.raii.get_attr(ffi::SQL_ATTR_ROW_NUMBER).into_result(&self.diag()) {
+            Ok(0) => Ok(None),
+            Ok(row) => Ok(Some(row as usize)),
+            // `HY092` is reported for attributes unknown to the driver
+            Err(ref e) if e.is_unsupported() || &e.get_raw_state()[..5] == b"HY092" => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    pub(super) fn set_attr(
+        &mut self,
+        attribute: ffi::SQLINTEGER,
+        value: ffi::SQLULEN,
+    ) -> Return<()> {
+        match unsafe {
+            ffi::SQLSetStmtAttrInteger(self.handle(), attribute, value as ffi::SQLPOINTER, 0)
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLSetStmtAttr returned unexpected result: {:?}", r),
+        }
+    }
+
+    pub(super) fn get_attr(&self, attribute: ffi::SQLINTEGER) -> Return<ffi::SQLULEN> {
+        let mut value: ffi::SQLULEN = 0;
+        match unsafe {
+            ffi::SQLGetStmtAttr(
+                self.handle(),
+                attribute,
+                &mut value as *mut ffi::SQLULEN as ffi::SQLPOINTER,
+                0,
+                null_mut(),
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(value),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(value),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLGetStmtAttr returned unexpected result: {:?}", r),
+        }
+    }
+}
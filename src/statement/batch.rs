@@ -0,0 +1,162 @@
+//! Executing a batch of SQL statements at once, see `Statement::exec_direct_batch`
+use super::{Allocated, Executed, HasResult, NoResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use {ffi, DiagnosticRecord, Handle, Raii, Result, Return};
+
+/// One result of a batch, returned by `BatchExecuted::next_result`
+pub enum BatchResult<'s, 'a: 's, 'b: 's, AC: AutocommitMode + 's> {
+    /// A result set, e.g. of a `SELECT` statement. Its rows can be fetched using the statement,
+    /// until the next result is requested.
+    Data(&'s mut Statement<'a, 'b, Executed, HasResult, AC>),
+    /// A statement without result set, e.g. `INSERT`, together with the number of affected rows,
+    /// if the driver knows it
+    NoData(Option<ffi::SQLLEN>),
+}
+
+impl<'s, 'a: 's, 'b: 's, AC: AutocommitMode + 's> fmt::Debug for BatchResult<'s, 'a, 'b, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BatchResult::Data(ref stmt) => f.debug_tuple("Data").field(stmt).finish(),
+            BatchResult::NoData(ref rows) => f.debug_tuple("NoData").field(rows).finish(),
+        }
+    }
+}
+
+/// A batch of SQL statements, which has been submitted by `Statement::exec_direct_batch`. Yields
+/// the result of each statement of the batch in turn.
+///
+/// Statements of the batch which fail do not end the iteration. Their errors are collected
+/// instead and can be inspected using `errors`. Whether the statements following a failed one
+/// are executed at all is up to the data source.
+#[derive(Debug)]
+pub struct BatchExecuted<'a, 'b, AC: AutocommitMode> {
+    stmt: Statement<'a, 'b, Executed, HasResult, AC>,
+    state: BatchState,
+    errors: Vec<DiagnosticRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BatchState {
+    /// The result of the first statement is available, since it is produced by `SQLExecDirect`
+    First,
+    /// `SQLMoreResults` moves on to the next result
+    Next,
+    Done,
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    /// Executes several SQL statements, separated by `;`, with a single call to `SQLExecDirect`.
+    /// Their results are retrieved one after another using `BatchExecuted::next_result`.
+    ///
+    /// Not every driver supports batches. Some execute only the first statement, others fail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let mut batch = stmt.exec_direct_batch(
+    ///     "UPDATE MOVIES SET YEAR = YEAR; SELECT TITLE FROM MOVIES",
+    /// )?;
+    /// while let Some(result) = batch.next_result()? {
+    ///     match result {
+    ///         BatchResult::Data(stmt) => {
+    ///             while let Some(mut cursor) = stmt.fetch()? {
+    ///                 println!("{:?}", cursor.get_data::<String>(1)?);
+    ///             }
+    ///         }
+    ///         BatchResult::NoData(rows) => println!("{:?} rows affected", rows),
+    ///     }
+    /// }
+    /// for error in batch.errors() {
+    ///     println!("{}", error);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn exec_direct_batch(mut self, sql: &str) -> Result<BatchExecuted<'a, 'b, AC>> {
+        let mut errors = Vec::new();
+        // `SQL_NO_DATA`, e.g. of an `UPDATE` without matching rows, is a result as well
+        let state = {
+            let _span = self.instrumentation.exec_direct(sql);
+            match self.raii.exec_direct(sql).into_result(&self.diag()) {
+                Ok(_) => BatchState::First,
+                Err(e) => {
+                    errors.push(e);
+                    BatchState::Next
+                }
+            }
+        };
+        Ok(BatchExecuted {
+            stmt: self.with_result(),
+            state,
+            errors,
+        })
+    }
+}
+
+impl<'a, 'b, AC: AutocommitMode> BatchExecuted<'a, 'b, AC> {
+    /// Result of the next statement of the batch, or `None` once all results have been
+    /// retrieved. Any rows of a previous result set, which have not been fetched yet, are
+    /// discarded. Wraps `SQLMoreResults`.
+    ///
+    /// Errors of failing statements are added to `errors`, rather than returned, and the
+    /// iteration continues with the next statement. Only errors which leave the results of the
+    /// batch inaccessible, like failing to describe a result, are returned. If `SQLMoreResults`
+    /// fails twice in a row, the batch is considered done.
+    pub fn next_result<'s>(&'s mut self) -> Result<Option<BatchResult<'s, 'a, 'b, AC>>> {
+        let mut failed = false;
+        loop {
+            match self.state {
+                BatchState::Done => return Ok(None),
+                BatchState::First => break,
+                BatchState::Next => {
+                    match self.stmt.raii.more_results().into_result(&self.stmt.diag()) {
+                        Ok(true) => break,
+                        Ok(false) => self.state = BatchState::Done,
+                        Err(e) => {
+                            self.errors.push(e);
+                            if failed {
+                                self.state = BatchState::Done;
+                            }
+                            failed = true;
+                        }
+                    }
+                }
+            }
+        }
+        self.state = BatchState::Next;
+        if self.stmt.result_cols()? > 0 {
+            Ok(Some(BatchResult::Data(&mut self.stmt)))
+        } else {
+            Ok(Some(BatchResult::NoData(self.stmt.affected_rows()?)))
+        }
+    }
+
+    /// Errors of the statements of the batch which failed so far, in the order of execution
+    pub fn errors(&self) -> &[DiagnosticRecord] {
+        &self.errors
+    }
+
+    /// Discards any results which have not been retrieved yet, so the statement can be used to
+    /// execute another query. Wraps `SQLFreeStmt` with `SQL_CLOSE`.
+    pub fn close(mut self) -> Result<Statement<'a, 'b, Executed, NoResult, AC>> {
+        self.stmt.raii.close().into_result(&self.stmt.diag())?;
+        Ok(self.stmt.with_result())
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn close(&mut self) -> Return<()> {
+        match unsafe { ffi::SQLFreeStmt(self.handle(), ffi::SQL_CLOSE) } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLFreeStmt returned unexpected result: {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Binding result set columns to application buffers using `SQLBindCol`
+use super::types::OdbcType;
+use super::{HasResult, SqlDate, SqlGuid, SqlTime, SqlTimestamp, Statement};
+use odbc_safe::AutocommitMode;
+use std::any::TypeId;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Types of fixed size, which can be bound to a result set column using `BoundStatement::bind_col`
+///
+/// # Safety
+///
+/// The driver writes values of the type's C data type directly into the bound buffer. The type
+/// must have the size of that C data type and every bit pattern the driver may write must be a
+/// valid value.
+pub unsafe trait FixedSizedType: for<'a> OdbcType<'a> + 'static {}
+
+unsafe impl FixedSizedType for u8 {}
+unsafe impl FixedSizedType for i8 {}
+unsafe impl FixedSizedType for i16 {}
+unsafe impl FixedSizedType for u16 {}
+unsafe impl FixedSizedType for i32 {}
+unsafe impl FixedSizedType for u32 {}
+unsafe impl FixedSizedType for i64 {}
+unsafe impl FixedSizedType for u64 {}
+unsafe impl FixedSizedType for f32 {}
+unsafe impl FixedSizedType for f64 {}
+unsafe impl FixedSizedType for SqlDate {}
+unsafe impl FixedSizedType for SqlTime {}
+unsafe impl FixedSizedType for SqlTimestamp {}
+unsafe impl FixedSizedType for SqlGuid {}
+
+struct BoundColumn {
+    column_number: u16,
+    value: *mut u8,
+    type_id: TypeId,
+    // Boxed, so the pointer passed to the driver stays valid if `columns` grows
+    indicator: Box<ffi::SQLLEN>,
+}
+
+/// A statement with result set, whose columns are bound to application buffers. Created by
+/// `Statement::into_bound`.
+///
+/// Each call to `fetch_bound` makes the driver write the values of the next row directly into
+/// the bound buffers, which is faster than retrieving them one by one using `Cursor::get_data`.
+/// While bound, the buffers are borrowed by the statement. Their values can be accessed using
+/// `get` and replaced using `set`.
+pub struct BoundStatement<'a, 'b, S, AC: AutocommitMode> {
+    pub(super) stmt: Statement<'a, 'b, S, HasResult, AC>,
+    columns: Vec<BoundColumn>,
+    buffers: PhantomData<&'b mut ()>,
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> fmt::Debug for BoundStatement<'a, 'b, S, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns: Vec<_> = self.columns.iter().map(|c| c.column_number).collect();
+        f.debug_struct("BoundStatement")
+            .field("stmt", &self.stmt)
+            .field("columns", &columns)
+            .finish()
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Turns the statement into a `BoundStatement`, so columns can be bound using `bind_col`.
+    pub fn into_bound(self) -> BoundStatement<'a, 'b, S, AC> {
+        BoundStatement {
+            stmt: self,
+            columns: Vec::new(),
+            buffers: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> BoundStatement<'a, 'b, S, AC> {
+    /// Binds a column of the result set to a buffer. Wraps `SQLBindCol`. Column numbers start at
+    /// `1`. Binding a column again replaces the previous binding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT YEAR FROM MOVIES")? {
+    ///     let mut year = 0i32;
+    ///     let mut stmt = stmt.into_bound().bind_col(1, &mut year)?;
+    ///     while stmt.fetch_bound()? {
+    ///         println!("{:?}", stmt.get::<i32>(1));
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bind_col<'c, T>(
+        mut self,
+        column_number: u16,
+        value: &'c mut T,
+    ) -> Result<BoundStatement<'a, 'c, S, AC>>
+    where
+        T: FixedSizedType,
+        'b: 'c,
+    {
+        let mut indicator = Box::new(0);
+        let value = value as *mut T as *mut u8;
+        self.stmt
+            .raii
+            .bind_col::<T>(column_number, value, &mut *indicator)
+            .into_result(&self.stmt.diag())?;
+        self.columns.retain(|c| c.column_number != column_number);
+        self.columns.push(BoundColumn {
+            column_number,
+            value,
+            type_id: TypeId::of::<T>(),
+            indicator,
+        });
+        Ok(BoundStatement {
+            stmt: self.stmt,
+            columns: self.columns,
+            buffers: PhantomData,
+        })
+    }
+
+    /// Fetches the next row into the bound buffers. Returns `false` if there are no more rows.
+    pub fn fetch_bound(&mut self) -> Result<bool> {
+        let has_row = self.stmt.raii.fetch().into_result(&self.stmt.diag())?;
+        self.stmt.instrumentation.fetched(has_row);
+        Ok(has_row)
+    }
+
+    /// Value of a bound column in the row fetched last, or `None` if it is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// If the column is not bound, or bound to a buffer of a different type.
+    pub fn get<T: FixedSizedType>(&self, column_number: u16) -> Option<&T> {
+        let column = self.column(column_number);
+        assert!(
+            column.type_id == TypeId::of::<T>(),
+            "column {} is bound to a buffer of a different type",
+            column_number
+        );
+        if *column.indicator == ffi::SQL_NULL_DATA {
+            None
+        } else {
+            Some(unsafe { &*(column.value as *const T) })
+        }
+    }
+
+    /// Replaces the value of a bound column, e.g. before writing the row to the data source with
+    /// `set_pos` and `SetPosOperation::Update`. `None` marks the value as `NULL`, leaving the
+    /// buffer itself untouched.
+    ///
+    /// # Panics
+    ///
+    /// If the column is not bound, or bound to a buffer of a different type.
+    pub fn set<T: FixedSizedType>(&mut self, column_number: u16, value: Option<T>) {
+        let column = self.column_mut(column_number);
+        assert!(
+            column.type_id == TypeId::of::<T>(),
+            "column {} is bound to a buffer of a different type",
+            column_number
+        );
+        match value {
+            Some(value) => {
+                unsafe { ptr::write(column.value as *mut T, value) };
+                *column.indicator = size_of::<T>() as ffi::SQLLEN;
+            }
+            None => *column.indicator = ffi::SQL_NULL_DATA,
+        }
+    }
+
+    /// `true` if the value of a bound column in the row fetched last is `NULL`, according to the
+    /// indicator the driver has written along with the value. The bound buffer itself is left
+    /// untouched by the driver in that case, so it still holds whatever it held before.
+    ///
+    /// # Panics
+    ///
+    /// If the column is not bound.
+    pub fn is_null(&self, column_number: u16) -> bool {
+        *self.column(column_number).indicator == ffi::SQL_NULL_DATA
+    }
+
+    /// Releases all column buffers, returning the statement. Wraps `SQLFreeStmt` with
+    /// `SQL_UNBIND`.
+    pub fn unbind(mut self) -> Result<Statement<'a, 'b, S, HasResult, AC>> {
+        self.stmt.raii.unbind().into_result(&self.stmt.diag())?;
+        Ok(self.stmt)
+    }
+
+    fn column(&self, column_number: u16) -> &BoundColumn {
+        self.columns
+            .iter()
+            .find(|c| c.column_number == column_number)
+            .unwrap_or_else(|| panic!("column {} is not bound", column_number))
+    }
+
+    fn column_mut(&mut self, column_number: u16) -> &mut BoundColumn {
+        self.columns
+            .iter_mut()
+            .find(|c| c.column_number == column_number)
+            .unwrap_or_else(|| panic!("column {} is not bound", column_number))
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    pub(super) fn bind_col<T>(
+        &mut self,
+        column_number: u16,
+        value: *mut u8,
+        indicator: *mut ffi::SQLLEN,
+    ) -> Return<()>
+    where
+        T: FixedSizedType,
+    {
+        match unsafe {
+            ffi::SQLBindCol(
+                self.handle(),
+                column_number,
+                T::c_data_type(),
+                value as ffi::SQLPOINTER,
+                size_of::<T>() as ffi::SQLLEN,
+                indicator,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLBindCol returned unexpected result: {:?}", r),
+        }
+    }
+
+    pub(super) fn unbind(&mut self) -> Return<()> {
+        match unsafe { ffi::SQLFreeStmt(self.handle(), ffi::SQL_UNBIND) } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLFreeStmt returned unexpected result: {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Bulk insertion, update, deletion and fetching of rows, using `SQLBulkOperations`
+use super::rowset::RowsetStatement;
+use super::{poll_while_executing, HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Operation performed by `Statement::bulk_operations`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BulkOperation {
+    /// Inserts the rows held by the bound column buffers (`SQL_ADD`)
+    AddByBookmark,
+    /// Updates the rows identified by the bookmarks in the bound bookmark column with the values
+    /// of the bound column buffers (`SQL_UPDATE_BY_BOOKMARK`)
+    UpdateByBookmark,
+    /// Deletes the rows identified by the bookmarks in the bound bookmark column
+    /// (`SQL_DELETE_BY_BOOKMARK`)
+    DeleteByBookmark,
+    /// Fetches the rows identified by the bookmarks in the bound bookmark column into the bound
+    /// column buffers (`SQL_FETCH_BY_BOOKMARK`)
+    FetchByBookmark,
+}
+
+impl fmt::Display for BulkOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BulkOperation::AddByBookmark => "SQL_ADD",
+            BulkOperation::UpdateByBookmark => "SQL_UPDATE_BY_BOOKMARK",
+            BulkOperation::DeleteByBookmark => "SQL_DELETE_BY_BOOKMARK",
+            BulkOperation::FetchByBookmark => "SQL_FETCH_BY_BOOKMARK",
+        })
+    }
+}
+
+impl BulkOperation {
+    fn operation(&self) -> ffi::SqlBulkOperation {
+        match *self {
+            BulkOperation::AddByBookmark => ffi::SQL_ADD,
+            BulkOperation::UpdateByBookmark => ffi::SQL_UPDATE_BY_BOOKMARK,
+            BulkOperation::DeleteByBookmark => ffi::SQL_DELETE_BY_BOOKMARK,
+            BulkOperation::FetchByBookmark => ffi::SQL_FETCH_BY_BOOKMARK,
+        }
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Performs `operation` on as many rows as the row array size, which is `1` unless the
+    /// statement is part of a `RowsetStatement`. Wraps `SQLBulkOperations`.
+    ///
+    /// This is the ODBC 3 replacement for the positioned bulk operations of `set_pos`. The column
+    /// buffers must have been bound with `SQLBindCol` and filled with an array of row values
+    /// beforehand, see `RowsetStatement::bulk_operations`. All operations but `AddByBookmark`
+    /// identify the rows by bookmark, which requires `StatementAttr::UseBookmarks` to be set
+    /// before executing the statement and column `0` to be bound, see
+    /// `RowsetStatement::bind_bookmarks`. As with `set_pos`, the cursor must support
+    /// modifications, see `StatementAttr::CursorType` and `StatementAttr::Concurrency`.
+    pub fn bulk_operations(&mut self, operation: BulkOperation) -> Result<()> {
+        self.raii
+            .bulk_operations(operation.operation())
+            .into_result(&self.diag())
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> RowsetStatement<'a, 'b, S, AC> {
+    /// Performs `operation` on the rows held by the bound column buffers. See
+    /// `Statement::bulk_operations`.
+    ///
+    /// Every row of the buffers is used, up to `row_array_size`, regardless of how many rows the
+    /// last `fetch_rowset` returned. Write the values with `set`, `set_text` and `set_bookmark`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_attr(StatementAttr::CursorType(CursorType::Static))?;
+    /// stmt.set_attr(StatementAttr::Concurrency(Concurrency::Lock))?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES")? {
+    ///     let mut stmt = stmt
+    ///         .into_rowset(2)?
+    ///         .bind_text_col(1, 255)?
+    ///         .bind_col::<i32>(2)?;
+    ///     stmt.set_text(0, 1, Some("Metropolis"));
+    ///     stmt.set(0, 2, Some(1927));
+    ///     stmt.set_text(1, 1, Some("Nosferatu"));
+    ///     stmt.set(1, 2, Some(1922));
+    ///     stmt.bulk_operations(BulkOperation::AddByBookmark)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_operations(&mut self, operation: BulkOperation) -> Result<()> {
+        self.stmt.bulk_operations(operation)
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn bulk_operations(&mut self, operation: ffi::SqlBulkOperation) -> Return<()> {
+        match poll_while_executing(|| unsafe { ffi::SQLBulkOperations(self.handle(), operation) }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            ffi::SQL_NEED_DATA => Return::NeedData,
+            r => panic!("SQLBulkOperations returned unexpected result: {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Cancelling statements, possibly from another thread
+use super::Statement;
+use odbc_safe::AutocommitMode;
+use std::sync::{Arc, Mutex, MutexGuard};
+use {ffi, safe, Result, Return};
+
+/// Statement handle shared with `CancelHandle`s. `None` once the statement has been dropped.
+type Shared = Arc<Mutex<Option<RawStatement>>>;
+
+/// Raw statement handle, as held by a `CancelHandle`.
+struct RawStatement(ffi::SQLHSTMT);
+
+// ODBC handles may be used from any thread. The driver manager takes care of synchronization.
+unsafe impl Send for RawStatement {}
+
+unsafe impl safe::Handle for RawStatement {
+    const HANDLE_TYPE: ffi::HandleType = ffi::SQL_HANDLE_STMT;
+
+    fn handle(&self) -> ffi::SQLHANDLE {
+        self.0 as ffi::SQLHANDLE
+    }
+}
+
+impl RawStatement {
+    fn cancel(&self) -> Return<()> {
+        match unsafe { ffi::SQLCancel(self.0) } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLCancel returned unexpected result: {:?}", r),
+        }
+    }
+}
+
+fn lock(shared: &Shared) -> MutexGuard<'_, Option<RawStatement>> {
+    // The lock is never held while panicking, but there is no harm in ignoring poison either.
+    shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Owned by a `Statement`. Invalidates all `CancelHandle`s of the statement, before its handle is
+/// freed.
+pub(super) struct CancelGuard(Shared);
+
+impl CancelGuard {
+    pub(super) fn new(handle: ffi::SQLHSTMT) -> CancelGuard {
+        CancelGuard(Arc::new(Mutex::new(Some(RawStatement(handle)))))
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        *lock(&self.0) = None;
+    }
+}
+
+/// Allows cancelling a statement from another thread. Obtained via `Statement::cancel_handle`.
+///
+/// `CancelHandle` is `Send` and `Sync` and may outlive the statement it has been obtained from.
+/// Once that statement is dropped, `cancel` does nothing.
+#[derive(Clone)]
+pub struct CancelHandle(Shared);
+
+impl CancelHandle {
+    /// Cancels the processing of the statement. Wraps `SQLCancel`.
+    ///
+    /// Cancellation is advisory. The driver may not honor it immediately, or at all. If it does,
+    /// the function currently executing on the statement, e.g. `exec_direct` or `fetch`, returns
+    /// an error with state `HY008` (Operation canceled). If no function is executing, any open
+    /// result set is closed, like `close_cursor` would do.
+    pub fn cancel(&self) -> Result<()> {
+        match *lock(&self.0) {
+            Some(ref stmt) => stmt.cancel().into_result(stmt),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Cancels the processing of this statement. Wraps `SQLCancel`.
+    ///
+    /// Cancellation is advisory, see `CancelHandle::cancel`. To cancel a statement blocked in
+    /// another thread, obtain a `CancelHandle` using `cancel_handle` first.
+    pub fn cancel(&self) -> Result<()> {
+        self.cancel_handle().cancel()
+    }
+
+    /// Returns a handle which can be sent to another thread in order to cancel this statement
+    /// while it is executing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # use std::{thread, time::Duration};
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let cancel = stmt.cancel_handle();
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(10));
+    ///     cancel.cancel()
+    /// });
+    /// stmt.exec_direct("SELECT TITLE FROM MOVIES")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle(self.cancel.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ptr::null_mut;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn cancel_handle_is_send_and_sync() {
+        assert_send_sync::<CancelHandle>();
+    }
+
+    #[test]
+    fn cancel_after_statement_is_dropped() {
+        let guard = CancelGuard::new(null_mut());
+        let handle = CancelHandle(guard.0.clone());
+        drop(guard);
+        assert!(handle.cancel().is_ok());
+    }
+}
@@ -0,0 +1,742 @@
+//! Catalog functions, returning information about the data source's schema as result sets
+use super::{poll_while_executing, Allocated, Executed, HasResult, NoResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::borrow::Cow;
+use std::fmt;
+use std::ptr::null;
+use {ffi, Connection, Handle, Raii, Result, Return};
+
+/// Argument of a catalog function. `None` is passed to the driver as null pointer.
+struct CatalogArg<'a>(Option<Cow<'a, [u8]>>);
+
+impl<'a> CatalogArg<'a> {
+    fn new(text: Option<&'a str>) -> CatalogArg<'a> {
+        CatalogArg(text.map(|text| unsafe { ::environment::DB_ENCODING }.encode(text).0))
+    }
+
+    fn ptr(&self) -> *const ffi::SQLCHAR {
+        match self.0 {
+            Some(ref bytes) => bytes.as_ptr(),
+            None => null(),
+        }
+    }
+
+    fn len(&self) -> ffi::SQLSMALLINT {
+        match self.0 {
+            Some(ref bytes) => bytes.len() as ffi::SQLSMALLINT,
+            None => 0,
+        }
+    }
+}
+
+/// Type of columns returned by `Statement::special_columns`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecialColumnType {
+    /// The optimal column or set of columns, which uniquely identifies a row in the table
+    /// (`SQL_BEST_ROWID`)
+    BestRowId,
+    /// The columns automatically updated by the data source whenever any value in the row is
+    /// updated (`SQL_ROWVER`)
+    RowVersion,
+}
+
+impl fmt::Display for SpecialColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            SpecialColumnType::BestRowId => "SQL_BEST_ROWID",
+            SpecialColumnType::RowVersion => "SQL_ROWVER",
+        })
+    }
+}
+
+/// Minimum required scope of the row id returned by `Statement::special_columns`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RowIdScope {
+    /// Valid only while positioned on that row (`SQL_SCOPE_CURROW`)
+    CurrentRow,
+    /// Valid for the duration of the current transaction (`SQL_SCOPE_TRANSACTION`)
+    Transaction,
+    /// Valid for the duration of the session, across transaction boundaries
+    /// (`SQL_SCOPE_SESSION`)
+    Session,
+}
+
+impl fmt::Display for RowIdScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            RowIdScope::CurrentRow => "SQL_SCOPE_CURROW",
+            RowIdScope::Transaction => "SQL_SCOPE_TRANSACTION",
+            RowIdScope::Session => "SQL_SCOPE_SESSION",
+        })
+    }
+}
+
+/// Whether `Statement::special_columns` may return columns that can have `NULL` values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NullableOption {
+    /// Exclude special columns that can have `NULL` values (`SQL_NO_NULLS`)
+    NoNulls,
+    /// Return special columns even if they can have `NULL` values (`SQL_NULLABLE`)
+    Nullable,
+}
+
+impl fmt::Display for NullableOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            NullableOption::NoNulls => "SQL_NO_NULLS",
+            NullableOption::Nullable => "SQL_NULLABLE",
+        })
+    }
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    pub fn tables(self, catalog_name: &String, schema_name: &String, table_name: &String, table_type: &String) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.tables_str(catalog_name.as_str(), schema_name.as_str(), table_name.as_str(), table_type.as_str())
+    }
+
+    pub fn tables_str(self, catalog_name: &str, schema_name: &str, table_name: &str, table_type: &str) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.tables_opt_str(Option::Some(catalog_name), Option::Some(schema_name), Option::Some(table_name), table_type)
+    }
+
+    pub fn tables_opt_str(self, catalog_name: Option<&str>, schema_name: Option<&str>, table_name:Option<&str>, table_type: &str) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.tables_filtered(catalog_name, schema_name, table_name, Some(table_type))
+    }
+
+    /// Returns the list of table, catalog, or schema names, and table types, stored in a specific
+    /// data source.
+    ///
+    /// Wraps [SQLTables][1]. Each of the arguments may be `None`, in which case it does not
+    /// restrict the result. `table_type` is a comma separated list of types, e.g.
+    /// `"TABLE,VIEW"`.
+    ///
+    /// The result set holds the columns `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME`, `TABLE_TYPE` and
+    /// `REMARKS`.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqltables-function
+    pub fn tables_filtered(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        table_type: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .tables(catalog_name, schema_name, table_name, table_type)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the list of column names in specified tables.
+    ///
+    /// Wraps [SQLColumns][1]. Each of the arguments may be `None`, in which case it does not
+    /// restrict the result. Schema, table and column names are treated as search patterns.
+    ///
+    /// The result set holds the 18 columns defined by the ODBC standard, starting with
+    /// `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME`, `COLUMN_NAME`, `DATA_TYPE` and `TYPE_NAME`.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlcolumns-function
+    pub fn columns(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .columns(catalog_name, schema_name, table_name, column_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the column names that make up the primary key of a table.
+    ///
+    /// Wraps [SQLPrimaryKeys][1]. `catalog_name` and `schema_name` may be `None`, in which case
+    /// they do not restrict the result. None of the arguments are treated as search patterns.
+    ///
+    /// The result set holds the columns `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME`, `COLUMN_NAME`,
+    /// `KEY_SEQ` and `PK_NAME`, ordered by `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME` and `KEY_SEQ`.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlprimarykeys-function
+    pub fn primary_keys(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .primary_keys(catalog_name, schema_name, table_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the foreign keys of a table, or the foreign keys in other tables referring to the
+    /// primary key of a table.
+    ///
+    /// Wraps [SQLForeignKeys][1]. If `pk_table_name` is specified, the result contains the foreign
+    /// keys referring to the primary key of that table. If `fk_table_name` is specified, the
+    /// result contains the foreign keys of that table. If both are specified, the result contains
+    /// the foreign keys of `fk_table_name` referring to the primary key of `pk_table_name`. If
+    /// both are `None`, all foreign keys in the catalog are returned, provided the driver supports
+    /// it. Catalog and schema names which are `None` do not restrict the result.
+    ///
+    /// The result set holds the 14 columns defined by the ODBC standard: `PKTABLE_CAT`,
+    /// `PKTABLE_SCHEM`, `PKTABLE_NAME`, `PKCOLUMN_NAME`, `FKTABLE_CAT`, `FKTABLE_SCHEM`,
+    /// `FKTABLE_NAME`, `FKCOLUMN_NAME`, `KEY_SEQ`, `UPDATE_RULE`, `DELETE_RULE`, `FK_NAME`,
+    /// `PK_NAME` and `DEFERRABILITY`.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlforeignkeys-function
+    pub fn foreign_keys(
+        mut self,
+        pk_catalog_name: Option<&str>,
+        pk_schema_name: Option<&str>,
+        pk_table_name: Option<&str>,
+        fk_catalog_name: Option<&str>,
+        fk_schema_name: Option<&str>,
+        fk_table_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .foreign_keys(
+                pk_catalog_name,
+                pk_schema_name,
+                pk_table_name,
+                fk_catalog_name,
+                fk_schema_name,
+                fk_table_name,
+            )
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns statistics about a single table and the indexes associated with it.
+    ///
+    /// Wraps [SQLStatistics][1]. `catalog_name` and `schema_name` may be `None`, in which case
+    /// they do not restrict the result. If `unique_only` is `true` only unique indexes are
+    /// returned (`SQL_INDEX_UNIQUE`), otherwise all of them (`SQL_INDEX_ALL`). If `reserved` is
+    /// `true` the driver is asked to retrieve `CARDINALITY` and `PAGES` unconditionally
+    /// (`SQL_ENSURE`), otherwise only if they are readily available (`SQL_QUICK`).
+    ///
+    /// The result set holds the 13 columns defined by the ODBC standard: `TABLE_CAT`,
+    /// `TABLE_SCHEM`, `TABLE_NAME`, `NON_UNIQUE`, `INDEX_QUALIFIER`, `INDEX_NAME`, `TYPE`,
+    /// `ORDINAL_POSITION`, `COLUMN_NAME`, `ASC_OR_DESC`, `CARDINALITY`, `PAGES` and
+    /// `FILTER_CONDITION`. A row with `TYPE` `SQL_TABLE_STAT` (0) describes the table itself,
+    /// all other rows describe one column of an index each.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlstatistics-function
+    pub fn statistics(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        unique_only: bool,
+        reserved: bool,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        let unique = if unique_only {
+            ffi::SQL_INDEX_UNIQUE
+        } else {
+            ffi::SQL_INDEX_ALL
+        };
+        let reserved = if reserved {
+            ffi::SQL_ENSURE
+        } else {
+            ffi::SQL_QUICK
+        };
+        self.raii
+            .statistics(catalog_name, schema_name, table_name, unique, reserved)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the list of tables and the privileges associated with each table.
+    ///
+    /// Wraps [SQLTablePrivileges][1]. Each of the arguments may be `None`, in which case it does
+    /// not restrict the result. Schema and table names are treated as search patterns.
+    ///
+    /// The result set holds the columns `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME`, `GRANTOR`,
+    /// `GRANTEE`, `PRIVILEGE` and `IS_GRANTABLE`, with one row per privilege.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqltableprivileges-function
+    pub fn table_privileges(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .table_privileges(catalog_name, schema_name, table_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the list of columns and the privileges associated with each column of a table.
+    ///
+    /// Wraps [SQLColumnPrivileges][1]. `catalog_name`, `schema_name` and `column_name` may be
+    /// `None`, in which case they do not restrict the result. Only the column name is treated as
+    /// search pattern.
+    ///
+    /// The result set holds the columns `TABLE_CAT`, `TABLE_SCHEM`, `TABLE_NAME`, `COLUMN_NAME`,
+    /// `GRANTOR`, `GRANTEE`, `PRIVILEGE` and `IS_GRANTABLE`, with one row per privilege.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlcolumnprivileges-function
+    pub fn column_privileges(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        column_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .column_privileges(catalog_name, schema_name, table_name, column_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the optimal set of columns that uniquely identifies a row in the table, or the
+    /// columns that are automatically updated when any value in the row is updated.
+    ///
+    /// Wraps [SQLSpecialColumns][1]. `catalog_name` and `schema_name` may be `None`, in which case
+    /// they do not restrict the result. None of the arguments are treated as search patterns.
+    ///
+    /// The result set holds the columns `SCOPE`, `COLUMN_NAME`, `DATA_TYPE`, `TYPE_NAME`,
+    /// `COLUMN_SIZE`, `BUFFER_LENGTH`, `DECIMAL_DIGITS` and `PSEUDO_COLUMN`. It is empty if no
+    /// such columns exist, e.g. because there is no unique index on the table.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlspecialcolumns-function
+    pub fn special_columns(
+        mut self,
+        id_type: SpecialColumnType,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        scope: RowIdScope,
+        nullable: NullableOption,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        let id_type = match id_type {
+            SpecialColumnType::BestRowId => ffi::SQL_BEST_ROWID,
+            SpecialColumnType::RowVersion => ffi::SQL_ROWVER,
+        };
+        let scope = match scope {
+            RowIdScope::CurrentRow => ffi::SQL_SCOPE_CURROW,
+            RowIdScope::Transaction => ffi::SQL_SCOPE_TRANSACTION,
+            RowIdScope::Session => ffi::SQL_SCOPE_SESSION,
+        };
+        let nullable = match nullable {
+            NullableOption::NoNulls => ffi::SQL_NO_NULLS,
+            NullableOption::Nullable => ffi::SQL_NULLABLE,
+        } as ffi::SQLUSMALLINT;
+        self.raii
+            .special_columns(id_type, catalog_name, schema_name, table_name, scope, nullable)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the list of procedure names stored in a specific data source.
+    ///
+    /// Wraps [SQLProcedures][1]. Each of the arguments may be `None`, in which case it does not
+    /// restrict the result. Schema and procedure names are treated as search patterns.
+    ///
+    /// The result set holds the columns `PROCEDURE_CAT`, `PROCEDURE_SCHEM`, `PROCEDURE_NAME`,
+    /// `NUM_INPUT_PARAMS`, `NUM_OUTPUT_PARAMS`, `NUM_RESULT_SETS`, `REMARKS` and
+    /// `PROCEDURE_TYPE`. The `NUM_*` columns are reserved for future use and should not be relied
+    /// on.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlprocedures-function
+    pub fn procedures(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        proc_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .procedures(catalog_name, schema_name, proc_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns the input and output parameters, as well as the columns of the result set, of
+    /// the specified procedures.
+    ///
+    /// Wraps [SQLProcedureColumns][1]. Each of the arguments may be `None`, in which case it
+    /// does not restrict the result. Schema, procedure and column names are treated as search
+    /// patterns.
+    ///
+    /// The result set holds the 19 columns defined by the ODBC standard, starting with
+    /// `PROCEDURE_CAT`, `PROCEDURE_SCHEM`, `PROCEDURE_NAME`, `COLUMN_NAME`, `COLUMN_TYPE`,
+    /// `DATA_TYPE`, `TYPE_NAME`, `COLUMN_SIZE`, `BUFFER_LENGTH`, `DECIMAL_DIGITS` and
+    /// `NULLABLE`. `COLUMN_TYPE` holds one of the values of `ffi::InputOutput`, distinguishing
+    /// input, output and input/output parameters, return values and result set columns.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlprocedurecolumns-function
+    pub fn procedure_columns(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        proc_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .procedure_columns(catalog_name, schema_name, proc_name, column_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Returns information about the data types supported by the data source.
+    ///
+    /// Wraps [SQLGetTypeInfo][1]. If `data_type` is `None` all types are listed, otherwise only
+    /// those with the given SQL data type. Drivers may map several type names to the same SQL
+    /// data type, e.g. `INTEGER` and `INT`.
+    ///
+    /// The result set holds the 19 columns defined by the ODBC standard, starting with
+    /// `TYPE_NAME`, `DATA_TYPE`, `COLUMN_SIZE`, `LITERAL_PREFIX`, `LITERAL_SUFFIX` and
+    /// `CREATE_PARAMS`. It is ordered by `DATA_TYPE` and then by how closely the type maps to it.
+    /// [1]: https://docs.microsoft.com/en-us/sql/odbc/reference/syntax/sqlgettypeinfo-function
+    pub fn type_info(
+        mut self,
+        data_type: Option<ffi::SqlDataType>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        // `SQL_ALL_TYPES` shares its value 0 with `SQL_UNKNOWN_TYPE`
+        let data_type = data_type.unwrap_or(ffi::SQL_UNKNOWN_TYPE);
+        self.raii.type_info(data_type).into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+}
+
+impl<'env, AC: AutocommitMode> Connection<'env, AC> {
+    /// Number of tables in the data source, or in the schema `schema_name`, which is treated as
+    /// a search pattern. Counts the rows returned by `SQLTables` for the table type `TABLE`, so
+    /// views and system tables are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// println!("{} tables", conn.tables_count(None)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tables_count(&self, schema_name: Option<&str>) -> Result<usize> {
+        let mut stmt = Statement::with_parent(self)?.tables_filtered(
+            None,
+            schema_name,
+            None,
+            Some("TABLE"),
+        )?;
+        let mut count = 0;
+        while stmt.fetch()?.is_some() {
+            count += 1;
+        }
+        stmt.close_cursor()?;
+        Ok(count)
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn tables(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        table_type: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(table_name);
+        let table_type = CatalogArg::new(table_type);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLTables(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                table_type.ptr(),
+                table_type.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLTables returned: {:?}", r),
+        }
+    }
+
+    fn columns(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(table_name);
+        let column = CatalogArg::new(column_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLColumns(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                column.ptr(),
+                column.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLColumns returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn primary_keys(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(Some(table_name));
+        match poll_while_executing(|| unsafe {
+            ffi::SQLPrimaryKeys(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLPrimaryKeys returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn foreign_keys(
+        &mut self,
+        pk_catalog_name: Option<&str>,
+        pk_schema_name: Option<&str>,
+        pk_table_name: Option<&str>,
+        fk_catalog_name: Option<&str>,
+        fk_schema_name: Option<&str>,
+        fk_table_name: Option<&str>,
+    ) -> Return<()> {
+        let pk_catalog = CatalogArg::new(pk_catalog_name);
+        let pk_schema = CatalogArg::new(pk_schema_name);
+        let pk_table = CatalogArg::new(pk_table_name);
+        let fk_catalog = CatalogArg::new(fk_catalog_name);
+        let fk_schema = CatalogArg::new(fk_schema_name);
+        let fk_table = CatalogArg::new(fk_table_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLForeignKeys(
+                self.handle(),
+                pk_catalog.ptr(),
+                pk_catalog.len(),
+                pk_schema.ptr(),
+                pk_schema.len(),
+                pk_table.ptr(),
+                pk_table.len(),
+                fk_catalog.ptr(),
+                fk_catalog.len(),
+                fk_schema.ptr(),
+                fk_schema.len(),
+                fk_table.ptr(),
+                fk_table.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLForeignKeys returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn statistics(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        unique: ffi::SQLUSMALLINT,
+        reserved: ffi::SQLUSMALLINT,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(Some(table_name));
+        match poll_while_executing(|| unsafe {
+            ffi::SQLStatistics(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                unique,
+                reserved,
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLStatistics returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn table_privileges(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(table_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLTablePrivileges(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLTablePrivileges returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn column_privileges(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        column_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(Some(table_name));
+        let column = CatalogArg::new(column_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLColumnPrivileges(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                column.ptr(),
+                column.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLColumnPrivileges returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn special_columns(
+        &mut self,
+        id_type: ffi::SQLUSMALLINT,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: &str,
+        scope: ffi::SQLUSMALLINT,
+        nullable: ffi::SQLUSMALLINT,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let table = CatalogArg::new(Some(table_name));
+        match poll_while_executing(|| unsafe {
+            ffi::SQLSpecialColumns(
+                self.handle(),
+                id_type,
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                scope,
+                nullable,
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLSpecialColumns returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn procedures(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        proc_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let procedure = CatalogArg::new(proc_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLProcedures(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                procedure.ptr(),
+                procedure.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLProcedures returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn procedure_columns(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        proc_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = CatalogArg::new(catalog_name);
+        let schema = CatalogArg::new(schema_name);
+        let procedure = CatalogArg::new(proc_name);
+        let column = CatalogArg::new(column_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLProcedureColumns(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                procedure.ptr(),
+                procedure.len(),
+                column.ptr(),
+                column.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLProcedureColumns returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn type_info(&mut self, data_type: ffi::SqlDataType) -> Return<()> {
+        match poll_while_executing(|| unsafe { ffi::SQLGetTypeInfo(self.handle(), data_type) }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLGetTypeInfo returned unexpected result: {:?}", r),
+        }
+    }
+}
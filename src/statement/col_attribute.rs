@@ -0,0 +1,253 @@
+//! Column metadata of the implementation row descriptor, queried with `SQLColAttribute`
+use super::{poll_while_executing, HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::ptr::null_mut;
+use result::with_growing_buffer;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Column attribute which can be queried using `Statement::col_attribute`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColAttr {
+    /// Title of the column, e.g. the alias given with `AS`, or else its name (`SQL_DESC_LABEL`).
+    /// `String`
+    Label,
+    /// Name of the column in the table it originates from, empty if unknown
+    /// (`SQL_DESC_BASE_COLUMN_NAME`). `String`
+    BaseColumnName,
+    /// Name of the table the column originates from, empty if unknown
+    /// (`SQL_DESC_BASE_TABLE_NAME`). `String`
+    BaseTableName,
+    /// Schema of the table the column originates from, empty if unknown or not applicable
+    /// (`SQL_DESC_SCHEMA_NAME`). `String`
+    SchemaName,
+    /// Catalog of the table the column originates from, empty if unknown or not applicable
+    /// (`SQL_DESC_CATALOG_NAME`). `String`
+    CatalogName,
+    /// Data source dependent name of the column type, e.g. `"VARCHAR"` (`SQL_DESC_TYPE_NAME`).
+    /// `String`
+    TypeName,
+    /// Maximum number of characters needed to display a value of the column
+    /// (`SQL_DESC_DISPLAY_SIZE`). `Integer`
+    DisplaySize,
+    /// Maximum length of a value of the column in bytes (`SQL_DESC_OCTET_LENGTH`). `Integer`
+    OctetLength,
+    /// Precision of numeric and time types (`SQL_DESC_PRECISION`). `Integer`
+    Precision,
+    /// Scale of decimal and numeric types (`SQL_DESC_SCALE`). `Integer`
+    Scale,
+    /// `SQL_NO_NULLS`, `SQL_NULLABLE` or `SQL_NULLABLE_UNKNOWN` (`SQL_DESC_NULLABLE`). `Integer`
+    Nullable,
+    /// `SQL_ATTR_READONLY`, `SQL_ATTR_WRITE` or `SQL_ATTR_READWRITE_UNKNOWN`
+    /// (`SQL_DESC_UPDATABLE`). `Integer`
+    Updatable,
+    /// `1` if the values are generated by the data source, like for an auto-increment column
+    /// (`SQL_DESC_AUTO_UNIQUE_VALUE`). `Integer`
+    AutoUniqueValue,
+    /// `1` if comparisons and sorting are case sensitive (`SQL_DESC_CASE_SENSITIVE`). `Integer`
+    CaseSensitive,
+    /// In which `WHERE` clause predicates the column can be used, e.g. `SQL_PRED_SEARCHABLE`
+    /// (`SQL_DESC_SEARCHABLE`). `Integer`
+    Searchable,
+}
+
+impl fmt::Display for ColAttr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ColAttr::Label => "SQL_DESC_LABEL",
+            ColAttr::BaseColumnName => "SQL_DESC_BASE_COLUMN_NAME",
+            ColAttr::BaseTableName => "SQL_DESC_BASE_TABLE_NAME",
+            ColAttr::SchemaName => "SQL_DESC_SCHEMA_NAME",
+            ColAttr::CatalogName => "SQL_DESC_CATALOG_NAME",
+            ColAttr::TypeName => "SQL_DESC_TYPE_NAME",
+            ColAttr::DisplaySize => "SQL_DESC_DISPLAY_SIZE",
+            ColAttr::OctetLength => "SQL_DESC_OCTET_LENGTH",
+            ColAttr::Precision => "SQL_DESC_PRECISION",
+            ColAttr::Scale => "SQL_DESC_SCALE",
+            ColAttr::Nullable => "SQL_DESC_NULLABLE",
+            ColAttr::Updatable => "SQL_DESC_UPDATABLE",
+            ColAttr::AutoUniqueValue => "SQL_DESC_AUTO_UNIQUE_VALUE",
+            ColAttr::CaseSensitive => "SQL_DESC_CASE_SENSITIVE",
+            ColAttr::Searchable => "SQL_DESC_SEARCHABLE",
+        })
+    }
+}
+
+/// Value returned by `Statement::col_attribute`. Which variant is returned depends on the
+/// `ColAttr` and is stated in its documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColAttrValue {
+    /// Character string attribute
+    String(String),
+    /// Numeric attribute
+    Integer(isize),
+}
+
+impl ColAttr {
+    fn field(&self) -> ffi::SQLUSMALLINT {
+        match *self {
+            ColAttr::Label => ffi::SQL_DESC_LABEL,
+            ColAttr::BaseColumnName => ffi::SQL_DESC_BASE_COLUMN_NAME,
+            ColAttr::BaseTableName => ffi::SQL_DESC_BASE_TABLE_NAME,
+            ColAttr::SchemaName => ffi::SQL_DESC_SCHEMA_NAME,
+            ColAttr::CatalogName => ffi::SQL_DESC_CATALOG_NAME,
+            ColAttr::TypeName => ffi::SQL_DESC_TYPE_NAME,
+            ColAttr::DisplaySize => ffi::SQL_DESC_DISPLAY_SIZE,
+            ColAttr::OctetLength => ffi::SQL_DESC_OCTET_LENGTH,
+            ColAttr::Precision => ffi::SQL_DESC_PRECISION as ffi::SQLUSMALLINT,
+            ColAttr::Scale => ffi::SQL_DESC_SCALE as ffi::SQLUSMALLINT,
+            ColAttr::Nullable => ffi::SQL_DESC_NULLABLE,
+            ColAttr::Updatable => ffi::SQL_DESC_UPDATABLE,
+            ColAttr::AutoUniqueValue => ffi::SQL_DESC_AUTO_UNIQUE_VALUE,
+            ColAttr::CaseSensitive => ffi::SQL_DESC_CASE_SENSITIVE,
+            ColAttr::Searchable => ffi::SQL_DESC_SEARCHABLE,
+        }
+    }
+
+    fn is_string(&self) -> bool {
+        matches!(
+            *self,
+            ColAttr::Label
+                | ColAttr::BaseColumnName
+                | ColAttr::BaseTableName
+                | ColAttr::SchemaName
+                | ColAttr::CatalogName
+                | ColAttr::TypeName
+        )
+    }
+}
+
+/// Metadata of a result set column, beyond what `Statement::describe_col` reports. Can be
+/// obtained via `Statement::column_attrs`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColumnAttrs {
+    /// Name of the column in the table it originates from. Empty if it is an expression or the
+    /// driver can not determine it.
+    pub base_column_name: String,
+    /// Name of the table the column originates from. Empty if unknown.
+    pub base_table_name: String,
+    /// Schema of the table the column originates from. Empty if unknown or not applicable.
+    pub schema_name: String,
+    /// Catalog of the table the column originates from. Empty if unknown or not applicable.
+    pub catalog_name: String,
+    /// Title of the column, e.g. the alias given with `AS`, or else its name
+    pub label: String,
+    /// `true` if the column can be updated, `false` if it is read-only and `None` if unknown
+    pub updatable: Option<bool>,
+    /// `true` if the values of the column are generated by the data source, like for an
+    /// auto-increment column
+    pub auto_unique_value: bool,
+    /// `true` if comparisons and sorting of the column are case sensitive
+    pub case_sensitive: bool,
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Returns metadata of the result set column with the given index, starting at 1. Reads the
+    /// fields of the implementation row descriptor using `SQLColAttribute`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE AS NAME FROM MOVIES")? {
+    ///     let attrs = stmt.column_attrs(1)?;
+    ///     println!("{} is {}.{}", attrs.label, attrs.base_table_name, attrs.base_column_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn column_attrs(&self, idx: u16) -> Result<ColumnAttrs> {
+        Ok(ColumnAttrs {
+            base_column_name: self.string_attr(idx, ffi::SQL_DESC_BASE_COLUMN_NAME)?,
+            base_table_name: self.string_attr(idx, ffi::SQL_DESC_BASE_TABLE_NAME)?,
+            schema_name: self.string_attr(idx, ffi::SQL_DESC_SCHEMA_NAME)?,
+            catalog_name: self.string_attr(idx, ffi::SQL_DESC_CATALOG_NAME)?,
+            label: self.string_attr(idx, ffi::SQL_DESC_LABEL)?,
+            updatable: match self.numeric_attr(idx, ffi::SQL_DESC_UPDATABLE)? {
+                ffi::SQL_ATTR_READONLY => Some(false),
+                ffi::SQL_ATTR_WRITE => Some(true),
+                _ => None,
+            },
+            auto_unique_value: self.numeric_attr(idx, ffi::SQL_DESC_AUTO_UNIQUE_VALUE)? != 0,
+            case_sensitive: self.numeric_attr(idx, ffi::SQL_DESC_CASE_SENSITIVE)? != 0,
+        })
+    }
+
+    /// Returns a single attribute of the result set column with the given index, starting at 1.
+    /// Wraps `SQLColAttribute`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+    ///     if let ColAttrValue::String(name) = stmt.col_attribute(1, ColAttr::TypeName)? {
+    ///         println!("TITLE is of type {}", name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn col_attribute(&self, idx: u16, attr: ColAttr) -> Result<ColAttrValue> {
+        if attr.is_string() {
+            self.string_attr(idx, attr.field()).map(ColAttrValue::String)
+        } else {
+            self.numeric_attr(idx, attr.field())
+                .map(|value| ColAttrValue::Integer(value as isize))
+        }
+    }
+
+    fn string_attr(&self, idx: u16, field: ffi::SQLUSMALLINT) -> Result<String> {
+        self.raii.col_attribute_string(idx, field).into_result(&self.diag())
+    }
+
+    fn numeric_attr(&self, idx: u16, field: ffi::SQLUSMALLINT) -> Result<ffi::SQLLEN> {
+        self.raii.col_attribute_numeric(idx, field).into_result(&self.diag())
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn col_attribute_string(&self, idx: u16, field: ffi::SQLUSMALLINT) -> Return<String> {
+        with_growing_buffer(256, |buffer| {
+            let mut length: ffi::SQLSMALLINT = 0;
+            let ret = match poll_while_executing(|| unsafe {
+                ffi::SQLColAttribute(
+                    self.handle(),
+                    idx,
+                    field,
+                    buffer.as_mut_ptr() as ffi::SQLPOINTER,
+                    buffer.len() as ffi::SQLSMALLINT,
+                    &mut length,
+                    null_mut(),
+                )
+            }) {
+                ffi::SQL_SUCCESS => Return::Success(()),
+                ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+                ffi::SQL_ERROR => Return::Error,
+                r => panic!("SQLColAttribute returned unexpected result: {:?}", r),
+            };
+            (ret, length.max(0) as usize)
+        })
+        .map(|((), value)| unsafe { ::environment::DB_ENCODING }.decode(&value).0.into_owned())
+    }
+
+    fn col_attribute_numeric(&self, idx: u16, field: ffi::SQLUSMALLINT) -> Return<ffi::SQLLEN> {
+        let mut value: ffi::SQLLEN = 0;
+        match poll_while_executing(|| unsafe {
+            ffi::SQLColAttribute(self.handle(), idx, field, null_mut(), 0, null_mut(), &mut value)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(value),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(value),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLColAttribute returned unexpected result: {:?}", r),
+        }
+    }
+}
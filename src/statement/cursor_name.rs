@@ -0,0 +1,89 @@
+//! Naming cursors for positioned `UPDATE` and `DELETE` statements, using `SQLSetCursorName` and
+//! `SQLGetCursorName`
+use super::{NoResult, Statement};
+use odbc_safe::AutocommitMode;
+use result::with_growing_buffer;
+use {ffi, Handle, Raii, Result, Return};
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, NoResult, AC> {
+    /// Names the cursor of the result set the statement is going to open. Wraps
+    /// `SQLSetCursorName`.
+    ///
+    /// The name is used by positioned statements, e.g.
+    /// `UPDATE MOVIES SET YEAR = ? WHERE CURRENT OF movies_cursor`, executed on another statement
+    /// of the same connection. It has to be set before the query is executed, since an open
+    /// cursor can not be renamed.
+    ///
+    /// Cursor names must be unique within a connection. If several statements are named
+    /// concurrently, derive each name from something unique, e.g. a counter or a UUID with the
+    /// hyphens removed, since names must be valid identifiers. Names starting with `SQLCUR` or
+    /// `SQL_CUR` are reserved for names generated by the driver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_cursor_name("movies_cursor")?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES")? {
+    ///     if stmt.fetch()?.is_some() {
+    ///         let update = Statement::with_parent(&conn)?;
+    ///         update.exec_direct("UPDATE MOVIES SET YEAR = 1970 WHERE CURRENT OF movies_cursor")?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cursor_name(&mut self, name: &str) -> Result<()> {
+        self.raii.set_cursor_name(name).into_result(&self.diag())
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Name of the cursor of the statement. Wraps `SQLGetCursorName`.
+    ///
+    /// Unless set with `set_cursor_name`, this is the name generated by the driver, which starts
+    /// with `SQL_CUR`.
+    pub fn cursor_name(&self) -> Result<String> {
+        self.raii.cursor_name().into_result(&self.diag())
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn set_cursor_name(&mut self, name: &str) -> Return<()> {
+        let bytes = unsafe { ::environment::DB_ENCODING }.encode(name).0;
+        match unsafe {
+            ffi::SQLSetCursorName(self.handle(), bytes.as_ptr(), bytes.len() as ffi::SQLSMALLINT)
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLSetCursorName returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn cursor_name(&self) -> Return<String> {
+        // Cursor names are at most 18 characters long in most data sources
+        with_growing_buffer(64, |buffer| {
+            let mut length: ffi::SQLSMALLINT = 0;
+            let ret = match unsafe {
+                ffi::SQLGetCursorName(
+                    self.handle(),
+                    buffer.as_mut_ptr(),
+                    buffer.len() as ffi::SQLSMALLINT,
+                    &mut length,
+                )
+            } {
+                ffi::SQL_SUCCESS => Return::Success(()),
+                ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+                ffi::SQL_ERROR => Return::Error,
+                r => panic!("SQLGetCursorName returned unexpected result: {:?}", r),
+            };
+            (ret, length.max(0) as usize)
+        })
+        .map(|((), name)| unsafe { ::environment::DB_ENCODING }.decode(&name).0.into_owned())
+    }
+}
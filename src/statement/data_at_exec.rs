@@ -0,0 +1,249 @@
+//! Sending parameter data at execution time using `SQLParamData` and `SQLPutData`
+use super::types::OdbcType;
+use super::{
+    poll_while_executing, Allocated, Executed, HasResult, NoResult, Prepared, ResultSetState,
+    Statement,
+};
+use odbc_safe::AutocommitMode;
+use std::ptr::null_mut;
+use {ffi, Handle, Raii, Result, Return};
+
+/// `Statement` state used to represent a statement, whose execution waits for the data of
+/// parameters bound with `bind_parameter_at_exec`. Entered by `exec_direct_at_exec` or
+/// `execute_at_exec`.
+pub enum NeedData {}
+
+/// Returned by `Statement::param_data`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamDataResult {
+    /// The driver needs the data of the parameter with this index. Send it using `put_data`.
+    NeedData(u16),
+    /// Data for all parameters has been sent and the statement has been executed. Call `finish`
+    /// to access the result.
+    Done,
+}
+
+/// Returned by `exec_direct_at_exec` and `execute_at_exec`
+pub enum ExecAtExecState<'a, 'b, S, AC: AutocommitMode> {
+    /// The driver waits for the data of parameters. Send it using `param_data` and `put_data`.
+    NeedData(Statement<'a, 'b, S, NeedData, AC>),
+    /// The statement has been executed without asking for any data. Call `finish` right away,
+    /// `param_data` must not be called.
+    Done(Statement<'a, 'b, S, NeedData, AC>),
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Binds a parameter, whose data is sent at execution time, to a parameter marker.
+    ///
+    /// Useful for large values, which should not be held in memory as a whole, e.g. BLOBs. The
+    /// type `T` only determines the C and SQL data types of the parameter. `length` is the total
+    /// length of the data in bytes, which some drivers require up front (see the
+    /// `SQL_NEED_LONG_DATA_LEN` information type).
+    ///
+    /// Execute the statement using `exec_direct_at_exec` or `execute_at_exec`, then send the data
+    /// using `param_data` and `put_data`.
+    pub fn bind_parameter_at_exec<'c, T>(
+        mut self,
+        parameter_index: u16,
+        length: Option<usize>,
+    ) -> Result<Self>
+    where
+        T: OdbcType<'c>,
+    {
+        let ind = match length {
+            Some(length) => ffi::SQL_LEN_DATA_AT_EXEC_OFFSET - length as ffi::SQLLEN,
+            None => ffi::SQL_DATA_AT_EXEC,
+        };
+        let ind_ptr = self.param_ind_buffers.alloc(parameter_index as usize, ind);
+        self.raii
+            .bind_parameter_at_exec::<T>(parameter_index, length.unwrap_or(0), ind_ptr)
+            .into_result(&self.diag())?;
+        Ok(self)
+    }
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    /// Executes a statement with parameters bound by `bind_parameter_at_exec`. Wraps
+    /// `SQLExecDirect`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let blob = vec![42u8; 100_000];
+    /// let stmt = Statement::with_parent(&conn)?
+    ///     .bind_parameter_at_exec::<&[u8]>(1, Some(blob.len()))?;
+    /// let stmt = match stmt.exec_direct_at_exec("INSERT INTO BLOBS (DATA) VALUES (?)")? {
+    ///     ExecAtExecState::NeedData(mut stmt) => {
+    ///         while let ParamDataResult::NeedData(_) = stmt.param_data()? {
+    ///             for chunk in blob.chunks(4096) {
+    ///                 stmt.put_data(&chunk)?;
+    ///             }
+    ///         }
+    ///         stmt
+    ///     }
+    ///     ExecAtExecState::Done(stmt) => stmt,
+    /// };
+    /// stmt.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn exec_direct_at_exec(
+        mut self,
+        statement_text: &str,
+    ) -> Result<ExecAtExecState<'a, 'b, Executed, AC>> {
+        let need_data = self
+            .raii
+            .exec_direct_at_exec(statement_text)
+            .into_result(&self.diag())?;
+        Ok(ExecAtExecState::new(self.with_result(), need_data))
+    }
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Prepared, NoResult, AC> {
+    /// Executes a prepared statement with parameters bound by `bind_parameter_at_exec`. Wraps
+    /// `SQLExecute`. See `exec_direct_at_exec`.
+    pub fn execute_at_exec(mut self) -> Result<ExecAtExecState<'a, 'b, Prepared, AC>> {
+        let need_data = self.raii.execute_at_exec().into_result(&self.diag())?;
+        Ok(ExecAtExecState::new(self.with_result(), need_data))
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> ExecAtExecState<'a, 'b, S, AC> {
+    fn new(stmt: Statement<'a, 'b, S, NeedData, AC>, need_data: bool) -> Self {
+        if need_data {
+            ExecAtExecState::NeedData(stmt)
+        } else {
+            ExecAtExecState::Done(stmt)
+        }
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, NeedData, AC> {
+    /// Asks the driver which parameter it needs data for next. Wraps `SQLParamData`.
+    ///
+    /// Must be called once before sending the data of the first parameter, and after the data of
+    /// each parameter has been sent. Returns `Done` once the driver has received all data and
+    /// executed the statement.
+    pub fn param_data(&mut self) -> Result<ParamDataResult> {
+        self.raii.param_data().into_result(&self.diag())
+    }
+
+    /// Sends data for the parameter requested by the last call to `param_data`. Wraps
+    /// `SQLPutData`.
+    ///
+    /// May be called several times for the same parameter, in order to send character or binary
+    /// data in chunks.
+    pub fn put_data<'c, T>(&mut self, value: &T) -> Result<()>
+    where
+        T: OdbcType<'c>,
+    {
+        self.raii.put_data(value).into_result(&self.diag())
+    }
+
+    /// Completes the execution, after `param_data` returned `Done`, or right away if the
+    /// execution returned `ExecAtExecState::Done`.
+    pub fn finish(mut self) -> Result<ResultSetState<'a, 'b, S, AC>> {
+        let num_cols = self.result_cols()?;
+        if num_cols > 0 {
+            Ok(ResultSetState::Data(self.into_state::<S, HasResult>()))
+        } else {
+            Ok(ResultSetState::NoData(self.into_state::<S, NoResult>()))
+        }
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn bind_parameter_at_exec<'c, T>(
+        &mut self,
+        parameter_index: u16,
+        length: usize,
+        str_len_or_ind_ptr: *mut ffi::SQLLEN,
+    ) -> Return<()>
+    where
+        T: OdbcType<'c>,
+    {
+        match unsafe {
+            ffi::SQLBindParameter(
+                self.handle(),
+                parameter_index,
+                ffi::SQL_PARAM_INPUT,
+                T::c_data_type(),
+                T::sql_data_type(),
+                length as ffi::SQLULEN,
+                0,
+                // Handed back to us by `SQLParamData`, to identify the parameter
+                parameter_index as usize as ffi::SQLPOINTER,
+                0,
+                str_len_or_ind_ptr,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("Unexpected return from SQLBindParameter: {:?}", r),
+        }
+    }
+
+    /// Returns `true` if the driver needs data for parameters
+    fn exec_direct_at_exec(&mut self, statement_text: &str) -> Return<bool> {
+        let bytes = unsafe { ::environment::DB_ENCODING }.encode(statement_text).0;
+        match poll_while_executing(|| unsafe {
+            ffi::SQLExecDirect(self.handle(), bytes.as_ptr(), bytes.len() as ffi::SQLINTEGER)
+        }) {
+            ffi::SQL_NEED_DATA => Return::Success(true),
+            ffi::SQL_SUCCESS | ffi::SQL_NO_DATA => Return::Success(false),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(false),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLExecDirect returned unexpected result: {:?}", r),
+        }
+    }
+
+    /// Returns `true` if the driver needs data for parameters
+    fn execute_at_exec(&mut self) -> Return<bool> {
+        match poll_while_executing(|| unsafe { ffi::SQLExecute(self.handle()) }) {
+            ffi::SQL_NEED_DATA => Return::Success(true),
+            ffi::SQL_SUCCESS | ffi::SQL_NO_DATA => Return::Success(false),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(false),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLExecute returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn param_data(&mut self) -> Return<ParamDataResult> {
+        let mut token: ffi::SQLPOINTER = null_mut();
+        match poll_while_executing(|| unsafe { ffi::SQLParamData(self.handle(), &mut token) }) {
+            ffi::SQL_NEED_DATA => {
+                Return::Success(ParamDataResult::NeedData(token as usize as u16))
+            }
+            ffi::SQL_SUCCESS | ffi::SQL_NO_DATA => Return::Success(ParamDataResult::Done),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(ParamDataResult::Done),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLParamData returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn put_data<'c, T>(&mut self, value: &T) -> Return<()>
+    where
+        T: OdbcType<'c>,
+    {
+        // Strings are sent in the encoding of the database
+        let enc_value = value.encoded_value();
+        let (length, value_ptr) = if enc_value.has_value() {
+            (enc_value.column_size(), enc_value.value_ptr())
+        } else {
+            (value.column_size(), value.value_ptr())
+        };
+        match poll_while_executing(|| unsafe {
+            ffi::SQLPutData(self.handle(), value_ptr, length as ffi::SQLLEN)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLPutData returned unexpected result: {:?}", r),
+        }
+    }
+}
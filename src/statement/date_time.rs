@@ -0,0 +1,289 @@
+//! Date and time types without further dependencies, converting to and from the types of the
+//! `chrono` and `time` crates
+use super::types::{EncodedValue, OdbcType, SqlDate, SqlTime, SqlTimestamp};
+use std::mem::size_of;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryFrom;
+use ffi;
+
+/// Calendar date, bound and retrieved as `SQL_DATE_STRUCT`
+///
+/// Unlike `SqlDate` this is a type of this crate, so it offers conversions to and from
+/// `chrono::NaiveDate` and `time::Date`, if the respective feature is enabled. The value is not
+/// validated, since data sources may return dates like `0000-00-00`. Converting such a date fails.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OdbcDate {
+    pub year: i16,
+    pub month: u16,
+    pub day: u16,
+}
+
+/// Time of day, bound and retrieved as `SQL_TIME_STRUCT`. See `OdbcDate`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OdbcTime {
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+}
+
+/// Date and time of day, bound and retrieved as `SQL_TIMESTAMP_STRUCT`. See `OdbcDate`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OdbcTimestamp {
+    pub year: i16,
+    pub month: u16,
+    pub day: u16,
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+    /// Nanoseconds
+    pub fraction: u32,
+}
+
+// The types share the layout of the ODBC structs, so they are bound without any copy
+macro_rules! odbc_struct_type {
+    ($t:ident, $sql:ident, $($field:ident),*) => {
+        unsafe impl<'a> OdbcType<'a> for $t {
+            fn sql_data_type() -> ffi::SqlDataType {
+                $sql::sql_data_type()
+            }
+            fn c_data_type() -> ffi::SqlCDataType {
+                $sql::c_data_type()
+            }
+
+            fn convert(buffer: &'a [u8]) -> Self {
+                let value = $sql::convert(buffer);
+                $t { $($field: value.$field),* }
+            }
+
+            fn column_size(&self) -> ffi::SQLULEN {
+                size_of::<$sql>() as ffi::SQLULEN
+            }
+            fn value_ptr(&self) -> ffi::SQLPOINTER {
+                self as *const Self as ffi::SQLPOINTER
+            }
+
+            fn encoded_value(&self) -> EncodedValue {
+                EncodedValue::new(None)
+            }
+        }
+    };
+}
+
+odbc_struct_type!(OdbcDate, SqlDate, year, month, day);
+odbc_struct_type!(OdbcTime, SqlTime, hour, minute, second);
+odbc_struct_type!(OdbcTimestamp, SqlTimestamp, year, month, day, hour, minute, second, fraction);
+
+impl OdbcTimestamp {
+    /// Date part of the timestamp
+    pub fn date(&self) -> OdbcDate {
+        OdbcDate { year: self.year, month: self.month, day: self.day }
+    }
+
+    /// Time of day of the timestamp, without the fraction of a second
+    pub fn time(&self) -> OdbcTime {
+        OdbcTime { hour: self.hour, minute: self.minute, second: self.second }
+    }
+}
+
+/// Conversions to `chrono` fail for values which do not exist, like `0000-00-00`, and return the
+/// original value. Conversions from `chrono` fail for years outside of the range of `i16`.
+#[cfg(feature = "chrono")]
+mod chrono_conversions {
+    use super::*;
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+    impl TryFrom<OdbcDate> for NaiveDate {
+        type Error = OdbcDate;
+        fn try_from(date: OdbcDate) -> Result<Self, OdbcDate> {
+            NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32)
+                .ok_or(date)
+        }
+    }
+
+    impl TryFrom<OdbcTime> for NaiveTime {
+        type Error = OdbcTime;
+        fn try_from(time: OdbcTime) -> Result<Self, OdbcTime> {
+            NaiveTime::from_hms_opt(time.hour as u32, time.minute as u32, time.second as u32)
+                .ok_or(time)
+        }
+    }
+
+    impl TryFrom<OdbcTimestamp> for NaiveDateTime {
+        type Error = OdbcTimestamp;
+        fn try_from(ts: OdbcTimestamp) -> Result<Self, OdbcTimestamp> {
+            let date = NaiveDate::try_from(ts.date()).map_err(|_| ts)?;
+            let time = NaiveTime::from_hms_nano_opt(
+                ts.hour as u32,
+                ts.minute as u32,
+                ts.second as u32,
+                ts.fraction,
+            ).ok_or(ts)?;
+            Ok(date.and_time(time))
+        }
+    }
+
+    impl TryFrom<NaiveDate> for OdbcDate {
+        type Error = NaiveDate;
+        fn try_from(date: NaiveDate) -> Result<Self, NaiveDate> {
+            Ok(OdbcDate {
+                year: i16::try_from(date.year()).map_err(|_| date)?,
+                month: date.month() as u16,
+                day: date.day() as u16,
+            })
+        }
+    }
+
+    /// Drops the fraction of a second, which `SQL_TIME_STRUCT` can not hold
+    impl From<NaiveTime> for OdbcTime {
+        fn from(time: NaiveTime) -> Self {
+            OdbcTime {
+                hour: time.hour() as u16,
+                minute: time.minute() as u16,
+                // `chrono` represents leap seconds as a fraction of more than one second
+                second: (time.second() + time.nanosecond() / 1_000_000_000) as u16,
+            }
+        }
+    }
+
+    impl TryFrom<NaiveDateTime> for OdbcTimestamp {
+        type Error = NaiveDateTime;
+        fn try_from(date_time: NaiveDateTime) -> Result<Self, NaiveDateTime> {
+            let date = OdbcDate::try_from(date_time.date()).map_err(|_| date_time)?;
+            let time = OdbcTime::from(date_time.time());
+            Ok(OdbcTimestamp {
+                year: date.year,
+                month: date.month,
+                day: date.day,
+                hour: time.hour,
+                minute: time.minute,
+                second: time.second,
+                fraction: date_time.nanosecond() % 1_000_000_000,
+            })
+        }
+    }
+}
+
+/// Conversions to `time` fail for values which do not exist, like `0000-00-00`, and return the
+/// original value. Years of the `time` crate always fit into an `i16`.
+#[cfg(feature = "time")]
+mod time_conversions {
+    use super::*;
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    impl TryFrom<OdbcDate> for Date {
+        type Error = OdbcDate;
+        fn try_from(date: OdbcDate) -> Result<Self, OdbcDate> {
+            let month = u8::try_from(date.month)
+                .ok()
+                .and_then(|month| Month::try_from(month).ok())
+                .ok_or(date)?;
+            let day = u8::try_from(date.day).map_err(|_| date)?;
+            Date::from_calendar_date(date.year as i32, month, day).map_err(|_| date)
+        }
+    }
+
+    impl TryFrom<OdbcTime> for Time {
+        type Error = OdbcTime;
+        fn try_from(time: OdbcTime) -> Result<Self, OdbcTime> {
+            time_of_day(time.hour, time.minute, time.second, 0).ok_or(time)
+        }
+    }
+
+    impl TryFrom<OdbcTimestamp> for PrimitiveDateTime {
+        type Error = OdbcTimestamp;
+        fn try_from(ts: OdbcTimestamp) -> Result<Self, OdbcTimestamp> {
+            let date = Date::try_from(ts.date()).map_err(|_| ts)?;
+            let time = time_of_day(ts.hour, ts.minute, ts.second, ts.fraction).ok_or(ts)?;
+            Ok(PrimitiveDateTime::new(date, time))
+        }
+    }
+
+    fn time_of_day(hour: u16, minute: u16, second: u16, nanos: u32) -> Option<Time> {
+        let hour = u8::try_from(hour).ok()?;
+        let minute = u8::try_from(minute).ok()?;
+        let second = u8::try_from(second).ok()?;
+        Time::from_hms_nano(hour, minute, second, nanos).ok()
+    }
+
+    impl From<Date> for OdbcDate {
+        fn from(date: Date) -> Self {
+            OdbcDate {
+                year: date.year() as i16,
+                month: u8::from(date.month()) as u16,
+                day: date.day() as u16,
+            }
+        }
+    }
+
+    /// Drops the fraction of a second, which `SQL_TIME_STRUCT` can not hold
+    impl From<Time> for OdbcTime {
+        fn from(time: Time) -> Self {
+            OdbcTime {
+                hour: time.hour() as u16,
+                minute: time.minute() as u16,
+                second: time.second() as u16,
+            }
+        }
+    }
+
+    impl From<PrimitiveDateTime> for OdbcTimestamp {
+        fn from(date_time: PrimitiveDateTime) -> Self {
+            let date = OdbcDate::from(date_time.date());
+            let time = OdbcTime::from(date_time.time());
+            OdbcTimestamp {
+                year: date.year,
+                month: date.month,
+                day: date.day,
+                hour: time.hour,
+                minute: time.minute,
+                second: time.second,
+                fraction: date_time.nanosecond(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_layout_as_odbc_structs() {
+        assert_eq!(size_of::<OdbcDate>(), size_of::<SqlDate>());
+        assert_eq!(size_of::<OdbcTime>(), size_of::<SqlTime>());
+        assert_eq!(size_of::<OdbcTimestamp>(), size_of::<SqlTimestamp>());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversions() {
+        use chrono::{NaiveDate, NaiveDateTime};
+        let date_time = NaiveDate::from_ymd_opt(2019, 8, 16)
+            .unwrap()
+            .and_hms_nano_opt(10, 20, 30, 500)
+            .unwrap();
+        let ts = OdbcTimestamp::try_from(date_time).unwrap();
+        assert_eq!((ts.year, ts.month, ts.day, ts.second, ts.fraction), (2019, 8, 16, 30, 500));
+        assert_eq!(NaiveDateTime::try_from(ts), Ok(date_time));
+        let invalid = OdbcDate { year: 0, month: 0, day: 0 };
+        assert_eq!(NaiveDate::try_from(invalid), Err(invalid));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_conversions() {
+        use time::{Date, Month, PrimitiveDateTime, Time};
+        let date_time = PrimitiveDateTime::new(
+            Date::from_calendar_date(2019, Month::August, 16).unwrap(),
+            Time::from_hms_nano(10, 20, 30, 500).unwrap(),
+        );
+        let ts = OdbcTimestamp::from(date_time);
+        assert_eq!((ts.year, ts.month, ts.day, ts.second, ts.fraction), (2019, 8, 16, 30, 500));
+        assert_eq!(PrimitiveDateTime::try_from(ts), Ok(date_time));
+        let invalid = OdbcTime { hour: 25, minute: 0, second: 0 };
+        assert_eq!(Time::try_from(invalid), Err(invalid));
+    }
+}
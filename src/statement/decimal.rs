@@ -0,0 +1,213 @@
+//! Lossless retrieval of `DECIMAL` and `NUMERIC` columns, using `SQL_C_NUMERIC`
+use super::descriptor::{DescType, RawDescriptor};
+use super::output::Output;
+use super::poll_while_executing;
+use std::fmt;
+use std::convert::TryFrom;
+use std::mem::size_of;
+use result::failed_on;
+use {ffi, DiagnosticRecord, Handle, Raii, Result, Return};
+
+/// Exact fixed-point number, with a value of `mantissa * 10^-scale`.
+///
+/// Can be retrieved from `DECIMAL` and `NUMERIC` columns using `Cursor::get_data`. Unlike `f64`
+/// no precision is lost, which matters e.g. for monetary amounts. The value is read as
+/// `SQL_NUMERIC_STRUCT`, using the precision and scale of the column as reported by
+/// `SQLDescribeCol`. Use `to_string` to obtain its exact decimal representation.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// let stmt = Statement::with_parent(&conn)?;
+/// if let Data(mut stmt) = stmt.exec_direct("SELECT CAST(12.34 AS DECIMAL(10,2))")? {
+///     let mut cursor = stmt.fetch()?.unwrap();
+///     let price: Decimal = cursor.get_data(1)?.unwrap();
+///     println!("{}", price); // 12.34
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: i8,
+}
+
+impl Decimal {
+    /// Creates a decimal with a value of `mantissa * 10^-scale`
+    pub fn new(mantissa: i128, scale: i8) -> Decimal {
+        Decimal { mantissa, scale }
+    }
+
+    /// Unscaled value
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Number of digits to the right of the decimal point. Negative values multiply the mantissa
+    /// by a power of ten.
+    pub fn scale(&self) -> i8 {
+        self.scale
+    }
+
+    /// Nearest floating point number. May lose precision.
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Fails with SQLSTATE `22003` if the magnitude does not fit into an `i128`. A precision of
+    /// 38 digits always fits, but drivers may fill all 128 bits of `val`.
+    fn from_numeric(numeric: &ffi::SQL_NUMERIC_STRUCT) -> Result<Decimal> {
+        let magnitude = u128::from_le_bytes(numeric.val);
+        let magnitude = i128::try_from(magnitude).map_err(|_| {
+            let message = format!("numeric value {} returned by driver is out of range", magnitude);
+            DiagnosticRecord::with_message(b"22003", &message)
+        })?;
+        let mantissa = if numeric.sign == 0 { -magnitude } else { magnitude };
+        Ok(Decimal::new(mantissa, numeric.scale))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if self.scale <= 0 {
+            let zeros = if self.mantissa == 0 { 0 } else { -(self.scale as i32) as usize };
+            return write!(f, "{}{}{}", sign, digits, "0".repeat(zeros));
+        }
+        let scale = self.scale as usize;
+        if digits.len() > scale {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{}{}.{}", sign, int, frac)
+        } else {
+            write!(f, "{}0.{}{}", sign, "0".repeat(scale - digits.len()), digits)
+        }
+    }
+}
+
+/// Reads `SQL_NUMERIC_STRUCT`, with precision and scale of the column
+unsafe impl<'a> Output<'a> for Decimal {
+    fn get_data(
+        stmt: &mut Raii<ffi::Stmt>,
+        col_or_param_num: u16,
+        _buffer: &'a mut Vec<u8>,
+    ) -> Return<Option<Self>> {
+        let (precision, scale) = match stmt.describe_col(col_or_param_num) {
+            Return::Success(desc) | Return::SuccessWithInfo(desc) => (
+                desc.column_size.map_or(38, |size| size.min(38)) as ffi::SQLSMALLINT,
+                desc.decimal_digits.unwrap_or(0) as ffi::SQLSMALLINT,
+            ),
+            Return::Error => return Return::Error,
+            Return::NeedData => return Return::NeedData,
+            Return::Failed(record) => return Return::Failed(record),
+        };
+        stmt.get_numeric(col_or_param_num, precision, scale).try_map(|numeric| {
+            numeric.map(|numeric| Decimal::from_numeric(&numeric)).transpose()
+        })
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn get_numeric(
+        &mut self,
+        col_or_param_num: u16,
+        precision: ffi::SQLSMALLINT,
+        scale: ffi::SQLSMALLINT,
+    ) -> Return<Option<ffi::SQL_NUMERIC_STRUCT>> {
+        // By default drivers use a scale of `0` for `SQL_C_NUMERIC`, truncating the fractional
+        // part. The precision and scale are set in the application row descriptor instead, which
+        // `SQLGetData` then uses for a target type of `SQL_ARD_TYPE`.
+        let ard = match self.descriptor(DescType::AppRowDesc) {
+            Return::Success(ard) | Return::SuccessWithInfo(ard) => RawDescriptor(ard),
+            // Taken from the statement right away, like the records of the descriptor below
+            Return::Error => return failed_on(self),
+            Return::NeedData => return Return::NeedData,
+            Return::Failed(record) => return Return::Failed(record),
+        };
+        let record = col_or_param_num as ffi::SQLSMALLINT;
+        let fields = [
+            (ffi::SQL_DESC_TYPE, ffi::SQL_C_NUMERIC as ffi::SQLSMALLINT),
+            (ffi::SQL_DESC_PRECISION, precision),
+            (ffi::SQL_DESC_SCALE, scale),
+        ];
+        for &(field, value) in &fields {
+            let ret = unsafe {
+                ffi::SQLSetDescField(ard.0, record, field, value as isize as ffi::SQLPOINTER, 0)
+            };
+            if ret == ffi::SQL_ERROR {
+                // Diagnostics are recorded on the descriptor, not on the statement
+                return failed_on(&ard);
+            }
+        }
+        let mut numeric = ffi::SQL_NUMERIC_STRUCT::default();
+        let mut indicator: ffi::SQLLEN = 0;
+        let ret = poll_while_executing(|| unsafe {
+            ffi::SQLGetDataArdType(
+                self.handle(),
+                col_or_param_num,
+                ffi::SQL_ARD_TYPE,
+                &mut numeric as *mut ffi::SQL_NUMERIC_STRUCT as ffi::SQLPOINTER,
+                size_of::<ffi::SQL_NUMERIC_STRUCT>() as ffi::SQLLEN,
+                &mut indicator,
+            )
+        });
+        let value = if indicator == ffi::SQL_NULL_DATA { None } else { Some(numeric) };
+        match ret {
+            ffi::SQL_SUCCESS => Return::Success(value),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(value),
+            ffi::SQL_ERROR => Return::Error,
+            // The value of the column has already been retrieved by a previous call
+            ffi::SQL_NO_DATA => Return::Failed(Box::new(DiagnosticRecord::with_message(
+                b"HY010",
+                "SQLGetData has already returned the column data",
+            ))),
+            r => panic!("unexpected return value from SQLGetData: {:?}", r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Decimal::new(1234, 2).to_string(), "12.34");
+        assert_eq!(Decimal::new(-5, 3).to_string(), "-0.005");
+        assert_eq!(Decimal::new(42, 0).to_string(), "42");
+        assert_eq!(Decimal::new(42, -2).to_string(), "4200");
+        assert_eq!(Decimal::new(0, 2).to_string(), "0.00");
+    }
+
+    #[test]
+    fn from_numeric() {
+        let mut numeric = ffi::SQL_NUMERIC_STRUCT {
+            precision: 10,
+            scale: 2,
+            sign: 0,
+            val: [0; ffi::SQL_MAX_NUMERIC_LEN],
+        };
+        // 123456 = 0x01E240
+        numeric.val[..3].copy_from_slice(&[0x40, 0xE2, 0x01]);
+        assert_eq!(Decimal::from_numeric(&numeric).unwrap(), Decimal::new(-123456, 2));
+        numeric.sign = 1;
+        assert_eq!(Decimal::from_numeric(&numeric).unwrap().to_string(), "1234.56");
+    }
+
+    #[test]
+    fn from_numeric_out_of_range() {
+        let numeric = ffi::SQL_NUMERIC_STRUCT {
+            precision: 38,
+            scale: 0,
+            sign: 1,
+            val: [0xFF; ffi::SQL_MAX_NUMERIC_LEN],
+        };
+        let error = Decimal::from_numeric(&numeric).unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"22003");
+    }
+}
@@ -0,0 +1,132 @@
+//! Copying descriptors between statements, using `SQLCopyDesc`
+use super::Statement;
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::ptr::null_mut;
+use {ffi, safe, Handle, Raii, Result, Return};
+
+/// Descriptor of a statement, see `Statement::copy_descriptor_from`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DescType {
+    /// Application parameter descriptor, describing the buffers bound to parameter markers
+    /// (`SQL_ATTR_APP_PARAM_DESC`)
+    AppParamDesc,
+    /// Application row descriptor, describing the buffers bound to columns
+    /// (`SQL_ATTR_APP_ROW_DESC`)
+    AppRowDesc,
+    /// Implementation parameter descriptor, describing the SQL types of the parameters
+    /// (`SQL_ATTR_IMP_PARAM_DESC`)
+    ImpParamDesc,
+    /// Implementation row descriptor, describing the columns of the result set. Can only be
+    /// copied from, not to. (`SQL_ATTR_IMP_ROW_DESC`)
+    ImpRowDesc,
+}
+
+impl fmt::Display for DescType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DescType::AppParamDesc => "SQL_ATTR_APP_PARAM_DESC",
+            DescType::AppRowDesc => "SQL_ATTR_APP_ROW_DESC",
+            DescType::ImpParamDesc => "SQL_ATTR_IMP_PARAM_DESC",
+            DescType::ImpRowDesc => "SQL_ATTR_IMP_ROW_DESC",
+        })
+    }
+}
+
+impl DescType {
+    fn attribute(&self) -> ffi::SQLINTEGER {
+        match *self {
+            DescType::AppParamDesc => ffi::SQL_ATTR_APP_PARAM_DESC,
+            DescType::AppRowDesc => ffi::SQL_ATTR_APP_ROW_DESC,
+            DescType::ImpParamDesc => ffi::SQL_ATTR_IMP_PARAM_DESC,
+            DescType::ImpRowDesc => ffi::SQL_ATTR_IMP_ROW_DESC,
+        }
+    }
+}
+
+/// Descriptor handle of a statement. Freed together with the statement.
+pub(super) struct RawDescriptor(pub(super) ffi::SQLHDESC);
+
+// Errors of `SQLCopyDesc` and `SQLSetDescField` are reported on the descriptor
+unsafe impl safe::Handle for RawDescriptor {
+    const HANDLE_TYPE: ffi::HandleType = ffi::SQL_HANDLE_DESC;
+
+    fn handle(&self) -> ffi::SQLHANDLE {
+        self.0 as ffi::SQLHANDLE
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Copies the descriptor `desc_type` of `other` to the same descriptor of this statement.
+    /// Wraps `SQLCopyDesc`.
+    ///
+    /// Copying the application parameter descriptor binds the parameter buffers of `other` to
+    /// this statement, without binding each parameter again. This is why the returned statement
+    /// borrows `other`, as if its buffers had been bound with `bind_parameter`. Note that bound
+    /// row buffers are shared the same way, so fetching from either statement overwrites them.
+    /// `DescType::ImpRowDesc` can not be the target of a copy, the driver returns an error for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let year = 1968;
+    /// let template = Statement::with_parent(&conn)?.bind_parameter(1, &year)?;
+    /// let stmt = Statement::with_parent(&conn)?
+    ///     .copy_descriptor_from(&template, DescType::AppParamDesc)?
+    ///     .copy_descriptor_from(&template, DescType::ImpParamDesc)?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES WHERE YEAR = ?")? {
+    ///     while let Some(mut cursor) = stmt.fetch()? {
+    ///         println!("{:?}", cursor.get_data::<String>(1)?);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_descriptor_from<'c, S2, R2>(
+        self,
+        other: &'c Statement<'a, 'c, S2, R2, AC>,
+        desc_type: DescType,
+    ) -> Result<Statement<'a, 'c, S, R, AC>>
+    where
+        'b: 'c,
+    {
+        let source = other.raii.descriptor(desc_type).into_result(&other.diag())?;
+        let target = self.raii.descriptor(desc_type).into_result(&self.diag())?;
+        let target = RawDescriptor(target);
+        copy_desc(source, &target).into_result(&target)?;
+        Ok(self)
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    pub(super) fn descriptor(&self, desc_type: DescType) -> Return<ffi::SQLHDESC> {
+        let mut desc: ffi::SQLHDESC = null_mut();
+        match unsafe {
+            ffi::SQLGetStmtAttr(
+                self.handle(),
+                desc_type.attribute(),
+                &mut desc as *mut ffi::SQLHDESC as ffi::SQLPOINTER,
+                0,
+                null_mut(),
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(desc),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(desc),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLGetStmtAttr returned unexpected result: {:?}", r),
+        }
+    }
+}
+
+fn copy_desc(source: ffi::SQLHDESC, target: &RawDescriptor) -> Return<()> {
+    match unsafe { ffi::SQLCopyDesc(source, target.0) } {
+        ffi::SQL_SUCCESS => Return::Success(()),
+        ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+        ffi::SQL_ERROR => Return::Error,
+        r => panic!("SQLCopyDesc returned unexpected result: {:?}", r),
+    }
+}
@@ -0,0 +1,160 @@
+//! Fetching rows without blocking the thread, for statements executed asynchronously, see
+//! `Statement::fetch_async`
+use super::{poll_while_executing, Cursor, HasResult, Statement, StatementAttr};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use {ffi, Handle, Raii, Result, Return};
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Lets the driver execute the functions called on the statement asynchronously. Shorthand
+    /// for `set_attr` with `StatementAttr::AsyncEnable(true)`, which can be chained before
+    /// executing the statement.
+    ///
+    /// The blocking methods of `Statement` keep working and poll the driver until the function
+    /// completes. Use `fetch_async` to retrieve rows without blocking the thread. Drivers which do
+    /// not support asynchronous execution return an error for which `is_unsupported` is `true`.
+    pub fn enable_async(mut self) -> Result<Self> {
+        self.set_attr(StatementAttr::AsyncEnable(true))?;
+        Ok(self)
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Fetches the next row like `fetch`, but returns a future, which completes once the driver
+    /// has fetched the row. Requires asynchronous execution to be enabled with `enable_async`,
+    /// otherwise the row is fetched by the first call to `poll`, blocking the thread.
+    ///
+    /// ODBC offers no portable way of being notified on completion, so the future has to ask the
+    /// driver again. As long as the driver returns `SQL_STILL_EXECUTING`, the future wakes its
+    /// task right away and returns `Poll::Pending`. Other tasks of the executor get to run in
+    /// between, but the task never goes to sleep: the future spins, keeping a thread of the
+    /// executor busy until the row has been fetched. Prefer the blocking `fetch` on a dedicated
+    /// thread, if that is a concern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # use std::future::Future;
+    /// # use std::task::{Context, Poll, Waker};
+    /// // A minimal executor, polling the future until it completes
+    /// fn block_on<F: Future>(future: F) -> F::Output {
+    ///     let mut future = Box::pin(future);
+    ///     let mut context = Context::from_waker(Waker::noop());
+    ///     loop {
+    ///         if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+    ///             return output;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?.enable_async()?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+    ///     // Within an `async fn` the future can be awaited instead
+    ///     while let Some(mut cursor) = block_on(stmt.fetch_async())? {
+    ///         println!("{:?}", cursor.get_data::<String>(1)?);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_async<'s>(&'s mut self) -> FetchFuture<'s, 'a, 'b, S, AC> {
+        FetchFuture {
+            stmt: Some(self),
+            in_flight: false,
+        }
+    }
+}
+
+/// Future returned by `Statement::fetch_async`. Yields the cursor of the fetched row, or `None`
+/// at the end of the result set.
+///
+/// Dropping the future while the driver is still executing `SQLFetch` cancels the fetch, using
+/// `SQLCancel`, and blocks until the driver has acknowledged the cancellation. The statement can
+/// be used again afterwards. Whether the row being fetched is skipped depends on how far the
+/// driver got.
+#[must_use = "futures do nothing unless polled"]
+pub struct FetchFuture<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> {
+    // `None` once the future has completed
+    stmt: Option<&'s mut Statement<'a, 'b, S, HasResult, AC>>,
+    // `true` once the driver has returned `SQL_STILL_EXECUTING`, until the fetch completes
+    in_flight: bool,
+}
+
+impl<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> fmt::Debug for FetchFuture<'s, 'a, 'b, S, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FetchFuture")
+            .field("completed", &self.stmt.is_none())
+            .field("in_flight", &self.in_flight)
+            .finish()
+    }
+}
+
+impl<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> Future for FetchFuture<'s, 'a, 'b, S, AC> {
+    type Output = Result<Option<Cursor<'s, 'a, 'b, S, AC>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let ret = {
+            let stmt = this.stmt.as_mut().expect("FetchFuture polled after completion");
+            match stmt.raii.fetch_once() {
+                Some(ret) => ret,
+                None => {
+                    this.in_flight = true;
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            }
+        };
+        this.in_flight = false;
+        let stmt = this.stmt.take().unwrap();
+        let ret = ret.into_result(&stmt.diag());
+        if let Ok(has_row) = ret {
+            stmt.instrumentation.fetched(has_row);
+        }
+        Poll::Ready(match ret {
+            Ok(true) => {
+                let buffer = vec![0; stmt.buffer_size];
+                Ok(Some(Cursor { stmt, buffer }))
+            }
+            Ok(false) => Ok(None),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> Drop for FetchFuture<'s, 'a, 'b, S, AC> {
+    fn drop(&mut self) {
+        if !self.in_flight {
+            return;
+        }
+        if let Some(ref mut stmt) = self.stmt {
+            // Errors can not be reported from here. If the driver does not honor the cancellation,
+            // the fetch simply runs to completion below.
+            let _ = stmt.cancel();
+            // The canceled function has to be called until it no longer returns
+            // `SQL_STILL_EXECUTING`, before any other function may be called on the statement
+            let _ = poll_while_executing(|| unsafe { ffi::SQLFetch(stmt.raii.handle()) });
+        }
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    /// Calls `SQLFetch` once. `None` if the driver is still executing it asynchronously.
+    fn fetch_once(&mut self) -> Option<Return<bool>> {
+        match unsafe { ffi::SQLFetch(self.handle()) } {
+            ffi::SQL_STILL_EXECUTING => None,
+            ffi::SQL_SUCCESS => Some(Return::Success(true)),
+            ffi::SQL_SUCCESS_WITH_INFO => Some(Return::SuccessWithInfo(true)),
+            ffi::SQL_ERROR => Some(Return::Error),
+            ffi::SQL_NO_DATA => Some(Return::Success(false)),
+            r => panic!("SQLFetch returned unexpected result: {:?}", r),
+        }
+    }
+}
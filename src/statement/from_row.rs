@@ -0,0 +1,118 @@
+//! Mapping rows of a result set to application types, see `FromRow`
+use super::Cursor;
+use odbc_safe::AutocommitMode;
+use Result;
+
+/// Types which can be constructed from the current row of a `Cursor`
+///
+/// With the `derive` feature, `#[derive(FromRow)]` implements this trait for structs with named
+/// fields. Each field is retrieved with `Cursor::get_data` from the column of the same name,
+/// compared ignoring ASCII case. Fields of type `Option<T>` are `None` for `NULL` values. For any
+/// other field, `NULL` is reported as an error with state `22002`, a missing column as error with
+/// state `42S22`. Columns are retrieved in ascending order, as required by many drivers.
+///
+/// Field attributes:
+///
+/// * `#[odbc(rename = "COLUMN_NAME")]` retrieves the field from the column `COLUMN_NAME`.
+/// * `#[odbc(skip)]` does not retrieve the field at all, but initializes it using `Default`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # mod doc {
+/// # use odbc::*;
+/// #[derive(FromRow)]
+/// struct Movie {
+///     title: String,
+///     #[odbc(rename = "YEAR")]
+///     released: Option<i32>,
+///     #[odbc(skip)]
+///     watched: bool,
+/// }
+///
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// let stmt = Statement::with_parent(&conn)?;
+/// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES")? {
+///     while let Some(mut cursor) = stmt.fetch()? {
+///         let movie = Movie::from_cursor(&mut cursor)?;
+///         println!("{} ({:?})", movie.title, movie.released);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+pub trait FromRow: Sized {
+    /// Constructs a value from the current row of `cursor`
+    fn from_cursor<'s, 'a, 'b, S, AC: AutocommitMode>(
+        cursor: &mut Cursor<'s, 'a, 'b, S, AC>,
+    ) -> Result<Self>;
+}
+
+/// Used by the code generated by `#[derive(FromRow)]`. Not part of the public API.
+#[doc(hidden)]
+pub mod derive_support {
+    use super::super::row::find_column;
+    use super::super::Cursor;
+    use odbc_safe::AutocommitMode;
+    use {DiagnosticRecord, Result};
+
+    /// Names of all columns of the result set
+    pub fn column_names<'s, 'a, 'b, S, AC: AutocommitMode>(
+        cursor: &Cursor<'s, 'a, 'b, S, AC>,
+    ) -> Result<Vec<String>> {
+        (1..=cursor.column_count()?)
+            .map(|col| cursor.column_name(col))
+            .collect()
+    }
+
+    /// Column number of the column, from which the field `name` is retrieved
+    pub fn column_number(columns: &[String], name: &str) -> Result<u16> {
+        match find_column(columns, name) {
+            Some(index) => Ok(index as u16 + 1),
+            None => Err(DiagnosticRecord::with_message(
+                b"42S22",
+                &format!("Result set has no column named {:?}", name),
+            )),
+        }
+    }
+
+    /// Value of a field, which must not be `NULL`
+    pub fn required<T>(value: Option<T>, name: &str) -> Result<T> {
+        value.ok_or_else(|| {
+            DiagnosticRecord::with_message(
+                b"22002",
+                &format!("Column {:?} is NULL, but the field is not an Option", name),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::derive_support::*;
+
+    #[test]
+    fn find_column_numbers() {
+        let columns = vec!["TITLE".to_owned(), "YEAR".to_owned()];
+        assert_eq!(column_number(&columns, "TITLE").unwrap(), 1);
+        assert_eq!(column_number(&columns, "year").unwrap(), 2);
+        let error = column_number(&columns, "DIRECTOR").unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"42S22");
+    }
+
+    #[test]
+    fn required_field() {
+        assert_eq!(required(Some(1968), "YEAR").unwrap(), 1968);
+        let error = required::<i32>(None, "YEAR").unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"22002");
+        assert_eq!(
+            error.to_string(),
+            "State: 22002, Native error: -1, Message: Column \"YEAR\" is NULL, but the field is \
+             not an Option"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use super::types::OdbcType;
 use odbc_safe::AutocommitMode;
 use statement::types::EncodedValue;
+#[cfg(feature = "log")]
+use super::instrument;
 use {ffi, Handle, Raii, Result, Return, Statement};
 
 impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
@@ -15,6 +17,9 @@ impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
     /// * `parameter_index` - Index of the marker to bind to the parameter. Starting at `1`
     /// * `value` - Reference to bind to the marker
     ///
+    /// Bind a reference to an `Option<T>` to pass a nullable parameter. `None` is passed as `NULL`,
+    /// using the same C and SQL data type as `T`.
+    ///
     /// # Example
     /// ```
     /// # use odbc::*;
@@ -41,21 +46,31 @@ impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
         T: ?Sized,
         'b: 'c,
     {
+        //the result of value_ptr is changed per calling.
+        //binding and saving must have the same value.
+        let enc_value = value.encoded_value();
+
+        // The length of the value passed to the driver, which is the encoded one, if any
         let ind = if value.value_ptr() == 0 as *const Self as ffi::SQLPOINTER {
             ffi::SQL_NULL_DATA
+        } else if enc_value.has_value() {
+            enc_value.column_size() as ffi::SQLLEN
         } else {
             value.column_size() as ffi::SQLLEN
         };
 
         let ind_ptr = self.param_ind_buffers.alloc(parameter_index as usize, ind);
-
-        //the result of value_ptr is changed per calling.
-        //binding and saving must have the same value.
-        let enc_value = value.encoded_value();
+        #[cfg(feature = "log")]
+        {
+            if log_enabled!(::log::Level::Trace) {
+                let bytes = instrument::parameter_bytes(value, &enc_value);
+                instrument::bind_parameter(parameter_index, T::c_data_type(), bytes);
+            }
+        }
 
         self.raii
             .bind_input_parameter(parameter_index, value, ind_ptr, &enc_value)
-            .into_result(&self)?;
+            .into_result(&self.diag())?;
 
         // save encoded value to avoid memory reuse.
         if enc_value.has_value() {
@@ -65,16 +80,104 @@ impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
         Ok(self)
     }
 
+    /// Binds the elements of `values` to consecutive parameter markers, starting with
+    /// `start_index`. Combined with `expand_in_params` this allows passing a list of values to an
+    /// `IN` clause.
+    ///
+    /// # Panics
+    ///
+    /// If the index of the last parameter exceeds `u16::MAX`
+    ///
+    /// # Example
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let years = [1968, 1993];
+    /// let sql = expand_in_params("SELECT TITLE FROM MOVIES WHERE YEAR IN (:years)", ":years", years.len());
+    /// let stmt = Statement::with_parent(&conn)?.bind_parameter_slice(1, &years)?;
+    /// if let Data(mut stmt) = stmt.exec_direct(&sql)? {
+    ///     // ...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bind_parameter_slice<'c, T>(
+        self,
+        start_index: u16,
+        values: &'c [T],
+    ) -> Result<Statement<'a, 'c, S, R, AC>>
+    where
+        T: OdbcType<'c>,
+        'b: 'c,
+    {
+        let mut stmt: Statement<'a, 'c, S, R, AC> = self;
+        for (offset, value) in values.iter().enumerate() {
+            let index = start_index as usize + offset;
+            assert!(index <= u16::MAX as usize, "parameter index {} exceeds u16::MAX", index);
+            stmt = stmt.bind_parameter(index as u16, value)?;
+        }
+        Ok(stmt)
+    }
+
     /// Releasing all parameter buffers set by `bind_parameter`. This method consumes the statement
     /// and returns a new one those lifetime is no longer limited by the buffers bound.
+    ///
+    /// Wraps `SQLFreeStmt` with `SQL_RESET_PARAMS`. Use it to bind a fresh set of parameters
+    /// before executing a prepared statement again. Column buffers are released by `unbind` on
+    /// `BoundStatement` or `RowsetStatement`, which then allow using `get_data` again.
     pub fn reset_parameters(mut self) -> Result<Statement<'a, 'a, S, R, AC>> {
-        self.param_ind_buffers.clear();
-        self.encoded_values.clear();
-        self.raii.reset_parameters().into_result(&mut self)?;
-        Ok(Statement::with_raii(self.raii))
+        self.raii.reset_parameters().into_result(&self.diag())?;
+        Ok(self.without_parameters())
     }
 }
 
+/// Replaces the marker `param_name` in `sql` with `count` comma separated parameter markers
+/// (`?`). SQL does not allow binding a list to a single parameter, so use this to build
+/// statements like `WHERE ID IN (?, ?, ?)` and bind the values with `bind_parameter_slice`.
+///
+/// The marker has to be followed by a character which can not be part of an identifier, so
+/// `:id` does not match the start of `:ids`. Occurrences within quoted literals or identifiers
+/// are left unchanged. With a `count` of `0` the marker is replaced by `NULL`, since `IN ()` is
+/// not valid SQL, while `IN (NULL)` matches no rows.
+///
+/// ```
+/// # use odbc::*;
+/// let sql = expand_in_params("SELECT * FROM T WHERE ID IN (:ids)", ":ids", 3);
+/// assert_eq!(sql, "SELECT * FROM T WHERE ID IN (?, ?, ?)");
+/// ```
+pub fn expand_in_params(sql: &str, param_name: &str, count: usize) -> String {
+    let replacement = if count == 0 {
+        "NULL".to_owned()
+    } else {
+        vec!["?"; count].join(", ")
+    };
+    let mut expanded = String::with_capacity(sql.len() + replacement.len());
+    let mut quote = None;
+    let mut rest = sql;
+    while let Some(c) = rest.chars().next() {
+        match quote {
+            // Doubled quotes within a literal are seen as closing and reopening it, which is fine
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if !param_name.is_empty()
+                && rest.starts_with(param_name)
+                && !rest[param_name.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_') =>
+            {
+                expanded.push_str(&replacement);
+                rest = &rest[param_name.len()..];
+                continue;
+            }
+            None => (),
+        }
+        expanded.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    expanded
+}
+
 impl<'p> Raii<'p, ffi::Stmt> {
     fn bind_input_parameter<'c, T>(
         &mut self,
@@ -87,11 +190,12 @@ impl<'p> Raii<'p, ffi::Stmt> {
         T: OdbcType<'c>,
         T: ?Sized,
     {
-        //if encoded value exists, use it.
-        let (column_size, value_ptr) = if enc_value.has_value() {
-            (enc_value.column_size(), enc_value.value_ptr())
+        //if encoded value exists, use it. The column size is that of the value, e.g. the
+        //precision of a decimal sent as text.
+        let value_ptr = if enc_value.has_value() {
+            enc_value.value_ptr()
         } else {
-            (value.column_size(), value.value_ptr())
+            value.value_ptr()
         };
 
         match unsafe {
@@ -101,7 +205,7 @@ impl<'p> Raii<'p, ffi::Stmt> {
                 ffi::SQL_PARAM_INPUT,
                 T::c_data_type(),
                 T::sql_data_type(),
-                column_size,
+                value.column_size(),
                 value.decimal_digits(),
                 value_ptr,
                 0,                  // buffer length
@@ -124,3 +228,30 @@ impl<'p> Raii<'p, ffi::Stmt> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expand_in_params_replaces_marker() {
+        assert_eq!(
+            expand_in_params("SELECT * FROM T WHERE A IN (:a) AND B = ?", ":a", 2),
+            "SELECT * FROM T WHERE A IN (?, ?) AND B = ?"
+        );
+        assert_eq!(expand_in_params("WHERE A IN (:a)", ":a", 1), "WHERE A IN (?)");
+        assert_eq!(expand_in_params("WHERE A IN (:a)", ":a", 0), "WHERE A IN (NULL)");
+    }
+
+    #[test]
+    fn expand_in_params_skips_literals_and_longer_names() {
+        assert_eq!(
+            expand_in_params("WHERE A IN (:a) AND B = ':a' AND \":a\" = :ab", ":a", 2),
+            "WHERE A IN (?, ?) AND B = ':a' AND \":a\" = :ab"
+        );
+        assert_eq!(
+            expand_in_params("WHERE A = 'it''s' OR A IN (:a)", ":a", 1),
+            "WHERE A = 'it''s' OR A IN (?)"
+        );
+    }
+}
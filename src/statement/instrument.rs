@@ -0,0 +1,281 @@
+//! Instrumentation of statement execution and result set retrieval, using `tracing`, `log` and
+//! `metrics` if the features of the same name are enabled. Without them every hook compiles to
+//! nothing.
+#[cfg(feature = "log")]
+use super::types::{EncodedValue, OdbcType};
+#[cfg(feature = "log")]
+use std::slice::from_raw_parts;
+use std::borrow::Cow;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::time::Instant;
+#[cfg(feature = "log")]
+use ffi;
+
+/// Kept by each statement, to report the duration and row count of its result set once it has
+/// been exhausted.
+#[derive(Debug, Default)]
+pub(super) struct Instrumentation {
+    #[cfg(feature = "tracing")]
+    result_set: Option<ResultSetTrace>,
+    // SQL text of the prepared statement, reported on each execution
+    #[cfg(any(feature = "tracing", feature = "log"))]
+    prepared_text: Option<String>,
+}
+
+#[cfg(feature = "tracing")]
+#[derive(Debug)]
+struct ResultSetTrace {
+    span: ::tracing::Span,
+    started: Instant,
+    rows: u64,
+}
+
+/// Keeps a span entered, until dropped
+pub(super) struct SpanGuard {
+    #[cfg(feature = "tracing")]
+    _span: ::tracing::span::EnteredSpan,
+}
+
+/// Keeps the span of an execution entered and records its duration in the histogram
+/// `sql.query.duration_seconds`, once dropped
+pub(super) struct ExecGuard {
+    #[cfg(feature = "tracing")]
+    _span: ::tracing::span::EnteredSpan,
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for ExecGuard {
+    fn drop(&mut self) {
+        ::metrics::histogram!("sql.query.duration_seconds")
+            .record(self.started.elapsed().as_secs_f64());
+    }
+}
+
+impl Instrumentation {
+    pub(super) fn new() -> Self {
+        Instrumentation::default()
+    }
+
+    /// Called before `SQLExecDirect`, the execution lasts until the guard is dropped
+    #[cfg_attr(not(any(feature = "tracing", feature = "log")), allow(unused_variables))]
+    pub(super) fn exec_direct(&mut self, statement_text: &str) -> ExecGuard {
+        #[cfg(feature = "log")]
+        debug!("executing {}", statement_text);
+        #[cfg(feature = "tracing")]
+        let span = ::tracing::info_span!("sql.exec_direct", sql = %statement_text);
+        #[cfg(feature = "tracing")]
+        self.trace_result_set(&span);
+        ExecGuard {
+            #[cfg(feature = "tracing")]
+            _span: span.entered(),
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+
+    /// Called before `SQLPrepare`, remembers the SQL text for `execute`
+    #[cfg_attr(not(any(feature = "tracing", feature = "log")), allow(unused_variables))]
+    pub(super) fn prepare(&mut self, statement_text: &str) {
+        #[cfg(any(feature = "tracing", feature = "log"))]
+        {
+            self.prepared_text = Some(statement_text.to_owned());
+        }
+    }
+
+    /// Like `prepare`, for statement text in the encoding of the database
+    pub(super) fn prepare_bytes(&mut self, statement_text: &[u8]) {
+        self.prepare(&decode_statement_text(statement_text))
+    }
+
+    /// Called before `SQLExecute` of a prepared statement, the execution lasts until the guard is
+    /// dropped
+    pub(super) fn execute(&mut self) -> ExecGuard {
+        #[cfg(any(feature = "tracing", feature = "log"))]
+        let statement_text = self.prepared_text.as_deref().unwrap_or_default();
+        #[cfg(feature = "log")]
+        debug!("executing prepared statement {}", statement_text);
+        #[cfg(feature = "tracing")]
+        let span = ::tracing::info_span!("sql.execute", sql = %statement_text);
+        #[cfg(feature = "tracing")]
+        self.trace_result_set(&span);
+        ExecGuard {
+            #[cfg(feature = "tracing")]
+            _span: span.entered(),
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+
+    /// Starts measuring the result set of an execution traced by `span`
+    #[cfg(feature = "tracing")]
+    fn trace_result_set(&mut self, span: &::tracing::Span) {
+        self.result_set = Some(ResultSetTrace {
+            span: span.clone(),
+            started: Instant::now(),
+            rows: 0,
+        });
+    }
+
+    /// Like `exec_direct`, for statement text in the encoding of the database
+    pub(super) fn exec_direct_bytes(&mut self, statement_text: &[u8]) -> ExecGuard {
+        self.exec_direct(&decode_statement_text(statement_text))
+    }
+
+    /// Called before `SQLGetData`, the span lasts until the guard is dropped
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(super) fn get_data(col_or_param_num: u16) -> SpanGuard {
+        SpanGuard {
+            #[cfg(feature = "tracing")]
+            _span: ::tracing::trace_span!("sql.get_data", column = col_or_param_num).entered(),
+        }
+    }
+
+    /// Called after each successful `SQLFetch`, with `false` once the result set is exhausted.
+    /// Fetched rows are counted by `sql.query.rows_fetched`.
+    pub(super) fn fetched(&mut self, has_row: bool) {
+        self.fetched_rows(if has_row { 1 } else { 0 });
+    }
+
+    /// Like `fetched`, for a fetch which may return several rows at once, e.g. a rowset. `0` once
+    /// the result set is exhausted.
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "log", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(super) fn fetched_rows(&mut self, rows: u64) {
+        #[cfg(feature = "log")]
+        {
+            if rows > 0 {
+                trace!("fetched {} row(s)", rows);
+            } else {
+                trace!("fetched the end of the result set");
+            }
+        }
+        #[cfg(feature = "metrics")]
+        {
+            if rows > 0 {
+                ::metrics::counter!("sql.query.rows_fetched").increment(rows);
+            }
+        }
+        #[cfg(feature = "tracing")]
+        {
+            ::tracing::trace!("sql.fetch");
+            if rows > 0 {
+                if let Some(ref mut result_set) = self.result_set {
+                    result_set.rows += rows;
+                }
+            } else if let Some(result_set) = self.result_set.take() {
+                ::tracing::info!(
+                    parent: &result_set.span,
+                    elapsed_ms = result_set.started.elapsed().as_millis() as u64,
+                    rows = result_set.rows,
+                    "sql.result_set_exhausted"
+                );
+            }
+        }
+    }
+}
+
+/// Statement text passed to the driver as bytes, decoded only if it is logged or traced
+#[cfg(any(feature = "tracing", feature = "log"))]
+fn decode_statement_text(statement_text: &[u8]) -> Cow<'_, str> {
+    unsafe { ::environment::DB_ENCODING }.decode(statement_text).0
+}
+
+#[cfg(not(any(feature = "tracing", feature = "log")))]
+fn decode_statement_text(_statement_text: &[u8]) -> Cow<'_, str> {
+    Cow::Borrowed("")
+}
+
+/// Logs the value of a parameter at trace level, as it is passed to the driver. Character data is
+/// shown as text, anything else as bytes.
+#[cfg(feature = "log")]
+pub(super) fn bind_parameter(
+    parameter_index: u16,
+    c_data_type: ffi::SqlCDataType,
+    value: Option<&[u8]>,
+) {
+    match value {
+        Some(bytes) if c_data_type == ffi::SQL_C_CHAR => {
+            let text = unsafe { ::environment::DB_ENCODING }.decode(bytes).0;
+            trace!("binding parameter {}: {:?}", parameter_index, text)
+        }
+        Some(bytes) => trace!("binding parameter {}: {:?}", parameter_index, bytes),
+        None => trace!("binding parameter {}: NULL", parameter_index),
+    }
+}
+
+/// Bytes passed to the driver for `value`. `None` for `NULL`.
+#[cfg(feature = "log")]
+pub(super) fn parameter_bytes<'v, 'c, T>(value: &'v T, encoded: &'v EncodedValue) -> Option<&'v [u8]>
+where
+    T: OdbcType<'c>,
+{
+    if let Some(ref buf) = encoded.buf {
+        Some(buf)
+    } else if value.value_ptr().is_null() {
+        None
+    } else {
+        Some(unsafe { from_raw_parts(value.value_ptr() as *const u8, value.column_size() as usize) })
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use super::*;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records the `rows` field of every event
+    struct Rows(Arc<Mutex<Vec<u64>>>);
+
+    impl Visit for Rows {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if field.name() == "rows" {
+                self.0.lock().unwrap().push(value);
+            }
+        }
+        fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+    }
+
+    struct RowsSubscriber(Arc<Mutex<Vec<u64>>>);
+
+    impl Subscriber for RowsSubscriber {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+        fn new_span(&self, _span: &Attributes) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _span: &Id, _values: &Record) {}
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        fn event(&self, event: &Event) {
+            event.record(&mut Rows(self.0.clone()));
+        }
+        fn enter(&self, _span: &Id) {}
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn row_count_of_exhausted_result_set() {
+        let rows = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(RowsSubscriber(rows.clone()), || {
+            let mut instrumentation = Instrumentation::new();
+            let span = instrumentation.exec_direct("SELECT TITLE FROM MOVIES");
+            drop(span);
+            instrumentation.fetched(true);
+            instrumentation.fetched(true);
+            // A rowset
+            instrumentation.fetched_rows(3);
+            instrumentation.fetched(false);
+            // Reported only once
+            instrumentation.fetched(false);
+        });
+        assert_eq!(*rows.lock().unwrap(), [5]);
+    }
+}
@@ -3,13 +3,75 @@ mod types;
 mod input;
 mod output;
 mod prepare;
-pub use self::output::Output;
-use {ffi, safe, Connection, Return, Result, Raii, Handle};
+mod rows;
+mod row;
+mod from_row;
+mod catalog;
+mod attribute;
+mod batch;
+mod cancel;
+mod bind;
+mod col_attribute;
+mod cursor_name;
+mod query;
+mod bulk;
+mod data_at_exec;
+mod decimal;
+mod descriptor;
+mod date_time;
+mod fetch_async;
+mod instrument;
+mod rowset;
+mod param_status;
+mod native_sql;
+mod set_pos;
+mod wide;
+pub use self::output::{Output, WideString};
+pub use self::rows::Rows;
+pub use self::row::{OdbcValue, Row};
+pub use self::from_row::FromRow;
+#[doc(hidden)]
+pub use self::from_row::derive_support as __derive;
+pub use self::cancel::CancelHandle;
+pub use self::batch::{BatchExecuted, BatchResult};
+pub use self::bind::{BoundStatement, FixedSizedType};
+pub use self::col_attribute::{ColAttr, ColAttrValue, ColumnAttrs};
+pub use self::bulk::BulkOperation;
+pub use self::catalog::{NullableOption, RowIdScope, SpecialColumnType};
+pub use self::data_at_exec::{ExecAtExecState, NeedData, ParamDataResult};
+pub use self::decimal::Decimal;
+pub use self::descriptor::DescType;
+pub use self::date_time::{OdbcDate, OdbcTime, OdbcTimestamp};
+pub use self::fetch_async::FetchFuture;
+pub use self::rowset::{RowStatus, Rowset, RowsetIter, RowsetRow, RowsetStatement};
+pub use self::param_status::ParamStatus;
+pub use self::set_pos::{LockType, SetPosOperation};
+use self::param_status::ParamStatusBuffers;
+use self::cancel::CancelGuard;
+use self::instrument::Instrumentation;
+pub use self::attribute::{Concurrency, CursorSensitivity, CursorType, StatementAttr,
+                          StatementAttrKey};
+use diagnostics::CollectWarnings;
+use {ffi, safe, Connection, DiagnosticContext, DiagnosticRecord, Return, Result, Raii, Handle};
 use ffi::SQLRETURN::*;
 use ffi::Nullable;
+use std::fmt;
 use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::thread;
+use std::time::Duration;
+pub use self::input::expand_in_params;
+pub use self::query::Query;
 pub use self::types::OdbcType;
-pub use self::types::{SqlDate, SqlTime, SqlSsTime2, SqlTimestamp, EncodedValue};
+use self::types::LossyString;
+pub use self::types::{SqlDate, SqlGuid, SqlTime, SqlSsTime2, SqlSsTimestampOffset, SqlTimestamp,
+                      EncodedValue};
+
+// Initial size of the buffer used by `Cursor::get_data`, unless changed with `with_buffer_size`
+const DEFAULT_BUFFER_SIZE: usize = 512;
+
+// Time to wait between calls to a function which returned `SQL_STILL_EXECUTING`
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
 
 // Allocate CHUNK_LEN elements at a time
 const CHUNK_LEN: usize = 64;
@@ -31,10 +93,6 @@ impl<T: Copy + Default> Chunks<T> {
         *v = value;
         v as *mut T
     }
-
-    fn clear(&mut self) {
-        self.0.clear()
-    }
 }
 
 /// `Statement` state used to represent a freshly allocated connection
@@ -62,11 +120,30 @@ pub enum ResultSetState<'a, 'b, S, AC: AutocommitMode> {
     NoData(Statement<'a, 'b, S, NoResult, AC>),
 }
 pub use ResultSetState::*;
-use std::ptr::null_mut;
+
+/// Selects the row to be fetched by `Statement::fetch_scroll`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FetchOrientation {
+    /// The next row
+    Next,
+    /// The row before the current one
+    Prior,
+    /// The first row of the result set
+    First,
+    /// The last row of the result set
+    Last,
+    /// The row with the given number, starting at 1. Negative numbers count from the end of the
+    /// result set, `-1` being the last row.
+    Absolute(i64),
+    /// The row with the given offset from the current one
+    Relative(i64),
+}
 use odbc_safe::AutocommitMode;
 
 /// A `Statement` can be used to execute queries and retrieves results.
 pub struct Statement<'a, 'b, S, R, AC: AutocommitMode> {
+    // Declared before `raii`, so it is dropped before the handle is freed
+    cancel: CancelGuard,
     raii: Raii<'a, ffi::Stmt>,
     state: PhantomData<S>,
     autocommit_mode: PhantomData<AC>,
@@ -76,6 +153,19 @@ pub struct Statement<'a, 'b, S, R, AC: AutocommitMode> {
     param_ind_buffers: Chunks<ffi::SQLLEN>,
     // encoded values are saved to use its pointer.
     encoded_values: Vec<EncodedValue>,
+    // Copies of the values bound by `Query`, which the driver holds pointers to
+    owned_values: Vec<Vec<u64>>,
+    // Initial size of the buffer of each `Cursor`
+    buffer_size: usize,
+    // Set by `enable_param_status`. Kept in every state, since the driver holds pointers to it.
+    param_status: Option<ParamStatusBuffers>,
+    // `SQL_DIAG_CURSOR_ROW_COUNT` of the last execution, see `cursor_row_count`
+    cursor_row_count: Option<usize>,
+    // Handle of the connection the statement has been allocated on, which outlives `'a`
+    connection: ffi::SQLHDBC,
+    // Warnings reported by the functions called on the statement, see `take_warnings`
+    diagnostics: DiagnosticContext,
+    instrumentation: Instrumentation,
 }
 
 /// Used to retrieve data from the fields of a query result
@@ -84,15 +174,103 @@ pub struct Cursor<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> {
     buffer: Vec<u8>,
 }
 
+/// Describes a column of a result set. Can be obtained via `Statement::describe_col`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ColumnDescriptor {
+    /// Name of the column
     pub name: String,
+    /// SQL data type of the column
     pub data_type: ffi::SqlDataType,
+    /// Size of the column on the data source. `None` if it can not be determined.
     pub column_size: Option<ffi::SQLULEN>,
+    /// Number of decimal digits of the column on the data source. `None` if it can not be
+    /// determined or is not applicable.
+    pub decimal_digits: Option<u16>,
+    /// `true` if the column allows `NULL` values, `false` if not and `None` if unknown.
+    pub nullable: Option<bool>,
+}
+
+/// Describes a parameter marker of a prepared statement. Can be obtained via
+/// `Statement::describe_param`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamDescriptor {
+    /// SQL data type the data source expects for the parameter
+    pub data_type: ffi::SqlDataType,
+    /// Column size of the parameter. `None` if it can not be determined.
+    pub param_size: Option<ffi::SQLULEN>,
+    /// Number of decimal digits of the parameter. `None` if it can not be determined or is not
+    /// applicable.
     pub decimal_digits: Option<u16>,
+    /// `true` if the parameter allows `NULL` values, `false` if not and `None` if unknown.
     pub nullable: Option<bool>,
 }
 
+/// Formats the column as `name (data type, column size, decimal digits)`, e.g.
+/// `TITLE (SQL_VARCHAR, 255)`. Column size and decimal digits are omitted if unknown.
+impl fmt::Display for ColumnDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ", self.name)?;
+        write_type(f, self.data_type, self.column_size, self.decimal_digits)
+    }
+}
+
+/// Formats the parameter as `(data type, parameter size, decimal digits)`, e.g.
+/// `(SQL_INTEGER, 10)`. Parameter size and decimal digits are omitted if unknown.
+impl fmt::Display for ParamDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_type(f, self.data_type, self.param_size, self.decimal_digits)
+    }
+}
+
+fn write_type(
+    f: &mut fmt::Formatter,
+    data_type: ffi::SqlDataType,
+    size: Option<ffi::SQLULEN>,
+    decimal_digits: Option<u16>,
+) -> fmt::Result {
+    // odbc-sys prefixes the types, which have been added by ODBC extensions, with `SQL_EXT_`
+    let name = format!("{:?}", data_type).replace("SQL_EXT_", "SQL_");
+    write!(f, "({}", name)?;
+    if let Some(size) = size {
+        write!(f, ", {}", size)?;
+    }
+    if let Some(decimal_digits) = decimal_digits {
+        write!(f, ", {}", decimal_digits)?;
+    }
+    f.write_str(")")
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> fmt::Debug for Statement<'a, 'b, S, R, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Statement")
+            .field("handle", &unsafe { self.raii.handle() })
+            .field("buffer_size", &self.buffer_size)
+            .finish()
+    }
+}
+
+impl<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> fmt::Debug for Cursor<'s, 'a, 'b, S, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("stmt", &self.stmt)
+            .field("buffer_len", &self.buffer.len())
+            .finish()
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> fmt::Debug for ResultSetState<'a, 'b, S, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Data(ref stmt) => f.debug_tuple("Data").field(stmt).finish(),
+            NoData(ref stmt) => f.debug_tuple("NoData").field(stmt).finish(),
+        }
+    }
+}
+
+/// `Statement` is `Send` for the same reasons as `Connection`, see its `Send` implementation, but
+/// it is not `Sync`. To cancel a statement executing in another thread use a `CancelHandle`.
+unsafe impl<'a, 'b, S, R, AC: AutocommitMode> Send for Statement<'a, 'b, S, R, AC> {}
+
 impl<'a, 'b, S, R, AC: AutocommitMode> Handle for Statement<'a, 'b, S, R, AC> {
     type To = ffi::Stmt;
     unsafe fn handle(&self) -> ffi::SQLHSTMT {
@@ -101,8 +279,9 @@ impl<'a, 'b, S, R, AC: AutocommitMode> Handle for Statement<'a, 'b, S, R, AC> {
 }
 
 impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
-    fn with_raii(raii: Raii<'a, ffi::Stmt>) -> Self {
+    fn with_raii(raii: Raii<'a, ffi::Stmt>, connection: ffi::SQLHDBC) -> Self {
         Statement {
+            cancel: CancelGuard::new(unsafe { raii.handle() }),
             raii: raii,
             autocommit_mode: PhantomData,
             state: PhantomData,
@@ -110,31 +289,162 @@ impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
             parameters: PhantomData,
             param_ind_buffers: Chunks::new(),
             encoded_values: Vec::new(),
+            owned_values: Vec::new(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            param_status: None,
+            cursor_row_count: None,
+            connection,
+            diagnostics: DiagnosticContext::new(),
+            instrumentation: Instrumentation::new(),
         }
     }
-}
 
-impl<'a, 'b, 'env, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
-    pub fn with_parent(ds: &'a Connection<'env, AC>) -> Result<Self> {
-        let raii = Raii::with_parent(ds).into_result(ds)?;
-        Ok(Self::with_raii(raii))
+    /// Moves the statement handle into a statement of another state. Bound parameters are carried
+    /// over, since they stay bound to the handle until `reset_parameters` is called.
+    fn into_state<S2, R2>(self) -> Statement<'a, 'b, S2, R2, AC> {
+        Statement {
+            cancel: self.cancel,
+            raii: self.raii,
+            autocommit_mode: PhantomData,
+            state: PhantomData,
+            result: PhantomData,
+            parameters: PhantomData,
+            param_ind_buffers: self.param_ind_buffers,
+            encoded_values: self.encoded_values,
+            owned_values: self.owned_values,
+            buffer_size: self.buffer_size,
+            param_status: self.param_status,
+            cursor_row_count: self.cursor_row_count,
+            connection: self.connection,
+            diagnostics: self.diagnostics,
+            instrumentation: self.instrumentation,
+        }
     }
 
-    pub fn affected_row_count(&self) -> Result<ffi::SQLLEN> {
-        self.raii.affected_row_count().into_result(self)
+    /// Releases the buffers of bound parameters, which must have been unbound from the handle
+    /// already, so the statement is no longer limited by their lifetime
+    fn without_parameters(self) -> Statement<'a, 'a, S, R, AC> {
+        Statement {
+            cancel: self.cancel,
+            raii: self.raii,
+            autocommit_mode: PhantomData,
+            state: PhantomData,
+            result: PhantomData,
+            parameters: PhantomData,
+            param_ind_buffers: Chunks::new(),
+            encoded_values: Vec::new(),
+            owned_values: Vec::new(),
+            buffer_size: self.buffer_size,
+            param_status: self.param_status,
+            cursor_row_count: self.cursor_row_count,
+            connection: self.connection,
+            diagnostics: self.diagnostics,
+            instrumentation: self.instrumentation,
+        }
     }
 
-    pub fn tables(self, catalog_name: &String, schema_name: &String, table_name: &String, table_type: &String) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
-        self.tables_str(catalog_name.as_str(), schema_name.as_str(), table_name.as_str(), table_type.as_str())
+    /// Number of columns of the result produced by the last execution. Reads the cursor row count
+    /// first, since `SQLNumResultCols` clears the diagnostics, including their header fields.
+    fn result_cols(&mut self) -> Result<i16> {
+        self.cursor_row_count = self.raii.cursor_row_count();
+        self.raii.num_result_cols().into_result(&self.diag())
     }
 
-    pub fn tables_str(self, catalog_name: &str, schema_name: &str, table_name: &str, table_type: &str) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
-        self.tables_opt_str(Option::Some(catalog_name), Option::Some(schema_name), Option::Some(table_name), table_type)
+    /// Changes the result state of the statement, keeping bound parameters
+    fn with_result<R2>(self) -> Statement<'a, 'b, S, R2, AC> {
+        Statement {
+            cancel: self.cancel,
+            raii: self.raii,
+            autocommit_mode: PhantomData,
+            state: PhantomData,
+            result: PhantomData,
+            parameters: PhantomData,
+            param_ind_buffers: self.param_ind_buffers,
+            encoded_values: self.encoded_values,
+            owned_values: self.owned_values,
+            buffer_size: self.buffer_size,
+            param_status: self.param_status,
+            cursor_row_count: self.cursor_row_count,
+            connection: self.connection,
+            diagnostics: self.diagnostics,
+            instrumentation: self.instrumentation,
+        }
+    }
+
+    /// Sets the initial size in bytes of the buffer each `Cursor` uses to retrieve column data.
+    /// Defaults to 512 bytes.
+    ///
+    /// A value which does not fit into the buffer is not truncated. Instead the buffer is enlarged
+    /// and the remaining data is retrieved with additional calls to `SQLGetData`. Choosing a size
+    /// large enough for the typical values of a query saves these calls, choosing a smaller one
+    /// saves memory if all values are small. The setting is kept when the statement changes its
+    /// state, e.g. on execution.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "buffer size may not be zero");
+        self.buffer_size = size;
+        self
+    }
+
+    /// Returns the warnings reported by the functions called on this statement so far, e.g.
+    /// about truncated data or changed option values, and removes them from the statement.
+    ///
+    /// These are the diagnostic records of functions returning `SQL_SUCCESS_WITH_INFO`, which are
+    /// not errors and therefore not part of any `Result`. They are kept when the statement
+    /// changes its state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+    ///     for warning in stmt.take_warnings() {
+    ///         println!("{}", warning);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn take_warnings(&self) -> Vec<DiagnosticRecord> {
+        self.diagnostics.take_warnings()
+    }
+
+    /// Diagnostics of the statement, to be passed to `into_result`, so warnings end up in
+    /// `take_warnings`
+    fn diag(&self) -> CollectWarnings<'_, Raii<'a, ffi::Stmt>> {
+        CollectWarnings::new(&self.raii, &self.diagnostics)
+    }
+
+    /// Frees the statement handle with `SQLFreeHandle`, rather than on drop, which only logs
+    /// errors. Any open cursor is closed and bound buffers are released.
+    ///
+    /// The statement is consumed even if an error is returned, so the handle can not be freed
+    /// twice. In that case freeing it is attempted once more, before this method returns.
+    pub fn close(self) -> Result<()> {
+        let Statement { cancel, raii, .. } = self;
+        // Invalidate all `CancelHandle`s, before the handle is freed
+        drop(cancel);
+        raii.free()
     }
 
-    pub fn tables_opt_str(mut self, catalog_name: Option<&str>, schema_name: Option<&str>, table_name:Option<&str>, table_type: &str) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
-        self.raii.tables(catalog_name, schema_name, table_name, table_type).into_result(&self)?;
-        Ok(Statement::with_raii(self.raii))
+    /// Releases all parameter buffers using `SQLFreeStmt` with `SQL_RESET_PARAMS`, then frees the
+    /// statement handle. See `reset_parameters` and `close`.
+    pub fn free_params_and_close(self) -> Result<()> {
+        self.reset_parameters()?.close()
+    }
+}
+
+impl<'a, 'b, 'env, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    pub fn with_parent(ds: &'a Connection<'env, AC>) -> Result<Self> {
+        let raii = Raii::with_parent(ds).into_result(ds)?;
+        Ok(Self::with_raii(raii, unsafe { ds.handle() }))
     }
 
     /// Executes a preparable statement, using the current values of the parameter marker variables
@@ -142,15 +452,16 @@ impl<'a, 'b, 'env, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC
     ///
     /// `SQLExecDirect` is the fastest way to submit an SQL statement for one-time execution.
     pub fn exec_direct(mut self, statement_text: &str) -> Result<ResultSetState<'a, 'b, Executed, AC>> {
-        if self.raii.exec_direct(statement_text).into_result(&self)? {
-            let num_cols = self.raii.num_result_cols().into_result(&self)?;
+        let _span = self.instrumentation.exec_direct(statement_text);
+        if self.raii.exec_direct(statement_text).into_result(&self.diag())? {
+            let num_cols = self.result_cols()?;
             if num_cols > 0 {
-                Ok(ResultSetState::Data(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::Data(self.into_state()))
             } else {
-                Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::NoData(self.into_state()))
             }
         } else {
-            Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+            Ok(ResultSetState::NoData(self.into_state()))
         }
     }
 
@@ -159,23 +470,96 @@ impl<'a, 'b, 'env, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC
     ///
     /// `SQLExecDirect` is the fastest way to submit an SQL statement for one-time execution.
     pub fn exec_direct_bytes(mut self, bytes: &[u8]) -> Result<ResultSetState<'a, 'b, Executed, AC>> {
-        if self.raii.exec_direct_bytes(bytes).into_result(&self)? {
-            let num_cols = self.raii.num_result_cols().into_result(&self)?;
+        let _span = self.instrumentation.exec_direct_bytes(bytes);
+        if self.raii.exec_direct_bytes(bytes).into_result(&self.diag())? {
+            let num_cols = self.result_cols()?;
             if num_cols > 0 {
-                Ok(ResultSetState::Data(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::Data(self.into_state()))
             } else {
-                Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::NoData(self.into_state()))
             }
         } else {
-            Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+            Ok(ResultSetState::NoData(self.into_state()))
         }
     }
 }
 
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Like `affected_row_count`, but available with or without an open result set, and `None`
+    /// if the driver does not know the number of rows.
+    ///
+    /// For `UPDATE`, `INSERT` and `DELETE` statements this is the number of affected rows. Some
+    /// drivers also report the number of rows of a `SELECT` statement, while others return
+    /// `None` for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let count = match stmt.exec_direct("UPDATE MOVIES SET YEAR = YEAR")? {
+    ///     Data(stmt) => stmt.affected_rows()?,
+    ///     NoData(stmt) => stmt.affected_rows()?,
+    /// };
+    /// println!("{:?}", count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn affected_rows(&self) -> Result<Option<ffi::SQLLEN>> {
+        let count = self.raii.affected_row_count().into_result(&self.diag())?;
+        Ok(if count < 0 { None } else { Some(count) })
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, NoResult, AC> {
+    /// Number of rows affected by the last `UPDATE`, `INSERT` or `DELETE` statement.
+    ///
+    /// Wraps `SQLRowCount`. Drivers return `-1` if the number of affected rows is not available.
+    /// The result is driver defined for any other kind of statement.
+    pub fn affected_row_count(&self) -> Result<ffi::SQLLEN> {
+        self.raii.affected_row_count().into_result(&self.diag())
+    }
+}
+
 impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
 
     pub fn affected_row_count(&self) -> Result<ffi::SQLLEN> {
-        self.raii.affected_row_count().into_result(self)
+        self.raii.affected_row_count().into_result(&self.diag())
+    }
+
+    /// Number of rows in the open cursor, if the driver knows it without fetching them. The
+    /// `SQL_DIAG_CURSOR_ROW_COUNT` diagnostic header field is read using `SQLGetDiagField` right
+    /// after the statement has been executed, since any later function call on the statement
+    /// resets it.
+    ///
+    /// Usually only static and keyset driven cursors can report their size, see
+    /// `Statement::set_cursor_type`. `None` if the count is not available, including result sets
+    /// of catalog functions. Drivers are free to report an approximate count, so do not rely on
+    /// it being exact.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_cursor_type(CursorType::Static)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+    ///     match stmt.cursor_row_count() {
+    ///         Some(count) => println!("Fetching {} movies", count),
+    ///         None => println!("Fetching movies"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cursor_row_count(&self) -> Option<usize> {
+        self.cursor_row_count
     }
 
     /// The number of columns in a result set
@@ -183,26 +567,93 @@ impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
     /// Can be called successfully only when the statement is in the prepared, executed, or
     /// positioned state. If the statement does not return columns the result will be 0.
     pub fn num_result_cols(&self) -> Result<i16> {
-        self.raii.num_result_cols().into_result(self)
+        self.raii.num_result_cols().into_result(&self.diag())
     }
 
     /// Returns description struct for result set column with a given index. Note: indexing is starting from 1.
     pub fn describe_col(&self, idx: u16) -> Result<ColumnDescriptor> {
-        self.raii.describe_col(idx).into_result(self)
+        self.raii.describe_col(idx).into_result(&self.diag())
     }
 
     /// Fetches the next rowset of data from the result set and returns data for all bound columns.
     pub fn fetch<'s>(&'s mut self) -> Result<Option<Cursor<'s, 'a, 'b, S, AC>>> {
-        if self.raii.fetch().into_result(self)? {
+        let has_row = self.raii.fetch().into_result(&self.diag())?;
+        self.instrumentation.fetched(has_row);
+        if has_row {
+            let buffer = vec![0; self.buffer_size];
+            Ok(Some(Cursor {
+                stmt: self,
+                buffer,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Fetches the row selected by `orientation` and returns data for all bound columns.
+    ///
+    /// Wraps `SQLFetchScroll`. Any orientation but `FetchOrientation::Next` requires a scrollable
+    /// cursor, so set `StatementAttr::CursorType` to something other than
+    /// `CursorType::ForwardOnly` using `set_attr` before executing the statement. Returns `None`
+    /// if the cursor is positioned before the start or after the end of the result set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_attr(StatementAttr::CursorType(CursorType::Static))?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR")? {
+    ///     if let Some(mut cursor) = stmt.fetch_scroll(FetchOrientation::Last)? {
+    ///         println!("Most recent movie: {:?}", cursor.get_data::<String>(1)?);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_scroll<'s>(
+        &'s mut self,
+        orientation: FetchOrientation,
+    ) -> Result<Option<Cursor<'s, 'a, 'b, S, AC>>> {
+        let (orientation, offset) = match orientation {
+            FetchOrientation::Next => (ffi::SQL_FETCH_NEXT, 0),
+            FetchOrientation::Prior => (ffi::SQL_FETCH_PRIOR, 0),
+            FetchOrientation::First => (ffi::SQL_FETCH_FIRST, 0),
+            FetchOrientation::Last => (ffi::SQL_FETCH_LAST, 0),
+            FetchOrientation::Absolute(n) => (ffi::SQL_FETCH_ABSOLUTE, n as ffi::SQLLEN),
+            FetchOrientation::Relative(n) => (ffi::SQL_FETCH_RELATIVE, n as ffi::SQLLEN),
+        };
+        let has_row = self.raii.fetch_scroll(orientation, offset).into_result(&self.diag())?;
+        self.instrumentation.fetched(has_row);
+        if has_row {
+            let buffer = vec![0; self.buffer_size];
             Ok(Some(Cursor {
                 stmt: self,
-                buffer: vec![0; 512],
+                buffer,
             }))
         } else {
             Ok(None)
         }
     }
 
+    /// Moves on to the next result set, if any. Wraps `SQLMoreResults`.
+    ///
+    /// Stored procedures and batches of SQL statements may return more than one result set.
+    /// Returns `None` if the current result set has been the last one. Any rows of the current
+    /// result set which have not been fetched yet are discarded. Some drivers report the results
+    /// of e.g. `INSERT` statements within a batch as result sets without any columns.
+    pub fn more_results(mut self) -> Result<Option<Statement<'a, 'b, S, HasResult, AC>>> {
+        if self.raii.more_results().into_result(&self.diag())? {
+            self.cursor_row_count = self.raii.cursor_row_count();
+            Ok(Some(self.into_state()))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Call this method to reuse the statement to execute another query.
     ///
     /// For many drivers allocating new statements is expensive. So reusing a `Statement` is usually
@@ -231,14 +682,18 @@ impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
     /// # };
     /// ```
     pub fn close_cursor(mut self) -> Result<Statement<'a, 'b, S, NoResult, AC>> {
-        self.raii.close_cursor().into_result(&self)?;
-        Ok(Statement::with_raii(self.raii))
+        self.raii.close_cursor().into_result(&self.diag())?;
+        Ok(self.into_state())
     }
 }
 
 impl<'a, 'b, 'c, S, AC: AutocommitMode> Cursor<'a, 'b, 'c, S, AC> {
     /// Retrieves data for a single column in the result set
     ///
+    /// Values of variable length, like `String` or `Vec<u8>`, are not truncated. If a value does
+    /// not fit into the cursor's buffer, the buffer is enlarged and `SQLGetData` is called again
+    /// to retrieve the remaining data.
+    ///
     /// ## Panics
     ///
     /// If you try to convert to `&str` but the data can't be converted
@@ -247,7 +702,66 @@ impl<'a, 'b, 'c, S, AC: AutocommitMode> Cursor<'a, 'b, 'c, S, AC> {
     where
         T: Output<'d>,
     {
-        T::get_data(&mut self.stmt.raii, col_or_param_num, &mut self.buffer).into_result(self.stmt)
+        let _span = Instrumentation::get_data(col_or_param_num);
+        T::get_data(&mut self.stmt.raii, col_or_param_num, &mut self.buffer)
+            .into_result(&self.stmt.diag())
+    }
+
+    /// Retrieves character data of a column as `String`, decoding it as UTF-8 and replacing
+    /// invalid byte sequences with `U+FFFD`.
+    ///
+    /// `get_data::<String>` decodes using the database encoding (see
+    /// `create_environment_v3_with_os_db_encoding`). This is a compatibility shim for drivers which return
+    /// data in an encoding other than the one expected, e.g. older ANSI drivers on Windows, where
+    /// losing a few characters is preferable to garbled text. Where possible, use a Unicode
+    /// capable driver instead, or retrieve the data as UTF-16 (`Vec<u16>`).
+    pub fn get_data_as_str_lossy(&mut self, col_or_param_num: u16) -> Result<Option<String>> {
+        let value = self.get_data::<LossyString>(col_or_param_num)?;
+        Ok(value.map(|value| value.0))
+    }
+
+    /// Number of columns in the result set. Wraps `SQLNumResultCols`.
+    pub fn column_count(&self) -> Result<u16> {
+        let num_cols = self.stmt.raii.num_result_cols().into_result(&self.stmt.diag())?;
+        Ok(num_cols as u16)
+    }
+
+    /// Name of a column in the result set. Wraps `SQLDescribeCol`. Column numbers start at `1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT * FROM MOVIES")? {
+    ///     while let Some(mut cursor) = stmt.fetch()? {
+    ///         for col in 1..=cursor.column_count()? {
+    ///             let name = cursor.column_name(col)?;
+    ///             println!("{} = {:?}", name, cursor.get_data::<String>(col)?);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn column_name(&self, col: u16) -> Result<String> {
+        let descriptor = self.stmt.raii.describe_col(col).into_result(&self.stmt.diag())?;
+        Ok(descriptor.name)
+    }
+}
+
+/// Calls `f` until it returns something other than `SQL_STILL_EXECUTING`. Functions executed
+/// asynchronously (see `StatementAttr::AsyncEnable`) must be called again with the same arguments
+/// until they complete.
+pub(crate) fn poll_while_executing<F: FnMut() -> ffi::SQLRETURN>(mut f: F) -> ffi::SQLRETURN {
+    loop {
+        match f() {
+            SQL_STILL_EXECUTING => thread::sleep(POLL_INTERVAL),
+            r => return r,
+        }
     }
 }
 
@@ -264,15 +778,37 @@ impl<'p> Raii<'p, ffi::Stmt> {
         }
     }
 
+    /// `None` if the driver does not report the count, or the call fails
+    fn cursor_row_count(&self) -> Option<usize> {
+        // Left untouched by drivers which do not support the field
+        let mut count: ffi::SQLLEN = -1;
+        let ret = unsafe {
+            ffi::SQLGetDiagField(
+                ffi::SQL_HANDLE_STMT,
+                self.handle() as ffi::SQLHANDLE,
+                0,
+                ffi::SqlHeaderDiagnosticIdentifier::SQL_DIAG_CURSOR_ROW_COUNT as ffi::SQLSMALLINT,
+                &mut count as *mut ffi::SQLLEN as ffi::SQLPOINTER,
+                0,
+                null_mut(),
+            )
+        };
+        match ret {
+            // Negative counts are used by some drivers to signal an unknown count
+            SQL_SUCCESS | SQL_SUCCESS_WITH_INFO if count >= 0 => Some(count as usize),
+            _ => None,
+        }
+    }
+
     fn num_result_cols(&self) -> Return<i16> {
         let mut num_cols: ffi::SQLSMALLINT = 0;
-        unsafe {
-            match ffi::SQLNumResultCols(self.handle(), &mut num_cols as *mut ffi::SQLSMALLINT) {
-                SQL_SUCCESS => Return::Success(num_cols),
-                SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(num_cols),
-                SQL_ERROR => Return::Error,
-                r => panic!("SQLNumResultCols returned unexpected result: {:?}", r),
-            }
+        match poll_while_executing(|| unsafe {
+            ffi::SQLNumResultCols(self.handle(), &mut num_cols as *mut ffi::SQLSMALLINT)
+        }) {
+            SQL_SUCCESS => Return::Success(num_cols),
+            SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(num_cols),
+            SQL_ERROR => Return::Error,
+            r => panic!("SQLNumResultCols returned unexpected result: {:?}", r),
         }
     }
 
@@ -283,8 +819,8 @@ impl<'p> Raii<'p, ffi::Stmt> {
         let mut column_size: ffi::SQLULEN = 0;
         let mut decimal_digits: ffi::SQLSMALLINT = 0;
         let mut nullable: Nullable = Nullable::SQL_NULLABLE_UNKNOWN;
-        unsafe {
-            match ffi::SQLDescribeCol(
+        let ret = match poll_while_executing(|| unsafe {
+            ffi::SQLDescribeCol(
                 self.handle(),
                 idx,
                 name_buffer.as_mut_ptr(),
@@ -294,52 +830,35 @@ impl<'p> Raii<'p, ffi::Stmt> {
                 &mut column_size as *mut ffi::SQLULEN,
                 &mut decimal_digits as *mut ffi::SQLSMALLINT,
                 &mut nullable as *mut ffi::Nullable,
-            ) {
-                SQL_SUCCESS => Return::Success(ColumnDescriptor {
-                    name: ::environment::DB_ENCODING.decode(&name_buffer[..(name_length as usize)]).0
-                        .to_string(),
-                    data_type: data_type,
-                    column_size: if column_size == 0 {
-                        None
-                    } else {
-                        Some(column_size)
-                    },
-                    decimal_digits: if decimal_digits == 0 {
-                        None
-                    } else {
-                        Some(decimal_digits as u16)
-                    },
-                    nullable: match nullable {
-                        Nullable::SQL_NULLABLE_UNKNOWN => None,
-                        Nullable::SQL_NULLABLE => Some(true),
-                        Nullable::SQL_NO_NULLS => Some(false),
-                    },
-                }),
-                SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(ColumnDescriptor {
-                    name: ::environment::DB_ENCODING.decode(&name_buffer[..(name_length as usize)]).0
-                        .to_string(),
-                    data_type: data_type,
-                    column_size: if column_size == 0 {
-                        None
-                    } else {
-                        Some(column_size)
-                    },
-                    decimal_digits: if decimal_digits == 0 {
-                        None
-                    } else {
-                        Some(decimal_digits as u16)
-                    },
-                    nullable: match nullable {
-                        Nullable::SQL_NULLABLE_UNKNOWN => None,
-                        Nullable::SQL_NULLABLE => Some(true),
-                        Nullable::SQL_NO_NULLS => Some(false),
-                    },
-                }),
-                SQL_ERROR => Return::Error,
-                r => panic!("SQLDescribeCol returned unexpected result: {:?}", r),
-            }
-        }
-
+            )
+        }) {
+            SQL_SUCCESS => Return::Success(()),
+            SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            SQL_ERROR => Return::Error,
+            r => panic!("SQLDescribeCol returned unexpected result: {:?}", r),
+        };
+        ret.map(|()| ColumnDescriptor {
+            name: unsafe { ::environment::DB_ENCODING }
+                .decode(&name_buffer[..(name_length as usize)])
+                .0
+                .to_string(),
+            data_type: data_type,
+            column_size: if column_size == 0 {
+                None
+            } else {
+                Some(column_size)
+            },
+            decimal_digits: if decimal_digits == 0 {
+                None
+            } else {
+                Some(decimal_digits as u16)
+            },
+            nullable: match nullable {
+                Nullable::SQL_NULLABLE_UNKNOWN => None,
+                Nullable::SQL_NULLABLE => Some(true),
+                Nullable::SQL_NO_NULLS => Some(false),
+            },
+        })
     }
 
     fn exec_direct(&mut self, statement_text: &str) -> Return<bool> {
@@ -349,17 +868,13 @@ impl<'p> Raii<'p, ffi::Stmt> {
         if length > ffi::SQLINTEGER::max_value() as usize {
             panic!("Statement text too long");
         }
-        match unsafe {
-            ffi::SQLExecDirect(
-                self.handle(),
-                bytes.as_ptr(),
-                length as ffi::SQLINTEGER,
-            )
-        } {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLExecDirect(self.handle(), bytes.as_ptr(), length as ffi::SQLINTEGER)
+        }) {
             ffi::SQL_SUCCESS => Return::Success(true),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
             ffi::SQL_ERROR => Return::Error,
-            ffi::SQL_NEED_DATA => panic!("SQLExecDirec returned SQL_NEED_DATA"),
+            ffi::SQL_NEED_DATA => Return::NeedData,
             ffi::SQL_NO_DATA => Return::Success(false),
             r => panic!("SQLExecDirect returned unexpected result: {:?}", r),
         }
@@ -370,17 +885,13 @@ impl<'p> Raii<'p, ffi::Stmt> {
         if length > ffi::SQLINTEGER::max_value() as usize {
             panic!("Statement text too long");
         }
-        match unsafe {
-            ffi::SQLExecDirect(
-                self.handle(),
-                bytes.as_ptr(),
-                length as ffi::SQLINTEGER,
-            )
-        } {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLExecDirect(self.handle(), bytes.as_ptr(), length as ffi::SQLINTEGER)
+        }) {
             ffi::SQL_SUCCESS => Return::Success(true),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
             ffi::SQL_ERROR => Return::Error,
-            ffi::SQL_NEED_DATA => panic!("SQLExecDirec returned SQL_NEED_DATA"),
+            ffi::SQL_NEED_DATA => Return::NeedData,
             ffi::SQL_NO_DATA => Return::Success(false),
             r => panic!("SQLExecDirect returned unexpected result: {:?}", r),
         }
@@ -388,7 +899,7 @@ impl<'p> Raii<'p, ffi::Stmt> {
 
     /// Fetches the next rowset of data from the result set and returns data for all bound columns.
     fn fetch(&mut self) -> Return<bool> {
-        match unsafe { ffi::SQLFetch(self.handle()) } {
+        match poll_while_executing(|| unsafe { ffi::SQLFetch(self.handle()) }) {
             ffi::SQL_SUCCESS => Return::Success(true),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
             ffi::SQL_ERROR => Return::Error,
@@ -397,58 +908,38 @@ impl<'p> Raii<'p, ffi::Stmt> {
         }
     }
 
-    fn tables(&mut self, catalog_name: Option<&str>, schema_name: Option<&str>, table_name: Option<&str>, table_type: &str) -> Return<()> {
-        unsafe {
-            let mut catalog: *const odbc_sys::SQLCHAR = null_mut();
-            let mut schema: *const odbc_sys::SQLCHAR = null_mut();
-            let mut table: *const odbc_sys::SQLCHAR = null_mut();
-
-            let mut catalog_size: odbc_sys::SQLSMALLINT = 0;
-            let mut schema_size: odbc_sys::SQLSMALLINT = 0;
-            let mut table_size: odbc_sys::SQLSMALLINT = 0;
-
-            if catalog_name.is_some() {
-                catalog = catalog_name.unwrap().as_ptr();
-                catalog_size = catalog_name.unwrap().len() as odbc_sys::SQLSMALLINT;
-            }
-
-            if schema_name.is_some() {
-                schema = schema_name.unwrap().as_ptr();
-                schema_size = schema_name.unwrap().len() as odbc_sys::SQLSMALLINT;
-            }
-
-            if table_name.is_some() {
-                table = table_name.unwrap().as_ptr();
-                table_size = table_name.unwrap().len() as odbc_sys::SQLSMALLINT;
-            }
+    fn fetch_scroll(
+        &mut self,
+        orientation: ffi::FetchOrientation,
+        offset: ffi::SQLLEN,
+    ) -> Return<bool> {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLFetchScroll(self.handle(), orientation, offset)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(true),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
+            ffi::SQL_ERROR => Return::Error,
+            ffi::SQL_NO_DATA => Return::Success(false),
+            r => panic!("SQLFetchScroll returned unexpected result: {:?}", r),
+        }
+    }
 
-            match odbc_sys::SQLTables(
-                self.handle(),
-                catalog,
-                catalog_size,
-                schema,
-                schema_size,
-                table,
-                table_size,
-                table_type.as_ptr(),
-                table_type.as_bytes().len() as odbc_sys::SQLSMALLINT,
-            ) {
-                SQL_SUCCESS => Return::Success(()),
-                SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
-                SQL_ERROR => Return::Error,
-                r => panic!("SQLTables returned: {:?}", r),
-            }
+    fn more_results(&mut self) -> Return<bool> {
+        match poll_while_executing(|| unsafe { ffi::SQLMoreResults(self.handle()) }) {
+            ffi::SQL_SUCCESS => Return::Success(true),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
+            ffi::SQL_ERROR => Return::Error,
+            ffi::SQL_NO_DATA => Return::Success(false),
+            r => panic!("SQLMoreResults returned unexpected result: {:?}", r),
         }
     }
 
     fn close_cursor(&mut self) -> Return<()> {
-        unsafe {
-            match ffi::SQLCloseCursor(self.handle()) {
-                ffi::SQL_SUCCESS => Return::Success(()),
-                ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
-                ffi::SQL_ERROR => Return::Error,
-                r => panic!("unexpected return value from SQLCloseCursor: {:?}", r),
-            }
+        match poll_while_executing(|| unsafe { ffi::SQLCloseCursor(self.handle()) }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("unexpected return value from SQLCloseCursor: {:?}", r),
         }
     }
 }
@@ -461,3 +952,44 @@ unsafe impl<'con, 'param, C, P, AC: AutocommitMode> safe::Handle for Statement<'
         <Raii<ffi::Stmt> as safe::Handle>::handle(&self.raii)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_column_descriptor() {
+        let column = ColumnDescriptor {
+            name: "TITLE".to_owned(),
+            data_type: ffi::SqlDataType::SQL_VARCHAR,
+            column_size: Some(255),
+            decimal_digits: None,
+            nullable: Some(true),
+        };
+        assert_eq!(column.to_string(), "TITLE (SQL_VARCHAR, 255)");
+        let column = ColumnDescriptor {
+            name: "PRICE".to_owned(),
+            data_type: ffi::SqlDataType::SQL_DECIMAL,
+            column_size: Some(10),
+            decimal_digits: Some(2),
+            nullable: None,
+        };
+        assert_eq!(column.to_string(), "PRICE (SQL_DECIMAL, 10, 2)");
+        let column = ColumnDescriptor {
+            name: "NAME".to_owned(),
+            data_type: ffi::SqlDataType::SQL_EXT_WVARCHAR,
+            column_size: None,
+            decimal_digits: None,
+            nullable: None,
+        };
+        assert_eq!(column.to_string(), "NAME (SQL_WVARCHAR)");
+    }
+
+    #[test]
+    fn display_attribute_values() {
+        assert_eq!(CursorType::Static.to_string(), "SQL_CURSOR_STATIC");
+        assert_eq!(Concurrency::RowVersion.to_string(), "SQL_CONCUR_ROWVER");
+        assert_eq!(CursorType::Other(42).to_string(), "cursor type 42");
+        assert_eq!(StatementAttrKey::MaxRows.to_string(), "SQL_ATTR_MAX_ROWS");
+    }
+}
@@ -0,0 +1,51 @@
+//! Translation of SQL into the dialect of the data source, on the connection of a statement
+use super::{Allocated, NoResult, Statement};
+use connection::native_sql;
+use odbc_safe::AutocommitMode;
+use {ffi, safe, Handle, Result};
+
+/// Connection handle of a statement, used to call `SQLNativeSql` and to read its diagnostics
+struct RawConnection(ffi::SQLHDBC);
+
+impl Handle for RawConnection {
+    type To = ffi::Dbc;
+    unsafe fn handle(&self) -> ffi::SQLHDBC {
+        self.0
+    }
+}
+
+unsafe impl safe::Handle for RawConnection {
+    const HANDLE_TYPE: ffi::HandleType = ffi::SQL_HANDLE_DBC;
+
+    fn handle(&self) -> ffi::SQLHANDLE {
+        self.0 as ffi::SQLHANDLE
+    }
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    /// Returns `sql` as the driver would send it to the data source, with ODBC escape sequences
+    /// translated into its native dialect. Same as `Connection::native_sql` on the connection of
+    /// this statement, which is convenient for finding out why a driver rejects a statement
+    /// before passing it to `exec_direct` or `prepare`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// println!("{}", stmt.native_sql("SELECT {fn UCASE(TITLE)} FROM MOVIES")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn native_sql(&self, sql: &str) -> Result<String> {
+        let conn = RawConnection(self.connection);
+        let encoding = unsafe { ::environment::DB_ENCODING };
+        let bytes = encoding.encode(sql).0;
+        let ret = native_sql(&conn, &bytes).map(|native| encoding.decode(&native).0.into_owned());
+        // The driver records diagnostics on the connection, not on the statement
+        ret.into_result(&conn)
+    }
+}
@@ -1,8 +1,19 @@
+use environment::OUTPUT_NTS;
 use raii::Raii;
+use std::mem;
+use std::sync::atomic::Ordering;
 use {ffi, Handle, Return};
 use super::types::OdbcType;
+use super::poll_while_executing;
 
 /// Indicates that a type can be retrieved using `Cursor::get_data`
+///
+/// Implemented for every `OdbcType`. The numeric types are read directly into the cursor's buffer
+/// as the matching C type: `u8` as `SQL_C_UTINYINT`, `i8` as `SQL_C_STINYINT`, `i16` as
+/// `SQL_C_SSHORT`, `u16` as `SQL_C_USHORT`, `i32` as `SQL_C_SLONG`, `u32` as `SQL_C_ULONG`, `i64`
+/// as `SQL_C_SBIGINT`, `u64` as `SQL_C_UBIGINT`, `f32` as `SQL_C_FLOAT` and `f64` as
+/// `SQL_C_DOUBLE`. The driver converts the column to the requested type, so no string parsing is
+/// involved.
 pub unsafe trait Output<'a>: Sized {
     fn get_data(
         stmt: &mut Raii<ffi::Stmt>,
@@ -24,6 +35,204 @@ where
     }
 }
 
+/// Character data retrieved as UTF-16 (`SQL_C_WCHAR`) and decoded to a `String`
+///
+/// Unlike `String`, which is retrieved as `SQL_C_CHAR` and decoded using the database encoding,
+/// this leaves the conversion to the driver, so `NCHAR` and `NVARCHAR` columns can hold any
+/// character, regardless of the code page of an ANSI driver. Prefer it for such columns on
+/// Windows, e.g. with SQL Server. Unpaired surrogates are replaced with `U+FFFD`.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// let stmt = Statement::with_parent(&conn)?;
+/// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+///     while let Some(mut cursor) = stmt.fetch()? {
+///         if let Some(WideString(title)) = cursor.get_data::<WideString>(1)? {
+///             println!("{}", title);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WideString(pub String);
+
+unsafe impl<'a> Output<'a> for WideString {
+    fn get_data(
+        stmt: &mut Raii<ffi::Stmt>,
+        col_or_param_num: u16,
+        buffer: &'a mut Vec<u8>,
+    ) -> Return<Option<Self>> {
+        stmt.get_data::<&[u16]>(col_or_param_num, buffer)
+            .map(|chars| chars.map(|chars| WideString(String::from_utf16_lossy(chars))))
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use super::Output;
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use {ffi, DiagnosticRecord, Raii, Result, Return, SqlDate, SqlTime, SqlTimestamp};
+
+    fn naive_date(date: &SqlDate) -> Result<NaiveDate> {
+        NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32).ok_or_else(
+            || {
+                let message = format!("data source returned invalid date: {:?}", date);
+                DiagnosticRecord::with_message(b"22007", &message)
+            },
+        )
+    }
+
+    fn naive_time(hour: u16, minute: u16, second: u16, nanos: u32) -> Result<NaiveTime> {
+        NaiveTime::from_hms_nano_opt(hour as u32, minute as u32, second as u32, nanos).ok_or_else(
+            || {
+                let message = format!(
+                    "data source returned invalid time: {}:{}:{}.{:09}",
+                    hour, minute, second, nanos
+                );
+                DiagnosticRecord::with_message(b"22007", &message)
+            },
+        )
+    }
+
+    /// Reads `SQL_DATE_STRUCT`
+    ///
+    /// A date which does not exist, like `0000-00-00`, is reported as an error with SQLSTATE
+    /// `22007`. Retrieve such values as `OdbcDate` instead.
+    unsafe impl<'a> Output<'a> for NaiveDate {
+        fn get_data(
+            stmt: &mut Raii<ffi::Stmt>,
+            col_or_param_num: u16,
+            buffer: &'a mut Vec<u8>,
+        ) -> Return<Option<Self>> {
+            stmt.get_data::<SqlDate>(col_or_param_num, buffer)
+                .try_map(|date| date.map(|date| naive_date(&date)).transpose())
+        }
+    }
+
+    /// Reads `SQL_TIME_STRUCT`
+    unsafe impl<'a> Output<'a> for NaiveTime {
+        fn get_data(
+            stmt: &mut Raii<ffi::Stmt>,
+            col_or_param_num: u16,
+            buffer: &'a mut Vec<u8>,
+        ) -> Return<Option<Self>> {
+            stmt.get_data::<SqlTime>(col_or_param_num, buffer).try_map(|time| {
+                time.map(|time| naive_time(time.hour, time.minute, time.second, 0))
+                    .transpose()
+            })
+        }
+    }
+
+    /// Reads `SQL_TIMESTAMP_STRUCT`
+    ///
+    /// A date which does not exist, like `0000-00-00`, is reported as an error with SQLSTATE
+    /// `22007`. Retrieve such values as `OdbcTimestamp` instead.
+    unsafe impl<'a> Output<'a> for NaiveDateTime {
+        fn get_data(
+            stmt: &mut Raii<ffi::Stmt>,
+            col_or_param_num: u16,
+            buffer: &'a mut Vec<u8>,
+        ) -> Return<Option<Self>> {
+            stmt.get_data::<SqlTimestamp>(col_or_param_num, buffer).try_map(|ts| {
+                ts.map(|ts| {
+                    let date = SqlDate {
+                        year: ts.year,
+                        month: ts.month,
+                        day: ts.day,
+                    };
+                    let time = naive_time(ts.hour, ts.minute, ts.second, ts.fraction)?;
+                    Ok(naive_date(&date)?.and_time(time))
+                })
+                .transpose()
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn invalid_date_is_an_error() {
+            let date = SqlDate {
+                year: 0,
+                month: 0,
+                day: 0,
+            };
+            let error = naive_date(&date).unwrap_err();
+            assert_eq!(&error.get_raw_state()[..5], b"22007");
+            assert!(naive_time(24, 0, 0, 0).is_err());
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use super::Output;
+    use statement::types::time_date_time;
+    use time::{OffsetDateTime, UtcOffset};
+    use {ffi, DiagnosticRecord, GetDiagRec, Raii, Result, Return, SqlSsTimestampOffset,
+         SqlTimestamp};
+
+    /// Reads `SQL_SS_TIMESTAMPOFFSET_STRUCT`, if the driver supports it. Otherwise reads
+    /// `SQL_TIMESTAMP_STRUCT`, assuming the timestamp is in UTC.
+    ///
+    /// A timestamp which does not exist, like `0000-00-00 00:00:00`, is reported as an error with
+    /// SQLSTATE `22007`.
+    unsafe impl<'a> Output<'a> for OffsetDateTime {
+        fn get_data(
+            stmt: &mut Raii<ffi::Stmt>,
+            col_or_param_num: u16,
+            buffer: &'a mut Vec<u8>,
+        ) -> Return<Option<Self>> {
+            // Reborrow, so the buffer can be used again for the fallback
+            match stmt.get_data::<SqlSsTimestampOffset>(col_or_param_num, &mut *buffer) {
+                Return::Error if is_unsupported_c_type(stmt) => (),
+                ret => return ret.try_map(|ts| ts.map(|ts| offset_date_time(&ts)).transpose()),
+            }
+            stmt.get_data::<SqlTimestamp>(col_or_param_num, buffer).try_map(|ts| {
+                ts.map(|ts| time_date_time(&ts).map(|ts| ts.assume_utc()))
+                    .transpose()
+            })
+        }
+    }
+
+    /// `true` if the last call to `SQLGetData` failed, since the driver does not know the C type
+    /// or can not convert the column to it
+    fn is_unsupported_c_type(stmt: &Raii<ffi::Stmt>) -> bool {
+        stmt.get_diag_rec(1).is_some_and(|record| {
+            let state = &record.get_raw_state()[..ffi::SQL_SQLSTATE_SIZE];
+            // Invalid application buffer type, restricted data type attribute violation
+            state == b"HY003" || state == b"07006" || record.is_unsupported()
+        })
+    }
+
+    fn offset_date_time(ts: &SqlSsTimestampOffset) -> Result<OffsetDateTime> {
+        let local = time_date_time(&SqlTimestamp {
+            year: ts.year,
+            month: ts.month,
+            day: ts.day,
+            hour: ts.hour,
+            minute: ts.minute,
+            second: ts.second,
+            fraction: ts.fraction,
+        })?;
+        let offset = UtcOffset::from_hms(ts.timezone_hour as i8, ts.timezone_minute as i8, 0)
+            .map_err(|_| {
+                let message = format!("data source returned invalid offset: {:?}", ts);
+                DiagnosticRecord::with_message(b"22007", &message)
+            })?;
+        Ok(local.assume_offset(offset))
+    }
+}
+
 impl<'p> Raii<'p, ffi::Stmt> {
     fn get_data<'a, T>(
         &mut self,
@@ -33,6 +242,11 @@ impl<'p> Raii<'p, ffi::Stmt> {
     where
         T: OdbcType<'a>,
     {
+        // Fixed size C types are written as a whole, regardless of the buffer length passed to
+        // the driver, so the buffer must at least be able to hold one of them
+        if buffer.len() < mem::size_of::<T>() {
+            buffer.resize(mem::size_of::<T>(), 0);
+        }
         self.get_partial_data(col_or_param_num, buffer, 0)
     }
 
@@ -53,14 +267,14 @@ impl<'p> Raii<'p, ffi::Stmt> {
         }
         let mut indicator: ffi::SQLLEN = 0;
         // Get buffer length...
-        let result = unsafe { ffi::SQLGetData(
+        let result = poll_while_executing(|| unsafe { ffi::SQLGetData(
                 self.handle(),
                 col_or_param_num,
                 T::c_data_type(),
                 buffer.as_mut_ptr().offset(start_pos as isize) as ffi::SQLPOINTER,
                 (buffer.len() - start_pos) as ffi::SQLLEN,
                 &mut indicator as *mut ffi::SQLLEN,
-            ) };
+            ) });
         match result {
             ffi::SQL_SUCCESS => {
                 if indicator == ffi::SQL_NULL_DATA {
@@ -68,9 +282,16 @@ impl<'p> Raii<'p, ffi::Stmt> {
                 } else {
                     assert!(start_pos + indicator as usize <= buffer.len(), "no more data but indicatior outside of data buffer");
                     let slice = &buffer[..(start_pos + indicator as usize)];
-                    Return::Success(Some(T::convert(slice)))
+                    match T::try_convert(slice) {
+                        Ok(value) => Return::Success(Some(value)),
+                        Err(record) => Return::Failed(Box::new(record)),
+                    }
                 }
             }
+            // A warning does not imply the value has been transferred, e.g. if it is NULL
+            ffi::SQL_SUCCESS_WITH_INFO if indicator == ffi::SQL_NULL_DATA => {
+                Return::SuccessWithInfo(None)
+            }
             ffi::SQL_SUCCESS_WITH_INFO => {
                 let initial_len = buffer.len();
                 // // As a workaround for drivers that don't include tailing null(s) check if last bytes are null
@@ -80,19 +301,28 @@ impl<'p> Raii<'p, ffi::Stmt> {
                 // for buggy drivers here. They always can implement OdbcType trait and set any
                 // amount of null-terminators to do the workaround.
 
-                let null_offset = T::null_bytes_count();
+                // Without null termination the driver fills the whole buffer with data
+                let null_offset = if OUTPUT_NTS.load(Ordering::SeqCst) {
+                    T::null_bytes_count()
+                } else {
+                    0
+                };
                 if indicator == ffi::SQL_NO_TOTAL {
                     buffer.resize(initial_len * 2, 0);
                     return self.get_partial_data(col_or_param_num, buffer, initial_len - null_offset);
                 } else {
-                    // Check if string has been truncated.
-                    if indicator >= initial_len as ffi::SQLLEN {
-                        buffer.resize(indicator as usize + T::null_bytes_count(), 0);
+                    // Check if string has been truncated. The indicator holds the length of the
+                    // data which has been available to this call, starting at `start_pos`.
+                    if indicator >= (initial_len - start_pos) as ffi::SQLLEN {
+                        buffer.resize(start_pos + indicator as usize + null_offset, 0);
                         return self.get_partial_data(col_or_param_num, buffer, initial_len - null_offset);
                     } else {
                         let slice = &buffer[..(start_pos + indicator as usize)];
                         // No truncation. Warning may be due to some other issue.
-                        Return::SuccessWithInfo(Some(T::convert(slice)))
+                        match T::try_convert(slice) {
+                            Ok(value) => Return::SuccessWithInfo(Some(value)),
+                            Err(record) => Return::Failed(Box::new(record)),
+                        }
                     }
                 }
             }
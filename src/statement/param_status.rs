@@ -0,0 +1,146 @@
+//! Status of each parameter set after execution, using `SQL_ATTR_PARAM_STATUS_PTR` and
+//! `SQL_ATTR_PARAMS_PROCESSED_PTR`
+use super::{NoResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::cmp::min;
+use std::fmt;
+use {ffi, DiagnosticRecord, Result};
+
+/// Status of a set of parameters, after the statement has been executed. Returned by
+/// `Statement::param_status_array`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParamStatus {
+    /// The statement has been executed successfully for this set of parameters
+    /// (`SQL_PARAM_SUCCESS`)
+    Success,
+    /// Executed successfully, but the driver reported a warning (`SQL_PARAM_SUCCESS_WITH_INFO`)
+    SuccessWithInfo,
+    /// An error occurred processing this set of parameters (`SQL_PARAM_ERROR`)
+    Error,
+    /// The set of parameters has not been used, e.g. because an earlier one caused an error which
+    /// aborted the execution (`SQL_PARAM_UNUSED`)
+    Unused,
+    /// The driver processes the parameter sets as a whole, so there is no diagnostic for this one
+    /// (`SQL_PARAM_DIAG_UNAVAILABLE`)
+    DiagUnavailable,
+}
+
+impl fmt::Display for ParamStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ParamStatus::Success => "SQL_PARAM_SUCCESS",
+            ParamStatus::SuccessWithInfo => "SQL_PARAM_SUCCESS_WITH_INFO",
+            ParamStatus::Error => "SQL_PARAM_ERROR",
+            ParamStatus::Unused => "SQL_PARAM_UNUSED",
+            ParamStatus::DiagUnavailable => "SQL_PARAM_DIAG_UNAVAILABLE",
+        })
+    }
+}
+
+impl ParamStatus {
+    fn from_raw(status: ffi::SQLUSMALLINT) -> ParamStatus {
+        match status {
+            ffi::SQL_PARAM_SUCCESS => ParamStatus::Success,
+            ffi::SQL_PARAM_SUCCESS_WITH_INFO => ParamStatus::SuccessWithInfo,
+            ffi::SQL_PARAM_ERROR => ParamStatus::Error,
+            ffi::SQL_PARAM_UNUSED => ParamStatus::Unused,
+            ffi::SQL_PARAM_DIAG_UNAVAILABLE => ParamStatus::DiagUnavailable,
+            s => panic!("driver returned unknown parameter status: {}", s),
+        }
+    }
+}
+
+/// Buffers the driver writes the status of each parameter set to. Owned by the statement, since
+/// the driver keeps pointers to them, until they are reset.
+pub(super) struct ParamStatusBuffers {
+    status: Vec<ffi::SQLUSMALLINT>,
+    // Boxed, so the pointer passed to the driver stays valid if the statement is moved
+    processed: Box<ffi::SQLULEN>,
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, NoResult, AC> {
+    /// Lets the driver report the status of each set of parameters on execution, see
+    /// `param_status_array` and `params_processed`. Sets `SQL_ATTR_PARAM_STATUS_PTR` and
+    /// `SQL_ATTR_PARAMS_PROCESSED_PTR` to buffers owned by the statement.
+    ///
+    /// The status array holds one entry per parameter set, as many as `SQL_ATTR_PARAMSET_SIZE`
+    /// at the time of this call. This crate does not offer a way to set this attribute or to
+    /// bind arrays of parameters, so there is a single parameter set and the status array has
+    /// one entry. Enabling it again replaces the status array.
+    pub fn enable_param_status(&mut self) -> Result<()> {
+        let paramset_size = self
+            .raii
+            .get_attr(ffi::SQL_ATTR_PARAMSET_SIZE as ffi::SQLINTEGER)
+            .into_result(&self.diag())? as usize;
+        if self.param_status.is_none() {
+            let mut processed = Box::new(0);
+            let processed_ptr = &mut *processed as *mut ffi::SQLULEN as usize as ffi::SQLULEN;
+            self.raii
+                .set_attr(ffi::SQL_ATTR_PARAMS_PROCESSED_PTR, processed_ptr)
+                .into_result(&self.diag())?;
+            // Kept for the lifetime of the statement, enabling the status again reuses it
+            self.param_status = Some(ParamStatusBuffers {
+                status: Vec::new(),
+                processed,
+            });
+        }
+        let mut status = vec![ffi::SQL_PARAM_UNUSED; paramset_size.max(1)];
+        let status_ptr = status.as_mut_ptr() as usize as ffi::SQLULEN;
+        self.raii
+            .set_attr(ffi::SQL_ATTR_PARAM_STATUS_PTR, status_ptr)
+            .into_result(&self.diag())?;
+        // The previous array may only be freed once the driver no longer points to it
+        if let Some(ref mut buffers) = self.param_status {
+            buffers.status = status;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    /// Status of each set of parameters processed by the last execution, in the order of the
+    /// parameter sets. Requires `enable_param_status` to have been called before execution.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.enable_param_status()?;
+    /// let year = 2001;
+    /// let stmt = stmt.bind_parameter(1, &year)?;
+    /// if let NoData(stmt) = stmt.exec_direct("UPDATE MOVIES SET YEAR = ? WHERE YEAR = 1968")? {
+    ///     for (index, status) in stmt.param_status_array()?.iter().enumerate() {
+    ///         println!("Parameter set {}: {}", index + 1, status);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn param_status_array(&self) -> Result<Vec<ParamStatus>> {
+        let buffers = self.param_status_buffers()?;
+        let processed = min(*buffers.processed as usize, buffers.status.len());
+        Ok(buffers.status[..processed]
+            .iter()
+            .map(|&status| ParamStatus::from_raw(status))
+            .collect())
+    }
+
+    /// Number of parameter sets processed by the last execution, including those for which an
+    /// error occurred. Requires `enable_param_status` to have been called before execution.
+    pub fn params_processed(&self) -> Result<usize> {
+        Ok(*self.param_status_buffers()?.processed as usize)
+    }
+
+    fn param_status_buffers(&self) -> Result<&ParamStatusBuffers> {
+        self.param_status.as_ref().ok_or_else(|| {
+            DiagnosticRecord::with_message(
+                b"HY010",
+                "parameter status has not been enabled with `enable_param_status`",
+            )
+        })
+    }
+}
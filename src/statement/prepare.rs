@@ -1,6 +1,7 @@
-use {ffi, ColumnDescriptor, Raii, Return, Handle, Statement, Result, Prepared, Allocated,
-     NoResult, ResultSetState};
+use {ffi, ColumnDescriptor, ParamDescriptor, Raii, Return, Handle, Statement, Result, Prepared,
+     Allocated, NoResult, ResultSetState};
 use odbc_safe::AutocommitMode;
+use super::poll_while_executing;
 
 impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
     /// Prepares a statement for execution. Executing a prepared statement is faster than directly
@@ -39,8 +40,9 @@ impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
     /// # }
     /// ```
     pub fn prepare(mut self, sql_text: &str) -> Result<Statement<'a, 'b, Prepared, NoResult, AC>> {
-        self.raii.prepare(sql_text).into_result(&mut self)?;
-        Ok(Statement::with_raii(self.raii))
+        self.instrumentation.prepare(sql_text);
+        self.raii.prepare(sql_text).into_result(&self.diag())?;
+        Ok(self.into_state())
     }
 
 
@@ -64,8 +66,9 @@ impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
     /// # }
     /// ```
     pub fn prepare_bytes(mut self, bytes: &[u8]) -> Result<Statement<'a, 'b, Prepared, NoResult, AC>> {
-        self.raii.prepare_byte(bytes).into_result(&mut self)?;
-        Ok(Statement::with_raii(self.raii))
+        self.instrumentation.prepare_bytes(bytes);
+        self.raii.prepare_byte(bytes).into_result(&self.diag())?;
+        Ok(self.into_state())
     }
 }
 
@@ -75,25 +78,78 @@ impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Prepared, NoResult, AC> {
     /// Can be called successfully only when the statement is in the prepared, executed, or
     /// positioned state. If the statement does not return columns the result will be 0.
     pub fn num_result_cols(&self) -> Result<i16> {
-        self.raii.num_result_cols().into_result(self)
+        self.raii.num_result_cols().into_result(&self.diag())
     }
 
     /// Returns description struct for result set column with a given index. Note: indexing is starting from 1.
     pub fn describe_col(&self, idx: u16) -> Result<ColumnDescriptor> {
-        self.raii.describe_col(idx).into_result(self)
+        self.raii.describe_col(idx).into_result(&self.diag())
+    }
+
+    /// The number of parameter markers in the prepared SQL text. Wraps `SQLNumParams`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let stmt = stmt.prepare("SELECT TITLE FROM MOVIES WHERE YEAR BETWEEN ? AND ?")?;
+    /// assert_eq!(stmt.num_params()?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn num_params(&self) -> Result<u16> {
+        self.raii.num_params().into_result(&self.diag())
+    }
+
+    /// Returns the description of the parameter marker with the given index, starting at 1. Wraps
+    /// `SQLDescribeParam`.
+    ///
+    /// Not all drivers support this function. Use `DiagnosticRecord::is_unsupported` to tell this
+    /// case apart from other errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let stmt = stmt.prepare("SELECT TITLE FROM MOVIES WHERE YEAR = ?")?;
+    /// match stmt.describe_param(1) {
+    ///     Ok(desc) => println!("Parameter is of type {:?}", desc.data_type),
+    ///     Err(ref e) if e.is_unsupported() => println!("Driver can not describe parameters"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe_param(&self, idx: u16) -> Result<ParamDescriptor> {
+        self.raii.describe_param(idx).into_result(&self.diag())
     }
 
     /// Executes a prepared statement.
+    ///
+    /// The statement stays prepared after execution. A `NoData` statement can be executed again
+    /// right away, a `Data` statement after its result set has been closed using `close_cursor`.
+    /// There is no need to prepare the SQL text a second time. Bound parameters stay bound, so
+    /// the statement is executed again with the current values of the same buffers, unless
+    /// `reset_parameters` is called to bind new ones.
     pub fn execute(mut self) -> Result<ResultSetState<'a, 'b, Prepared, AC>> {
-        if self.raii.execute().into_result(&mut self)? {
-            let num_cols = self.raii.num_result_cols().into_result(&self)?;
+        let _span = self.instrumentation.execute();
+        if self.raii.execute().into_result(&self.diag())? {
+            let num_cols = self.result_cols()?;
             if num_cols > 0 {
-                Ok(ResultSetState::Data(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::Data(self.into_state()))
             } else {
-                Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+                Ok(ResultSetState::NoData(self.into_state()))
             }
         } else {
-            Ok(ResultSetState::NoData(Statement::with_raii(self.raii)))
+            Ok(ResultSetState::NoData(self.into_state()))
         }
     }
 }
@@ -101,13 +157,9 @@ impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Prepared, NoResult, AC> {
 impl<'p> Raii<'p, ffi::Stmt> {
     fn prepare(&mut self, sql_text: &str) -> Return<()> {
         let bytes = unsafe { crate::environment::DB_ENCODING }.encode(sql_text).0;
-        match unsafe {
-            ffi::SQLPrepare(
-                self.handle(),
-                bytes.as_ptr(),
-                bytes.len() as ffi::SQLINTEGER,
-            )
-        } {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLPrepare(self.handle(), bytes.as_ptr(), bytes.len() as ffi::SQLINTEGER)
+        }) {
             ffi::SQL_SUCCESS => Return::Success(()),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
             ffi::SQL_ERROR => Return::Error,
@@ -116,13 +168,9 @@ impl<'p> Raii<'p, ffi::Stmt> {
     }
 
     fn prepare_byte(&mut self, bytes: &[u8]) -> Return<()> {
-        match unsafe {
-            ffi::SQLPrepare(
-                self.handle(),
-                bytes.as_ptr(),
-                bytes.len() as ffi::SQLINTEGER,
-            )
-        } {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLPrepare(self.handle(), bytes.as_ptr(), bytes.len() as ffi::SQLINTEGER)
+        }) {
             ffi::SQL_SUCCESS => Return::Success(()),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
             ffi::SQL_ERROR => Return::Error,
@@ -130,12 +178,65 @@ impl<'p> Raii<'p, ffi::Stmt> {
         }
     }
 
+    fn num_params(&self) -> Return<u16> {
+        let mut num_params: ffi::SQLSMALLINT = 0;
+        match poll_while_executing(|| unsafe {
+            ffi::SQLNumParams(self.handle(), &mut num_params)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(num_params as u16),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(num_params as u16),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLNumParams returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn describe_param(&self, idx: u16) -> Return<ParamDescriptor> {
+        let mut data_type = ffi::SqlDataType::SQL_UNKNOWN_TYPE;
+        let mut param_size: ffi::SQLULEN = 0;
+        let mut decimal_digits: ffi::SQLSMALLINT = 0;
+        let mut nullable = ffi::SQL_NULLABLE_UNKNOWN as ffi::SQLSMALLINT;
+        let ret = match poll_while_executing(|| unsafe {
+            ffi::SQLDescribeParam(
+                self.handle(),
+                idx,
+                &mut data_type,
+                &mut param_size,
+                &mut decimal_digits,
+                &mut nullable,
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLDescribeParam returned unexpected result: {:?}", r),
+        };
+        ret.map(|()| ParamDescriptor {
+            data_type,
+            param_size: if param_size == 0 {
+                None
+            } else {
+                Some(param_size)
+            },
+            decimal_digits: if decimal_digits == 0 {
+                None
+            } else {
+                Some(decimal_digits as u16)
+            },
+            nullable: match nullable {
+                n if n == ffi::SQL_NULLABLE as ffi::SQLSMALLINT => Some(true),
+                n if n == ffi::SQL_NO_NULLS as ffi::SQLSMALLINT => Some(false),
+                _ => None,
+            },
+        })
+    }
+
     fn execute(&mut self) -> Return<bool> {
-        match unsafe { ffi::SQLExecute(self.handle()) } {
+        match poll_while_executing(|| unsafe { ffi::SQLExecute(self.handle()) }) {
             ffi::SQL_SUCCESS => Return::Success(true),
             ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
             ffi::SQL_ERROR => Return::Error,
             ffi::SQL_NO_DATA => Return::Success(false),
+            ffi::SQL_NEED_DATA => Return::NeedData,
             r => panic!("SQLExecute returned unexpected result: {:?}", r),
         }
     }
@@ -0,0 +1,196 @@
+//! Composing a statement together with its parameters, see `Query`
+use super::types::OdbcType;
+use super::{Allocated, NoResult, Prepared, ResultSetState, Statement};
+#[cfg(feature = "log")]
+use super::instrument;
+use odbc_safe::AutocommitMode;
+use std::ptr::{copy_nonoverlapping, null_mut};
+use std::slice::from_raw_parts;
+use {ffi, Handle, Raii, Result, Return};
+
+/// SQL text together with the values of its parameter markers, executed using `prepare`,
+/// `bind_parameter` and `execute`.
+///
+/// Parameters are bound to the markers in the order in which they are added, so there are no
+/// indices to keep track of. Values are copied by `bind`, which makes the statement independent
+/// of their lifetime.
+///
+/// # Example
+///
+/// ```
+/// # use odbc::*;
+/// # fn doc() -> Result<()> {
+/// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+/// let conn = env.connect("TestDataSource", "", "")?;
+/// let stmt = Statement::with_parent(&conn)?;
+/// let query = Query::new("SELECT TITLE FROM MOVIES WHERE YEAR > ? AND TITLE <> ?")
+///     .bind(1970)
+///     .bind("Jurassic Park");
+/// if let Data(mut stmt) = query.execute(stmt)? {
+///     while let Some(mut cursor) = stmt.fetch()? {
+///         println!("{:?}", cursor.get_data::<String>(1)?);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Query {
+    sql: String,
+    parameters: Vec<Parameter>,
+}
+
+/// Copy of a value bound by `Query::bind`
+#[derive(Debug)]
+struct Parameter {
+    c_data_type: ffi::SqlCDataType,
+    sql_data_type: ffi::SqlDataType,
+    column_size: ffi::SQLULEN,
+    decimal_digits: ffi::SQLSMALLINT,
+    // `None` for `NULL`. Held in `u64`s, so the driver can read e.g. an `f64` or a
+    // `SQL_TIMESTAMP_STRUCT` from an aligned address.
+    value: Option<Vec<u64>>,
+    // Length of the value in bytes
+    len: usize,
+}
+
+impl Parameter {
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        self.value.as_ref().map_or(null_mut(), |value| value.as_ptr() as ffi::SQLPOINTER)
+    }
+
+    #[cfg(feature = "log")]
+    fn bytes(&self) -> Option<&[u8]> {
+        self.value
+            .as_ref()
+            .map(|value| unsafe { from_raw_parts(value.as_ptr() as *const u8, self.len) })
+    }
+}
+
+/// Copies `bytes` into a buffer aligned for any of the C types of `OdbcType`
+fn aligned_copy(bytes: &[u8]) -> Vec<u64> {
+    let mut buffer = vec![0u64; bytes.len().div_ceil(8)];
+    unsafe { copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len()) };
+    buffer
+}
+
+impl Query {
+    /// A query executing `sql`, without any parameters yet
+    pub fn new(sql: &str) -> Self {
+        Query {
+            sql: sql.to_owned(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Adds the value for the next parameter marker. Bind an `Option<T>` to pass `NULL`.
+    pub fn bind<'v, T>(mut self, value: T) -> Self
+    where
+        T: OdbcType<'v>,
+    {
+        let encoded = value.encoded_value();
+        let bytes = if encoded.has_value() {
+            encoded.buf.as_deref()
+        } else if value.value_ptr().is_null() {
+            None
+        } else {
+            Some(unsafe {
+                from_raw_parts(value.value_ptr() as *const u8, value.column_size() as usize)
+            })
+        };
+        self.parameters.push(Parameter {
+            c_data_type: T::c_data_type(),
+            sql_data_type: T::sql_data_type(),
+            column_size: value.column_size(),
+            decimal_digits: value.decimal_digits(),
+            value: bytes.map(aligned_copy),
+            len: bytes.map_or(0, <[u8]>::len),
+        });
+        self
+    }
+
+    /// Prepares the SQL text on `stmt`, binds all parameters and executes it
+    pub fn execute<'a, 'b, AC: AutocommitMode>(
+        self,
+        stmt: Statement<'a, 'b, Allocated, NoResult, AC>,
+    ) -> Result<ResultSetState<'a, 'b, Prepared, AC>> {
+        let mut stmt = stmt.prepare(&self.sql)?;
+        for (index, parameter) in self.parameters.into_iter().enumerate() {
+            stmt.bind_owned_parameter(index as u16 + 1, parameter)?;
+        }
+        stmt.execute()
+    }
+}
+
+impl<'a, 'b, S, R, AC: AutocommitMode> Statement<'a, 'b, S, R, AC> {
+    fn bind_owned_parameter(&mut self, parameter_index: u16, parameter: Parameter) -> Result<()> {
+        let ind = if parameter.value.is_some() {
+            parameter.len as ffi::SQLLEN
+        } else {
+            ffi::SQL_NULL_DATA
+        };
+        let ind_ptr = self.param_ind_buffers.alloc(parameter_index as usize, ind);
+        #[cfg(feature = "log")]
+        {
+            if log_enabled!(::log::Level::Trace) {
+                let bytes = parameter.bytes();
+                instrument::bind_parameter(parameter_index, parameter.c_data_type, bytes);
+            }
+        }
+        self.raii
+            .bind_owned_parameter(parameter_index, &parameter, ind_ptr)
+            .into_result(&self.diag())?;
+        // The statement keeps the buffer alive, until the parameters are reset
+        if let Some(value) = parameter.value {
+            self.owned_values.push(value);
+        }
+        Ok(())
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn bind_owned_parameter(
+        &mut self,
+        parameter_index: u16,
+        parameter: &Parameter,
+        str_len_or_ind_ptr: *mut ffi::SQLLEN,
+    ) -> Return<()> {
+        match unsafe {
+            ffi::SQLBindParameter(
+                self.handle(),
+                parameter_index,
+                ffi::SQL_PARAM_INPUT,
+                parameter.c_data_type,
+                parameter.sql_data_type,
+                parameter.column_size,
+                parameter.decimal_digits,
+                parameter.value_ptr(),
+                0,
+                str_len_or_ind_ptr,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("Unexpected return from SQLBindParameter: {:?}", r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::mem::align_of;
+
+    #[test]
+    fn parameters_are_aligned_copies() {
+        let query = Query::new("SELECT ?, ?, ?").bind(1.5f64).bind("abc").bind(None::<i32>);
+        let double = &query.parameters[0];
+        assert_eq!(double.value_ptr() as usize % align_of::<f64>(), 0);
+        assert_eq!(unsafe { *(double.value_ptr() as *const f64) }, 1.5);
+        let text = &query.parameters[1];
+        let text = unsafe { from_raw_parts(text.value_ptr() as *const u8, text.len) };
+        assert_eq!(text, b"abc");
+        assert!(query.parameters[2].value_ptr().is_null());
+    }
+}
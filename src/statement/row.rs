@@ -0,0 +1,338 @@
+//! Owned copies of fetched rows, see `Cursor::into_row`
+use super::{Cursor, Decimal, SqlDate, SqlGuid, SqlTime, SqlTimestamp};
+use ffi::SqlDataType::*;
+use odbc_safe::AutocommitMode;
+use std::ops::Index;
+use Result;
+
+/// Value of a single field, as retrieved by `Cursor::into_row`
+///
+/// The variant is chosen by the SQL data type of the column. Integers of any size are widened to
+/// `i64`, floating point numbers to `f64`. Columns of types without a dedicated variant are
+/// retrieved as `String`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OdbcValue {
+    /// `SQL_BIT`
+    Bool(bool),
+    /// `SQL_TINYINT`, `SQL_SMALLINT`, `SQL_INTEGER` and `SQL_BIGINT`
+    Int(i64),
+    /// `SQL_REAL`, `SQL_FLOAT` and `SQL_DOUBLE`
+    Float(f64),
+    /// `SQL_DECIMAL` and `SQL_NUMERIC`
+    Decimal(Decimal),
+    /// Character data and all types not covered by any other variant
+    String(String),
+    /// `SQL_BINARY`, `SQL_VARBINARY` and `SQL_LONGVARBINARY`
+    Bytes(Vec<u8>),
+    /// `SQL_TYPE_DATE`
+    Date(SqlDate),
+    /// `SQL_TYPE_TIME`
+    Time(SqlTime),
+    /// `SQL_TYPE_TIMESTAMP`
+    Timestamp(SqlTimestamp),
+    /// `SQL_GUID`
+    Guid(SqlGuid),
+}
+
+/// A row of a result set, which owns the values of all its fields. Created by
+/// `Cursor::into_row`.
+///
+/// Fields can be accessed by index, starting at `0` (unlike column numbers, which start at
+/// `1`), or by column name. `NULL` values are `None`.
+///
+/// With the `serde` feature, rows implement `Serialize` and `Deserialize` as maps from column
+/// name to value. Decimals, dates, times, timestamps and GUIDs are serialized as strings, so they
+/// come back as `OdbcValue::String` when deserialized.
+///
+/// # Panics
+///
+/// Indexing panics if the index is out of bounds or there is no column with the given name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    columns: Vec<String>,
+    values: Vec<Option<OdbcValue>>,
+}
+
+impl<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode> Cursor<'s, 'a, 'b, S, AC> {
+    /// Retrieves the values of all columns, so they can be kept after fetching the next row.
+    ///
+    /// Column types and names are queried using `SQLDescribeCol`. See `OdbcValue` for how types
+    /// are mapped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// let mut rows = Vec::new();
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES")? {
+    ///     while let Some(cursor) = stmt.fetch()? {
+    ///         rows.push(cursor.into_row()?);
+    ///     }
+    /// }
+    /// for row in &rows {
+    ///     println!("{:?} ({:?})", row["TITLE"], row["YEAR"]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_row(mut self) -> Result<Row> {
+        let num_cols = self.column_count()?;
+        let mut columns = Vec::with_capacity(num_cols as usize);
+        let mut values = Vec::with_capacity(num_cols as usize);
+        for col in 1..=num_cols {
+            let descriptor = self.stmt.describe_col(col)?;
+            let value = match descriptor.data_type {
+                SQL_EXT_BIT => self.get_data(col)?.map(OdbcValue::Bool),
+                SQL_EXT_TINYINT | SQL_SMALLINT | SQL_INTEGER | SQL_EXT_BIGINT => {
+                    self.get_data(col)?.map(OdbcValue::Int)
+                }
+                SQL_REAL | SQL_FLOAT | SQL_DOUBLE => self.get_data(col)?.map(OdbcValue::Float),
+                SQL_DECIMAL | SQL_NUMERIC => self.get_data(col)?.map(OdbcValue::Decimal),
+                SQL_EXT_BINARY | SQL_EXT_VARBINARY | SQL_EXT_LONGVARBINARY => {
+                    self.get_data(col)?.map(OdbcValue::Bytes)
+                }
+                SQL_DATE => self.get_data(col)?.map(OdbcValue::Date),
+                SQL_TIME => self.get_data(col)?.map(OdbcValue::Time),
+                SQL_TIMESTAMP => self.get_data(col)?.map(OdbcValue::Timestamp),
+                SQL_EXT_GUID => self.get_data(col)?.map(OdbcValue::Guid),
+                _ => self.get_data(col)?.map(OdbcValue::String),
+            };
+            columns.push(descriptor.name);
+            values.push(value);
+        }
+        Ok(Row { columns, values })
+    }
+}
+
+impl Row {
+    /// Number of fields
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// `true` if the row has no fields
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Names of the columns, in the order of the fields
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Values of all fields
+    pub fn values(&self) -> &[Option<OdbcValue>] {
+        &self.values
+    }
+
+    /// Index of the field with the given column name. Names are compared case-sensitively first,
+    /// and ignoring ASCII case if there is no exact match, since many data sources change the
+    /// case of unquoted identifiers.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        find_column(&self.columns, name)
+    }
+
+    /// Value of the field with the given column name, or `None` if there is no such column.
+    pub fn get(&self, name: &str) -> Option<&Option<OdbcValue>> {
+        self.column_index(name).map(|i| &self.values[i])
+    }
+
+    /// Column names and values of all fields
+    pub fn into_parts(self) -> (Vec<String>, Vec<Option<OdbcValue>>) {
+        (self.columns, self.values)
+    }
+}
+
+/// Position of the column `name` in `columns`. See `Row::column_index`.
+pub(super) fn find_column(columns: &[String], name: &str) -> Option<usize> {
+    columns
+        .iter()
+        .position(|c| c == name)
+        .or_else(|| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
+}
+
+impl Index<usize> for Row {
+    type Output = Option<OdbcValue>;
+
+    fn index(&self, index: usize) -> &Option<OdbcValue> {
+        &self.values[index]
+    }
+}
+
+impl Index<&str> for Row {
+    type Output = Option<OdbcValue>;
+
+    fn index(&self, name: &str) -> &Option<OdbcValue> {
+        self.get(name)
+            .unwrap_or_else(|| panic!("row has no column named {:?}", name))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::{OdbcValue, Row};
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+    use serde::ser::{Serialize, SerializeMap, Serializer};
+    use std::fmt;
+
+    impl Serialize for OdbcValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match *self {
+                OdbcValue::Bool(value) => serializer.serialize_bool(value),
+                OdbcValue::Int(value) => serializer.serialize_i64(value),
+                OdbcValue::Float(value) => serializer.serialize_f64(value),
+                OdbcValue::Decimal(ref value) => serializer.collect_str(value),
+                OdbcValue::String(ref value) => serializer.serialize_str(value),
+                OdbcValue::Bytes(ref value) => serializer.serialize_bytes(value),
+                OdbcValue::Date(ref d) => serializer.collect_str(&format_args!(
+                    "{:04}-{:02}-{:02}",
+                    d.year, d.month, d.day
+                )),
+                OdbcValue::Time(ref t) => serializer.collect_str(&format_args!(
+                    "{:02}:{:02}:{:02}",
+                    t.hour, t.minute, t.second
+                )),
+                OdbcValue::Timestamp(ref ts) => serializer.collect_str(&format_args!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:09}",
+                    ts.year, ts.month, ts.day, ts.hour, ts.minute, ts.second, ts.fraction
+                )),
+                OdbcValue::Guid(ref g) => serializer.collect_str(&format_args!(
+                    "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+                    g.d1, g.d2, g.d3, g.d4[0], g.d4[1], g.d4[2], g.d4[3], g.d4[4], g.d4[5],
+                    g.d4[6], g.d4[7]
+                )),
+            }
+        }
+    }
+
+    impl Serialize for Row {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (name, value) in self.columns.iter().zip(&self.values) {
+                map.serialize_entry(name, value)?;
+            }
+            map.end()
+        }
+    }
+
+    struct OdbcValueVisitor;
+
+    impl<'de> Visitor<'de> for OdbcValueVisitor {
+        type Value = OdbcValue;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a boolean, number, string or byte array")
+        }
+
+        fn visit_bool<E: de::Error>(self, value: bool) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::Bool(value))
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::Int(value))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<OdbcValue, E> {
+            if value <= i64::MAX as u64 {
+                Ok(OdbcValue::Int(value as i64))
+            } else {
+                Ok(OdbcValue::Float(value as f64))
+            }
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::Float(value))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::String(value.to_owned()))
+        }
+
+        fn visit_string<E: de::Error>(self, value: String) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::String(value))
+        }
+
+        fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::Bytes(value.to_owned()))
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<OdbcValue, E> {
+            Ok(OdbcValue::Bytes(value))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for OdbcValue {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<OdbcValue, D::Error> {
+            deserializer.deserialize_any(OdbcValueVisitor)
+        }
+    }
+
+    struct RowVisitor;
+
+    impl<'de> Visitor<'de> for RowVisitor {
+        type Value = Row;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map from column names to values")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Row, A::Error> {
+            let capacity = access.size_hint().unwrap_or(0);
+            let mut columns = Vec::with_capacity(capacity);
+            let mut values = Vec::with_capacity(capacity);
+            while let Some((name, value)) = access.next_entry()? {
+                columns.push(name);
+                values.push(value);
+            }
+            Ok(Row { columns, values })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Row {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Row, D::Error> {
+            deserializer.deserialize_map(RowVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn movie() -> Row {
+        Row {
+            columns: vec!["TITLE".to_owned(), "YEAR".to_owned()],
+            values: vec![Some(OdbcValue::String("Jurassic Park".to_owned())), None],
+        }
+    }
+
+    #[test]
+    fn index_by_position_and_name() {
+        let row = movie();
+        assert_eq!(row.len(), 2);
+        assert_eq!(row[0], Some(OdbcValue::String("Jurassic Park".to_owned())));
+        assert_eq!(row["TITLE"], row[0]);
+        assert_eq!(row["year"], None);
+        assert_eq!(row.get("DIRECTOR"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        extern crate serde_json;
+        let json = serde_json::to_string(&movie()).unwrap();
+        assert_eq!(json, r#"{"TITLE":"Jurassic Park","YEAR":null}"#);
+        let row: Row = serde_json::from_str(&json).unwrap();
+        assert_eq!(row, movie());
+    }
+
+    #[test]
+    #[should_panic(expected = "row has no column named \"DIRECTOR\"")]
+    fn index_by_unknown_name() {
+        let _ = &movie()["DIRECTOR"];
+    }
+}
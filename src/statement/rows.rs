@@ -0,0 +1,77 @@
+use super::{Cursor, HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::iter::FusedIterator;
+use Result;
+
+/// Iterator over the rows of a result set. Created by `Statement::rows`.
+pub struct Rows<'s, 'a: 's, 'b: 's, S: 's, AC: AutocommitMode + 's, F> {
+    stmt: &'s mut Statement<'a, 'b, S, HasResult, AC>,
+    f: F,
+    done: bool,
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Iterates over the remaining rows of the result set, converting each of them with `f`.
+    ///
+    /// A `Cursor` borrows the statement it has been fetched from, so it can not be yielded by an
+    /// iterator directly. Instead `f` extracts the values of interest from each cursor. Iteration
+    /// stops after the first error, be it returned by `fetch` or by `f`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES")? {
+    ///     for title in stmt.rows(|cursor| cursor.get_data::<String>(1)) {
+    ///         println!("{:?}", title?);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rows<'s, T, F>(&'s mut self, f: F) -> Rows<'s, 'a, 'b, S, AC, F>
+    where
+        F: FnMut(&mut Cursor<'_, 'a, 'b, S, AC>) -> Result<T>,
+    {
+        Rows {
+            stmt: self,
+            f,
+            done: false,
+        }
+    }
+}
+
+impl<'s, 'a, 'b, S, AC: AutocommitMode, T, F> Iterator for Rows<'s, 'a, 'b, S, AC, F>
+where
+    F: FnMut(&mut Cursor<'_, 'a, 'b, S, AC>) -> Result<T>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+        let item = match self.stmt.fetch() {
+            Ok(Some(mut cursor)) => (self.f)(&mut cursor),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => Err(e),
+        };
+        if item.is_err() {
+            self.done = true;
+        }
+        Some(item)
+    }
+}
+
+impl<'s, 'a, 'b, S, AC: AutocommitMode, T, F> FusedIterator for Rows<'s, 'a, 'b, S, AC, F>
+where
+    F: FnMut(&mut Cursor<'_, 'a, 'b, S, AC>) -> Result<T>,
+{
+}
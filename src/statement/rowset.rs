@@ -0,0 +1,584 @@
+//! Fetching blocks of rows into column-wise bound buffers, using `SQL_ATTR_ROW_ARRAY_SIZE`
+use super::bind::FixedSizedType;
+use super::{HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::any::Any;
+use std::borrow::Cow;
+use std::cmp::min;
+use std::fmt;
+use std::mem;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Buffer holding the values of one column for all rows of a rowset
+enum ColumnBuffer {
+    /// `Vec<T>` of a `FixedSizedType`
+    Fixed(Box<dyn Any + Send>),
+    /// Character data, `width` bytes per row, including the terminating zero
+    Text { values: Vec<u8>, width: usize },
+    /// Variable length bookmarks of column `0`, up to `width` bytes per row
+    Bookmark { values: Vec<u8>, width: usize },
+}
+
+struct RowsetColumn {
+    column_number: u16,
+    buffer: ColumnBuffer,
+    indicators: Vec<ffi::SQLLEN>,
+}
+
+/// A statement with result set, which fetches up to `row_array_size` rows at once into buffers
+/// owned by the statement. Created by `Statement::into_rowset`.
+///
+/// For large result sets this is considerably faster than fetching rows one by one, since the
+/// number of round trips to the driver (and often to the data source) is divided by the size of
+/// the rowset. Bind the columns of interest using `bind_col` or `bind_text_col`, then call
+/// `fetch_rowset` repeatedly.
+///
+/// The bound buffers can also be written, using `set`, `set_text` and `set_bookmark`, to pass rows
+/// to `bulk_operations`.
+pub struct RowsetStatement<'a, 'b, S, AC: AutocommitMode> {
+    // Declared first, so the handle is freed before the buffers it points to
+    pub(super) stmt: Statement<'a, 'b, S, HasResult, AC>,
+    columns: Vec<RowsetColumn>,
+    // Boxed, so the pointer passed to the driver stays valid if the statement is moved
+    rows_fetched: Box<ffi::SQLULEN>,
+    row_status: Vec<ffi::SQLUSMALLINT>,
+}
+
+/// Status of a row within a `Rowset`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RowStatus {
+    /// The row has been fetched successfully (`SQL_ROW_SUCCESS`)
+    Success,
+    /// The row has been fetched, but the driver reported a warning (`SQL_ROW_SUCCESS_WITH_INFO`)
+    SuccessWithInfo,
+    /// An error occurred while fetching the row (`SQL_ROW_ERROR`)
+    Error,
+    /// The row has been updated since it was last fetched (`SQL_ROW_UPDATED`)
+    Updated,
+    /// The row has been deleted since it was last fetched (`SQL_ROW_DELETED`)
+    Deleted,
+    /// The row has been inserted by `SQLBulkOperations` (`SQL_ROW_ADDED`)
+    Added,
+    /// There is no row at this position of the rowset (`SQL_ROW_NOROW`)
+    NoRow,
+    /// A status not defined by the ODBC standard, e.g. specific to the driver
+    Other(u16),
+}
+
+impl fmt::Display for RowStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            RowStatus::Other(status) => return write!(f, "row status {}", status),
+            RowStatus::Success => "SQL_ROW_SUCCESS",
+            RowStatus::SuccessWithInfo => "SQL_ROW_SUCCESS_WITH_INFO",
+            RowStatus::Error => "SQL_ROW_ERROR",
+            RowStatus::Updated => "SQL_ROW_UPDATED",
+            RowStatus::Deleted => "SQL_ROW_DELETED",
+            RowStatus::Added => "SQL_ROW_ADDED",
+            RowStatus::NoRow => "SQL_ROW_NOROW",
+        })
+    }
+}
+
+/// Rows fetched by a single call to `RowsetStatement::fetch_rowset`
+pub struct Rowset<'r> {
+    columns: &'r [RowsetColumn],
+    row_status: &'r [ffi::SQLUSMALLINT],
+}
+
+/// A single row within a `Rowset`
+#[derive(Clone, Copy)]
+pub struct RowsetRow<'r> {
+    rowset: &'r Rowset<'r>,
+    index: usize,
+}
+
+/// Iterator over the rows of a `Rowset`. Created by `Rowset::iter`.
+pub struct RowsetIter<'r> {
+    rowset: &'r Rowset<'r>,
+    index: usize,
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Turns the statement into a `RowsetStatement`, which fetches up to `row_array_size` rows
+    /// with each call to `fetch_rowset`. Sets `SQL_ATTR_ROW_ARRAY_SIZE`, as well as
+    /// `SQL_ATTR_ROW_STATUS_PTR` and `SQL_ATTR_ROWS_FETCHED_PTR`
+    ///
+    /// This is the only way to change the row array size. It is not exposed by `StatementAttr`,
+    /// since the single value buffers bound by `Statement::into_bound` would overflow if the
+    /// driver fetched more than one row at once.
+    ///
+    /// # Panics
+    ///
+    /// If `row_array_size` is `0`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES")? {
+    ///     let mut stmt = stmt
+    ///         .into_rowset(1000)?
+    ///         .bind_text_col(1, 255)?
+    ///         .bind_col::<i32>(2)?;
+    ///     while let Some(rowset) = stmt.fetch_rowset()? {
+    ///         for row in rowset.iter() {
+    ///             println!("{:?} ({:?})", row.get_text(1), row.get::<i32>(2));
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_rowset(mut self, row_array_size: usize) -> Result<RowsetStatement<'a, 'b, S, AC>> {
+        assert!(row_array_size > 0, "row_array_size must not be 0");
+        let attribute = ffi::SQL_ATTR_ROW_ARRAY_SIZE as ffi::SQLINTEGER;
+        self.raii.set_attr(attribute, row_array_size as ffi::SQLULEN).into_result(&self.diag())?;
+        let mut rowset = RowsetStatement {
+            stmt: self,
+            columns: Vec::new(),
+            rows_fetched: Box::new(0),
+            row_status: vec![ffi::SQL_ROW_NOROW; row_array_size],
+        };
+        let status_ptr = rowset.row_status.as_mut_ptr() as usize as ffi::SQLULEN;
+        let fetched_ptr = &mut *rowset.rows_fetched as *mut ffi::SQLULEN as usize as ffi::SQLULEN;
+        rowset
+            .stmt
+            .raii
+            .set_attr(ffi::SQL_ATTR_ROW_STATUS_PTR, status_ptr)
+            .into_result(&rowset.stmt.diag())?;
+        rowset
+            .stmt
+            .raii
+            .set_attr(ffi::SQL_ATTR_ROWS_FETCHED_PTR as ffi::SQLINTEGER, fetched_ptr)
+            .into_result(&rowset.stmt.diag())?;
+        Ok(rowset)
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> RowsetStatement<'a, 'b, S, AC> {
+    /// Maximum number of rows fetched at once
+    pub fn row_array_size(&self) -> usize {
+        self.row_status.len()
+    }
+
+    /// Binds a column of the result set to a buffer of `T`, holding one value per row of the
+    /// rowset. Wraps `SQLBindCol`. Column numbers start at `1`. Binding a column again replaces
+    /// the previous binding.
+    pub fn bind_col<T>(mut self, column_number: u16) -> Result<Self>
+    where
+        T: FixedSizedType + Send,
+    {
+        let n = self.row_array_size();
+        // All bit patterns written by the driver are valid, see `FixedSizedType`
+        let mut values: Vec<T> = (0..n).map(|_| unsafe { mem::zeroed() }).collect();
+        let mut indicators = vec![0; n];
+        self.stmt
+            .raii
+            .bind_col::<T>(
+                column_number,
+                values.as_mut_ptr() as *mut u8,
+                indicators.as_mut_ptr(),
+            )
+            .into_result(&self.stmt.diag())?;
+        self.push_column(column_number, ColumnBuffer::Fixed(Box::new(values)), indicators);
+        Ok(self)
+    }
+
+    /// Binds a column of the result set to a character buffer, holding up to `max_len` bytes
+    /// per row. Longer values are truncated. Wraps `SQLBindCol` with `SQL_C_CHAR`.
+    pub fn bind_text_col(mut self, column_number: u16, max_len: usize) -> Result<Self> {
+        let n = self.row_array_size();
+        // Room for the terminating zero
+        let width = max_len + 1;
+        let mut values = vec![0u8; n * width];
+        let mut indicators = vec![0; n];
+        self.stmt
+            .raii
+            .bind_bytes_col(
+                column_number,
+                ffi::SQL_C_CHAR,
+                &mut values,
+                width,
+                indicators.as_mut_ptr(),
+            )
+            .into_result(&self.stmt.diag())?;
+        self.push_column(column_number, ColumnBuffer::Text { values, width }, indicators);
+        Ok(self)
+    }
+
+    /// Binds the bookmark column `0` to a buffer holding up to `max_len` bytes per row, which
+    /// identify the rows for `bulk_operations`. Wraps `SQLBindCol` with `SQL_C_VARBOOKMARK`.
+    ///
+    /// Bookmarks have to be enabled with `StatementAttr::UseBookmarks` before the statement is
+    /// executed. The length of a bookmark is up to the driver, `SQLColAttribute` with
+    /// `SQL_DESC_OCTET_LENGTH` of column `0` reports it.
+    pub fn bind_bookmarks(mut self, max_len: usize) -> Result<Self> {
+        let n = self.row_array_size();
+        let mut values = vec![0u8; n * max_len];
+        let mut indicators = vec![0; n];
+        self.stmt
+            .raii
+            .bind_bytes_col(0, ffi::SQL_C_BINARY, &mut values, max_len, indicators.as_mut_ptr())
+            .into_result(&self.stmt.diag())?;
+        let buffer = ColumnBuffer::Bookmark {
+            values,
+            width: max_len,
+        };
+        self.push_column(0, buffer, indicators);
+        Ok(self)
+    }
+
+    /// Writes the value of a column bound using `bind_col` for the row with the given index,
+    /// starting at `0`. `None` writes `NULL`. The buffers are passed to the driver by
+    /// `bulk_operations`, e.g. to insert rows, and overwritten by the next `fetch_rowset`.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is not smaller than `row_array_size()`, or the column is not bound to a buffer of
+    /// `T`.
+    pub fn set<T: FixedSizedType>(&mut self, row: usize, column_number: u16, value: Option<T>) {
+        let column = self.column_mut(row, column_number);
+        let values = match column.buffer {
+            ColumnBuffer::Fixed(ref mut values) => values.downcast_mut::<Vec<T>>(),
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            panic!("column {} is bound to a buffer of a different type", column_number)
+        });
+        column.indicators[row] = match value {
+            Some(value) => {
+                values[row] = value;
+                mem::size_of::<T>() as ffi::SQLLEN
+            }
+            None => ffi::SQL_NULL_DATA,
+        };
+    }
+
+    /// Writes the value of a column bound using `bind_text_col` for the row with the given index,
+    /// encoded in the database encoding. `None` writes `NULL`. See `set`.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is not smaller than `row_array_size()`, the column is not bound as text, or the
+    /// encoded value is longer than the `max_len` it has been bound with.
+    pub fn set_text(&mut self, row: usize, column_number: u16, value: Option<&str>) {
+        let column = self.column_mut(row, column_number);
+        let (values, width) = match column.buffer {
+            ColumnBuffer::Text {
+                ref mut values,
+                width,
+            } => (values, width),
+            _ => panic!("column {} is not bound as text", column_number),
+        };
+        column.indicators[row] = match value {
+            Some(value) => {
+                let bytes = unsafe { ::environment::DB_ENCODING }.encode(value).0;
+                assert!(
+                    bytes.len() < width,
+                    "value of {} bytes does not fit into column {}",
+                    bytes.len(),
+                    column_number
+                );
+                let start = row * width;
+                values[start..start + bytes.len()].copy_from_slice(&bytes);
+                values[start + bytes.len()] = 0;
+                bytes.len() as ffi::SQLLEN
+            }
+            None => ffi::SQL_NULL_DATA,
+        };
+    }
+
+    /// Writes the bookmark identifying the row with the given index to the buffer bound by
+    /// `bind_bookmarks`, e.g. one fetched previously. See `set`.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is not smaller than `row_array_size()`, no bookmarks are bound, or `bookmark` is
+    /// longer than the `max_len` they have been bound with.
+    pub fn set_bookmark(&mut self, row: usize, bookmark: &[u8]) {
+        let column = self.column_mut(row, 0);
+        let (values, width) = match column.buffer {
+            ColumnBuffer::Bookmark {
+                ref mut values,
+                width,
+            } => (values, width),
+            _ => panic!("column 0 is not bound to bookmarks"),
+        };
+        assert!(
+            bookmark.len() <= width,
+            "bookmark of {} bytes does not fit into the bound buffer",
+            bookmark.len()
+        );
+        let start = row * width;
+        values[start..start + bookmark.len()].copy_from_slice(bookmark);
+        column.indicators[row] = bookmark.len() as ffi::SQLLEN;
+    }
+
+    /// Fetches the next rowset into the bound buffers. Returns `None` if there are no more rows.
+    pub fn fetch_rowset(&mut self) -> Result<Option<Rowset<'_>>> {
+        let has_rows = self.stmt.raii.fetch().into_result(&self.stmt.diag())?;
+        let len = if has_rows { min(*self.rows_fetched as usize, self.row_status.len()) } else { 0 };
+        self.stmt.instrumentation.fetched_rows(len as u64);
+        if has_rows {
+            Ok(Some(Rowset {
+                columns: &self.columns,
+                row_status: &self.row_status[..len],
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Releases all column buffers and resets the row array size to `1`, returning the
+    /// statement. Wraps `SQLFreeStmt` with `SQL_UNBIND`.
+    pub fn unbind(mut self) -> Result<Statement<'a, 'b, S, HasResult, AC>> {
+        self.stmt.raii.unbind().into_result(&self.stmt.diag())?;
+        self.stmt
+            .raii
+            .set_attr(ffi::SQL_ATTR_ROW_ARRAY_SIZE as ffi::SQLINTEGER, 1)
+            .into_result(&self.stmt.diag())?;
+        let pointers = [
+            ffi::SQL_ATTR_ROW_STATUS_PTR,
+            ffi::SQL_ATTR_ROWS_FETCHED_PTR as ffi::SQLINTEGER,
+        ];
+        for &attribute in &pointers {
+            self.stmt.raii.set_attr(attribute, 0).into_result(&self.stmt.diag())?;
+        }
+        Ok(self.stmt)
+    }
+
+    fn push_column(
+        &mut self,
+        column_number: u16,
+        buffer: ColumnBuffer,
+        indicators: Vec<ffi::SQLLEN>,
+    ) {
+        self.columns.retain(|c| c.column_number != column_number);
+        self.columns.push(RowsetColumn {
+            column_number,
+            buffer,
+            indicators,
+        });
+    }
+
+    fn column_mut(&mut self, row: usize, column_number: u16) -> &mut RowsetColumn {
+        assert!(row < self.row_array_size(), "row {} is out of bounds", row);
+        self.columns
+            .iter_mut()
+            .find(|c| c.column_number == column_number)
+            .unwrap_or_else(|| panic!("column {} is not bound", column_number))
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> fmt::Debug for RowsetStatement<'a, 'b, S, AC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns: Vec<_> = self.columns.iter().map(|c| c.column_number).collect();
+        f.debug_struct("RowsetStatement")
+            .field("stmt", &self.stmt)
+            .field("row_array_size", &self.row_array_size())
+            .field("columns", &columns)
+            .finish()
+    }
+}
+
+impl<'r> Rowset<'r> {
+    /// Number of rows in the rowset, including rows with errors
+    pub fn len(&self) -> usize {
+        self.row_status.len()
+    }
+
+    /// `true` if the rowset contains no rows
+    pub fn is_empty(&self) -> bool {
+        self.row_status.is_empty()
+    }
+
+    /// The row with the given index, starting at `0`
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not smaller than `len()`
+    pub fn row(&self, index: usize) -> RowsetRow<'_> {
+        assert!(index < self.len(), "row {} is out of bounds", index);
+        RowsetRow {
+            rowset: self,
+            index,
+        }
+    }
+
+    /// Iterates over the rows of the rowset
+    pub fn iter(&self) -> RowsetIter<'_> {
+        RowsetIter {
+            rowset: self,
+            index: 0,
+        }
+    }
+
+    fn column(&self, column_number: u16) -> &'r RowsetColumn {
+        self.columns
+            .iter()
+            .find(|c| c.column_number == column_number)
+            .unwrap_or_else(|| panic!("column {} is not bound", column_number))
+    }
+}
+
+impl<'r> fmt::Debug for Rowset<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Rowset").field("len", &self.len()).finish()
+    }
+}
+
+impl<'r> RowsetRow<'r> {
+    /// Status of the row, reported by the driver
+    pub fn status(&self) -> RowStatus {
+        match self.rowset.row_status[self.index] {
+            ffi::SQL_ROW_SUCCESS => RowStatus::Success,
+            ffi::SQL_ROW_SUCCESS_WITH_INFO => RowStatus::SuccessWithInfo,
+            ffi::SQL_ROW_ERROR => RowStatus::Error,
+            ffi::SQL_ROW_UPDATED => RowStatus::Updated,
+            ffi::SQL_ROW_DELETED => RowStatus::Deleted,
+            ffi::SQL_ROW_ADDED => RowStatus::Added,
+            ffi::SQL_ROW_NOROW => RowStatus::NoRow,
+            s => RowStatus::Other(s),
+        }
+    }
+
+    /// Value of a column bound using `bind_col`, or `None` if it is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// If the column is not bound, or bound to a buffer of a different type.
+    pub fn get<T: FixedSizedType>(&self, column_number: u16) -> Option<&'r T> {
+        let column = self.rowset.column(column_number);
+        let values = match column.buffer {
+            ColumnBuffer::Fixed(ref values) => values.downcast_ref::<Vec<T>>(),
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            panic!("column {} is bound to a buffer of a different type", column_number)
+        });
+        if column.indicators[self.index] == ffi::SQL_NULL_DATA {
+            None
+        } else {
+            Some(&values[self.index])
+        }
+    }
+
+    /// Value of a column bound using `bind_text_col`, or `None` if it is `NULL`. Decoded from the
+    /// database encoding. Values longer than the buffer are truncated.
+    ///
+    /// # Panics
+    ///
+    /// If the column is not bound, or bound using `bind_col`.
+    pub fn get_text(&self, column_number: u16) -> Option<Cow<'r, str>> {
+        let column = self.rowset.column(column_number);
+        let (values, width) = match column.buffer {
+            ColumnBuffer::Text { ref values, width } => (values, width),
+            _ => panic!("column {} is not bound as text", column_number),
+        };
+        let indicator = column.indicators[self.index];
+        if indicator == ffi::SQL_NULL_DATA {
+            return None;
+        }
+        // The indicator holds the length of the complete value, or `SQL_NO_TOTAL` if unknown
+        let len = if indicator < 0 || indicator as usize >= width {
+            width - 1
+        } else {
+            indicator as usize
+        };
+        let start = self.index * width;
+        Some(unsafe { ::environment::DB_ENCODING }.decode(&values[start..start + len]).0)
+    }
+
+    /// Bookmark of the row, bound using `RowsetStatement::bind_bookmarks`. `None` if the driver
+    /// did not provide one.
+    ///
+    /// # Panics
+    ///
+    /// If no bookmarks are bound
+    pub fn bookmark(&self) -> Option<&'r [u8]> {
+        let column = self.rowset.column(0);
+        let (values, width) = match column.buffer {
+            ColumnBuffer::Bookmark { ref values, width } => (values, width),
+            _ => panic!("column 0 is not bound to bookmarks"),
+        };
+        let indicator = column.indicators[self.index];
+        if indicator == ffi::SQL_NULL_DATA {
+            return None;
+        }
+        // Truncated, if the bookmark is longer than the buffer
+        let len = if indicator < 0 { width } else { min(indicator as usize, width) };
+        let start = self.index * width;
+        Some(&values[start..start + len])
+    }
+}
+
+impl<'r> fmt::Debug for RowsetRow<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RowsetRow")
+            .field("index", &self.index)
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl<'r> Iterator for RowsetIter<'r> {
+    type Item = RowsetRow<'r>;
+
+    fn next(&mut self) -> Option<RowsetRow<'r>> {
+        if self.index < self.rowset.len() {
+            let row = RowsetRow {
+                rowset: self.rowset,
+                index: self.index,
+            };
+            self.index += 1;
+            Some(row)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.rowset.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'r> ExactSizeIterator for RowsetIter<'r> {}
+
+impl<'r> fmt::Debug for RowsetIter<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RowsetIter").field("index", &self.index).finish()
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn bind_bytes_col(
+        &mut self,
+        column_number: u16,
+        c_data_type: ffi::SqlCDataType,
+        values: &mut [u8],
+        width: usize,
+        indicators: *mut ffi::SQLLEN,
+    ) -> Return<()> {
+        match unsafe {
+            ffi::SQLBindCol(
+                self.handle(),
+                column_number,
+                c_data_type,
+                values.as_mut_ptr() as ffi::SQLPOINTER,
+                width as ffi::SQLLEN,
+                indicators,
+            )
+        } {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLBindCol returned unexpected result: {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Positioned operations on the rows of the current rowset, using `SQLSetPos`
+use super::bind::BoundStatement;
+use super::{poll_while_executing, HasResult, Statement};
+use odbc_safe::AutocommitMode;
+use std::fmt;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Operation performed by `Statement::set_pos`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SetPosOperation {
+    /// Positions the cursor on the row, e.g. to retrieve its data using `get_data`
+    /// (`SQL_POSITION`)
+    Position,
+    /// Refreshes the bound buffers and the status of the row with the data of the data source
+    /// (`SQL_REFRESH`)
+    Refresh,
+    /// Updates the row in the data source with the values of the bound buffers (`SQL_UPDATE`)
+    Update,
+    /// Deletes the row from the data source (`SQL_DELETE`)
+    Delete,
+    /// Inserts the values of the bound buffers as new row. Deprecated in favour of
+    /// `SQLBulkOperations`, but still supported by some drivers (`SQL_ADD`)
+    Add,
+}
+
+impl fmt::Display for SetPosOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            SetPosOperation::Position => "SQL_POSITION",
+            SetPosOperation::Refresh => "SQL_REFRESH",
+            SetPosOperation::Update => "SQL_UPDATE",
+            SetPosOperation::Delete => "SQL_DELETE",
+            SetPosOperation::Add => "SQL_ADD",
+        })
+    }
+}
+
+/// Lock placed on the row by `Statement::set_pos`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LockType {
+    /// Leaves the row locked or unlocked as it was before (`SQL_LOCK_NO_CHANGE`)
+    NoChange,
+    /// Locks the row exclusively (`SQL_LOCK_EXCLUSIVE`)
+    Exclusive,
+    /// Unlocks the row (`SQL_LOCK_UNLOCK`)
+    Unlock,
+}
+
+impl fmt::Display for LockType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            LockType::NoChange => "SQL_LOCK_NO_CHANGE",
+            LockType::Exclusive => "SQL_LOCK_EXCLUSIVE",
+            LockType::Unlock => "SQL_LOCK_UNLOCK",
+        })
+    }
+}
+
+impl SetPosOperation {
+    fn operation(&self) -> ffi::SQLUSMALLINT {
+        match *self {
+            SetPosOperation::Position => ffi::SQL_POSITION,
+            SetPosOperation::Refresh => ffi::SQL_REFRESH,
+            SetPosOperation::Update => ffi::SQL_UPDATE,
+            SetPosOperation::Delete => ffi::SQL_DELETE,
+            SetPosOperation::Add => ffi::SQL_SETPOS_ADD,
+        }
+    }
+}
+
+impl LockType {
+    fn lock_type(&self) -> ffi::SQLUSMALLINT {
+        match *self {
+            LockType::NoChange => ffi::SQL_LOCK_NO_CHANGE,
+            LockType::Exclusive => ffi::SQL_LOCK_EXCLUSIVE,
+            LockType::Unlock => ffi::SQL_LOCK_UNLOCK,
+        }
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> Statement<'a, 'b, S, HasResult, AC> {
+    /// Performs `operation` on a row of the rowset fetched last. Wraps `SQLSetPos`.
+    ///
+    /// Rows are numbered starting at `1`. Row `0` applies the operation to every row of the
+    /// rowset. `Update` and `Add` write the values of the bound column buffers, so these must
+    /// have been filled with the new values beforehand, using `BoundStatement::set`.
+    ///
+    /// Positioned operations require a cursor which supports them. Before executing the
+    /// statement, set `StatementAttr::CursorType` to something other than
+    /// `CursorType::ForwardOnly`, and `StatementAttr::Concurrency` to something other than
+    /// `Concurrency::ReadOnly` for `Update`, `Delete` and `Add`. Which combinations are supported
+    /// depends on the driver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let mut stmt = Statement::with_parent(&conn)?;
+    /// stmt.set_attr(StatementAttr::CursorType(CursorType::KeysetDriven))?;
+    /// stmt.set_attr(StatementAttr::Concurrency(Concurrency::Lock))?;
+    /// let sql = "SELECT TITLE FROM MOVIES WHERE YEAR < 1970";
+    /// if let Data(mut stmt) = stmt.exec_direct(sql)? {
+    ///     if stmt.fetch()?.is_some() {
+    ///         stmt.set_pos(1, SetPosOperation::Delete, LockType::NoChange)?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_pos(
+        &mut self,
+        row: u16,
+        operation: SetPosOperation,
+        lock_type: LockType,
+    ) -> Result<()> {
+        self.raii
+            .set_pos(row, operation.operation(), lock_type.lock_type())
+            .into_result(&self.diag())
+    }
+}
+
+impl<'a, 'b, S, AC: AutocommitMode> BoundStatement<'a, 'b, S, AC> {
+    /// Performs `operation` on a row of the rowset fetched last, using the bound column buffers.
+    /// See `Statement::set_pos`.
+    pub fn set_pos(
+        &mut self,
+        row: u16,
+        operation: SetPosOperation,
+        lock_type: LockType,
+    ) -> Result<()> {
+        self.stmt.set_pos(row, operation, lock_type)
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn set_pos(
+        &mut self,
+        row: u16,
+        operation: ffi::SQLUSMALLINT,
+        lock_type: ffi::SQLUSMALLINT,
+    ) -> Return<()> {
+        match poll_while_executing(|| unsafe {
+            ffi::SQLSetPos(self.handle(), ffi::SQLULEN::from(row), operation, lock_type)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            ffi::SQL_NEED_DATA => Return::NeedData,
+            r => panic!("SQLSetPos returned unexpected result: {:?}", r),
+        }
+    }
+}
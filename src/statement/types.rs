@@ -1,8 +1,12 @@
 use ffi;
+use {DiagnosticRecord, Result};
 use std::slice::from_raw_parts;
 use std::mem::{size_of, transmute};
+use std::ptr::read_unaligned;
 use std::ffi::CString;
 use std::borrow::Cow::{Borrowed, Owned};
+#[cfg(feature = "time")]
+use std::convert::TryFrom;
 
 pub struct EncodedValue {
     pub buf: Option<Vec<u8>>,
@@ -38,6 +42,11 @@ pub unsafe trait OdbcType<'a>: Sized {
     fn sql_data_type() -> ffi::SqlDataType;
     fn c_data_type() -> ffi::SqlCDataType;
     fn convert(_: &'a [u8]) -> Self;
+    /// Like `convert`, but reports data the type can not represent as an error, rather than
+    /// panicking. Used by `Cursor::get_data`. Calls `convert` by default.
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        Ok(Self::convert(buffer))
+    }
     fn column_size(&self) -> ffi::SQLULEN;
     fn null_bytes_count() -> usize {
         0
@@ -99,6 +108,49 @@ unsafe impl<'a> OdbcType<'a> for Vec<u8> {
     }
 }
 
+/// Fixed length binary data, e.g. a hash stored in a `BINARY(32)` column. Bound as `SQL_BINARY`.
+///
+/// `get_data` reports a value of any other length than `N` bytes as an error with SQLSTATE
+/// `07006`, `convert` panics. Retrieve such values as `Vec<u8>` instead.
+unsafe impl<'a, const N: usize> OdbcType<'a> for [u8; N] {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_EXT_BINARY
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_BINARY
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        Self::try_convert(buffer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        if buffer.len() != N {
+            let message = format!(
+                "data source returned {} bytes for a binary value of {} bytes",
+                buffer.len(),
+                N
+            );
+            return Err(invalid_value(b"07006", message));
+        }
+        let mut value = [0; N];
+        value.copy_from_slice(buffer);
+        Ok(value)
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        N as ffi::SQLULEN
+    }
+
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        self.as_ptr() as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        EncodedValue::new(None)
+    }
+}
+
 unsafe impl<'a> OdbcType<'a> for &'a[u16] {
     fn sql_data_type() -> ffi::SqlDataType {
         ffi::SQL_EXT_WVARCHAR
@@ -278,11 +330,46 @@ unsafe impl<'a> OdbcType<'a> for ::std::borrow::Cow<'a, str> {
     }
 }
 
+/// Character data retrieved as `SQL_C_CHAR` and decoded as UTF-8, replacing invalid sequences
+/// with `U+FFFD`, regardless of the database encoding. Used by `Cursor::get_data_as_str_lossy`.
+pub(crate) struct LossyString(pub String);
+
+unsafe impl<'a> OdbcType<'a> for LossyString {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_VARCHAR
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_CHAR
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        LossyString(String::from_utf8_lossy(buffer).into_owned())
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        self.0.len() as ffi::SQLULEN
+    }
+
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        self.0.as_ptr() as *const Self as ffi::SQLPOINTER
+    }
+
+    fn null_bytes_count() -> usize {
+        1
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        EncodedValue::new(None)
+    }
+}
+
 fn convert_primitive<T>(buf: &[u8]) -> T
 where
     T: Copy,
 {
-    unsafe { from_raw_parts(buf.as_ptr() as *const T, 1)[0] }
+    assert!(buf.len() >= size_of::<T>());
+    // The buffer holds bytes, so it is not necessarily aligned for `T`
+    unsafe { read_unaligned(buf.as_ptr() as *const T) }
 }
 
 unsafe impl<'a> OdbcType<'a> for u8 {
@@ -534,8 +621,9 @@ unsafe impl<'a> OdbcType<'a> for bool {
     }
 
     fn convert(buffer: &'a [u8]) -> Self {
-        assert!(buffer.len() == 1);
-        buffer[0] > 0
+        // `SQL_C_BIT` is a single byte, but some drivers report booleans as (wider) integers
+        assert!(!buffer.is_empty());
+        buffer.iter().any(|&byte| byte != 0)
     }
 
     fn column_size(&self) -> ffi::SQLULEN {
@@ -671,6 +759,313 @@ unsafe impl<'a> OdbcType<'a> for SqlSsTime2 {
     }
 }
 
+pub type SqlSsTimestampOffset = ffi::SQL_SS_TIMESTAMPOFFSET_STRUCT;
+
+/// SQL Server `datetimeoffset`. Drivers not supporting `SQL_C_SS_TIMESTAMPOFFSET` report an error.
+unsafe impl<'a> OdbcType<'a> for SqlSsTimestampOffset {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_SS_TIMESTAMPOFFSET
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_SS_TIMESTAMPOFFSET
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        convert_primitive(buffer)
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<Self>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        EncodedValue::new(None)
+    }
+}
+
+pub type SqlGuid = ffi::SQLGUID;
+
+unsafe impl<'a> OdbcType<'a> for SqlGuid {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_EXT_GUID
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_GUID
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        assert_eq!(buffer.len(), size_of::<Self>());
+        convert_primitive(buffer)
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<Self>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        EncodedValue::new(None)
+    }
+}
+
+/// `uuid::Uuid` is exchanged as `SQL_C_GUID`, e.g. with `uniqueidentifier` columns of Microsoft
+/// SQL Server. Its bytes are in RFC 4122 order, unlike `SQLGUID`, whose first three fields are in
+/// native byte order. So the value is bound through a converted copy.
+#[cfg(feature = "uuid")]
+unsafe impl<'a> OdbcType<'a> for ::uuid::Uuid {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_EXT_GUID
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_GUID
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        let guid = SqlGuid::convert(buffer);
+        ::uuid::Uuid::from_fields(guid.d1, guid.d2, guid.d3, &guid.d4)
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<SqlGuid>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        // Never passed to the driver, since `encoded_value` is used instead
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        let (d1, d2, d3, d4) = self.as_fields();
+        let guid = SqlGuid { d1, d2, d3, d4: *d4 };
+        let bytes = unsafe {
+            from_raw_parts(&guid as *const SqlGuid as *const u8, size_of::<SqlGuid>())
+        };
+        EncodedValue::new(Some(bytes.to_vec()))
+    }
+}
+
+/// Exchanged as its exact decimal representation (`SQL_C_CHAR`), which the driver converts from
+/// and to `DECIMAL` or `NUMERIC`. Binding `SQL_C_NUMERIC` instead would not carry the scale, since
+/// `SQLBindParameter` leaves it at `0` in the application parameter descriptor.
+///
+/// Fractional digits beyond the 28 supported by `rust_decimal` are rounded. `get_data` reports a
+/// value whose integral part does not fit into 96 bits, e.g. of a `DECIMAL(38)` column, as an
+/// error with SQLSTATE `22003`, `convert` panics. Retrieve such values as `odbc::Decimal` instead.
+#[cfg(feature = "rust_decimal")]
+unsafe impl<'a> OdbcType<'a> for ::rust_decimal::Decimal {
+    fn sql_data_type() -> ffi::SqlDataType {
+        ffi::SQL_DECIMAL
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        ffi::SQL_C_CHAR
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        Self::try_convert(buffer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        let text = String::convert(buffer);
+        let text = text.trim();
+        text.parse::<::rust_decimal::Decimal>().map_err(|e| {
+            invalid_value(b"22003", format!("invalid decimal {:?} returned by driver: {}", text, e))
+        })
+    }
+
+    /// Precision of the value, i.e. its number of significant digits, which covers the scale
+    fn column_size(&self) -> ffi::SQLULEN {
+        let mut precision = 1;
+        let mut mantissa = self.mantissa().unsigned_abs() / 10;
+        while mantissa > 0 {
+            precision += 1;
+            mantissa /= 10;
+        }
+        precision.max(self.scale()) as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        // Never passed to the driver, since `encoded_value` is used instead
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn null_bytes_count() -> usize {
+        1
+    }
+
+    fn decimal_digits(&self) -> ffi::SQLSMALLINT {
+        self.scale() as ffi::SQLSMALLINT
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        EncodedValue::new(Some(self.to_string().into_bytes()))
+    }
+}
+
+/// Binds the types of the `time` crate through a copy of the matching ODBC struct
+#[cfg(feature = "time")]
+fn encode_struct<T>(value: &T) -> EncodedValue {
+    let bytes = unsafe { from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+    EncodedValue::new(Some(bytes.to_vec()))
+}
+
+/// Error for a value returned by the data source, which the requested type can not represent
+fn invalid_value(state: &[u8; 5], message: String) -> DiagnosticRecord {
+    DiagnosticRecord::with_message(state, &message)
+}
+
+#[cfg(feature = "time")]
+fn time_date(date: &SqlDate) -> Result<::time::Date> {
+    ::time::Month::try_from(date.month as u8)
+        .and_then(|month| ::time::Date::from_calendar_date(date.year as i32, month, date.day as u8))
+        .map_err(|_| {
+            invalid_value(b"22007", format!("data source returned invalid date: {:?}", date))
+        })
+}
+
+#[cfg(feature = "time")]
+fn sql_date(date: ::time::Date) -> SqlDate {
+    SqlDate {
+        year: date.year() as i16,
+        month: date.month() as u16,
+        day: date.day() as u16,
+    }
+}
+
+/// Reads and binds `SQL_DATE_STRUCT`
+///
+/// `get_data` reports a date which does not exist, like `0000-00-00`, as an error with SQLSTATE
+/// `22007`, `convert` panics. Retrieve such values as `OdbcDate` instead.
+#[cfg(feature = "time")]
+unsafe impl<'a> OdbcType<'a> for ::time::Date {
+    fn sql_data_type() -> ffi::SqlDataType {
+        SqlDate::sql_data_type()
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        SqlDate::c_data_type()
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        Self::try_convert(buffer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        time_date(&SqlDate::convert(buffer))
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<SqlDate>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        // Never passed to the driver, since `encoded_value` is used instead
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        encode_struct(&sql_date(*self))
+    }
+}
+
+/// Reads and binds `SQL_TIME_STRUCT`, which has no fractional seconds. They are zero when read
+/// and dropped when bound. Invalid times are handled like invalid dates of `time::Date`.
+#[cfg(feature = "time")]
+unsafe impl<'a> OdbcType<'a> for ::time::Time {
+    fn sql_data_type() -> ffi::SqlDataType {
+        SqlTime::sql_data_type()
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        SqlTime::c_data_type()
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        Self::try_convert(buffer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        let time = SqlTime::convert(buffer);
+        ::time::Time::from_hms(time.hour as u8, time.minute as u8, time.second as u8)
+            .map_err(|_| {
+                invalid_value(b"22007", format!("data source returned invalid time: {:?}", time))
+            })
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<SqlTime>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        // Never passed to the driver, since `encoded_value` is used instead
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        encode_struct(&SqlTime {
+            hour: self.hour() as u16,
+            minute: self.minute() as u16,
+            second: self.second() as u16,
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+pub(crate) fn time_date_time(ts: &SqlTimestamp) -> Result<::time::PrimitiveDateTime> {
+    let date = SqlDate {
+        year: ts.year,
+        month: ts.month,
+        day: ts.day,
+    };
+    let time =
+        ::time::Time::from_hms_nano(ts.hour as u8, ts.minute as u8, ts.second as u8, ts.fraction)
+            .map_err(|_| {
+                invalid_value(b"22007", format!("data source returned invalid timestamp: {:?}", ts))
+            })?;
+    Ok(::time::PrimitiveDateTime::new(time_date(&date)?, time))
+}
+
+/// Reads and binds `SQL_TIMESTAMP_STRUCT`
+///
+/// Invalid timestamps are handled like invalid dates of `time::Date`. Retrieve them as
+/// `OdbcTimestamp` instead.
+#[cfg(feature = "time")]
+unsafe impl<'a> OdbcType<'a> for ::time::PrimitiveDateTime {
+    fn sql_data_type() -> ffi::SqlDataType {
+        SqlTimestamp::sql_data_type()
+    }
+    fn c_data_type() -> ffi::SqlCDataType {
+        SqlTimestamp::c_data_type()
+    }
+
+    fn convert(buffer: &'a [u8]) -> Self {
+        Self::try_convert(buffer).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        time_date_time(&SqlTimestamp::convert(buffer))
+    }
+
+    fn column_size(&self) -> ffi::SQLULEN {
+        size_of::<SqlTimestamp>() as ffi::SQLULEN
+    }
+    fn value_ptr(&self) -> ffi::SQLPOINTER {
+        // Never passed to the driver, since `encoded_value` is used instead
+        self as *const Self as ffi::SQLPOINTER
+    }
+
+    fn encoded_value(&self) -> EncodedValue {
+        let date = sql_date(self.date());
+        encode_struct(&SqlTimestamp {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            hour: self.hour() as u16,
+            minute: self.minute() as u16,
+            second: self.second() as u16,
+            fraction: self.nanosecond(),
+        })
+    }
+}
+
 unsafe impl<'a, T> OdbcType<'a> for Option<T> where T: OdbcType<'a> {
     fn sql_data_type() -> ffi::SqlDataType {
         T::sql_data_type()
@@ -684,6 +1079,10 @@ unsafe impl<'a, T> OdbcType<'a> for Option<T> where T: OdbcType<'a> {
         Some(T::convert(buffer))
     }
 
+    fn try_convert(buffer: &'a [u8]) -> Result<Self> {
+        T::try_convert(buffer).map(Some)
+    }
+
     fn column_size(&self) -> ffi::SQLULEN {
         if let Some(t) = self {
             t.column_size()
@@ -702,9 +1101,13 @@ unsafe impl<'a, T> OdbcType<'a> for Option<T> where T: OdbcType<'a> {
     fn null_bytes_count() -> usize {
         T::null_bytes_count()
     }
-    
+
     fn encoded_value(&self) -> EncodedValue {
-        EncodedValue::new(None)
+        if let Some(t) = self {
+            t.encoded_value()
+        } else {
+            EncodedValue::new(None)
+        }
     }
 }
 
@@ -715,6 +1118,80 @@ mod test {
     use std::collections::HashSet;
     use std::borrow::Cow;
 
+    #[test]
+    fn convert_guid() {
+        let guid = SqlGuid {
+            d1: 0x1234_5678,
+            d2: 0x9abc,
+            d3: 0xdef0,
+            d4: [1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let bytes = unsafe {
+            from_raw_parts(&guid as *const SqlGuid as *const u8, size_of::<SqlGuid>())
+        };
+        assert_eq!(SqlGuid::convert(bytes), guid);
+    }
+
+    #[test]
+    fn convert_fixed_size_binary() {
+        assert_eq!(<[u8; 4]>::convert(&[1, 2, 3, 4]), [1, 2, 3, 4]);
+        assert_eq!([1u8, 2, 3].column_size(), 3);
+    }
+
+    #[test]
+    fn fixed_size_binary_of_other_length_is_an_error() {
+        let error = <[u8; 4]>::try_convert(&[1, 2]).unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"07006");
+        assert!(<[u8; 4]>::try_convert(&[1, 2, 3, 4, 5]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "returned 5 bytes")]
+    fn convert_too_long_binary() {
+        <[u8; 4]>::convert(&[1, 2, 3, 4, 5]);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_round_trip() {
+        let uuid = ::uuid::Uuid::parse_str("12345678-9abc-def0-0102-030405060708").unwrap();
+        let enc = uuid.encoded_value();
+        let bytes = enc.buf.as_ref().unwrap();
+        assert_eq!(SqlGuid::convert(bytes).d1, 0x1234_5678);
+        assert_eq!(::uuid::Uuid::convert(bytes), uuid);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn invalid_time_date_is_an_error() {
+        let date = SqlDate {
+            year: 0,
+            month: 0,
+            day: 0,
+        };
+        let bytes = unsafe {
+            from_raw_parts(&date as *const SqlDate as *const u8, size_of::<SqlDate>())
+        };
+        let error = ::time::Date::try_convert(bytes).unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"22007");
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn rust_decimal_text() {
+        let value = ::rust_decimal::Decimal::new(-12345, 2);
+        assert_eq!(value.encoded_value().buf.unwrap(), b"-123.45".to_vec());
+        assert_eq!(value.column_size(), 5);
+        assert_eq!(::rust_decimal::Decimal::new(5, 2).column_size(), 2);
+        assert_eq!(::rust_decimal::Decimal::new(0, 0).column_size(), 1);
+        assert_eq!(value.decimal_digits(), 2);
+        assert_eq!(::rust_decimal::Decimal::convert(b"-123.45"), value);
+        assert_eq!(::rust_decimal::Decimal::convert(b"1.5E2"), ::rust_decimal::Decimal::new(150, 0));
+        let too_large = b"12345678901234567890123456789012345678";
+        let error = ::rust_decimal::Decimal::try_convert(too_large).unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"22003");
+    }
+
     #[test]
     fn encoded_value_test() {
         let mut checker = HashSet::new();
@@ -778,4 +1255,15 @@ mod test {
         encoded_values.clear();
 
     }
+
+    #[test]
+    fn convert_bool() {
+        assert!(!bool::convert(&[0]));
+        assert!(bool::convert(&[1]));
+        // Integer representations, as one, two or four bytes
+        assert!(bool::convert(&[0xff]));
+        assert!(!bool::convert(&[0, 0]));
+        assert!(bool::convert(&[0, 1]));
+        assert!(bool::convert(&[1, 0, 0, 0]));
+    }
 }
\ No newline at end of file
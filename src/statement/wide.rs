@@ -0,0 +1,222 @@
+//! Variants of functions taking SQL text or identifiers, which call the Unicode (`W`) functions
+//! of ODBC, passing the text as UTF-16
+use super::{
+    poll_while_executing, Allocated, Executed, HasResult, NoResult, Prepared, ResultSetState,
+    Statement,
+};
+use odbc_safe::AutocommitMode;
+use std::ptr::null;
+use {ffi, Handle, Raii, Result, Return};
+
+/// Optional argument of a catalog function, encoded as UTF-16
+struct WideArg(Option<Vec<ffi::SQLWCHAR>>);
+
+impl WideArg {
+    fn new(text: Option<&str>) -> WideArg {
+        WideArg(text.map(encode_wide))
+    }
+
+    fn ptr(&self) -> *const ffi::SQLWCHAR {
+        match self.0 {
+            Some(ref chars) => chars.as_ptr(),
+            None => null(),
+        }
+    }
+
+    // Length in characters
+    fn len(&self) -> ffi::SQLSMALLINT {
+        match self.0 {
+            Some(ref chars) => chars.len() as ffi::SQLSMALLINT,
+            None => 0,
+        }
+    }
+}
+
+fn encode_wide(text: &str) -> Vec<ffi::SQLWCHAR> {
+    text.encode_utf16().collect()
+}
+
+impl<'a, 'b, AC: AutocommitMode> Statement<'a, 'b, Allocated, NoResult, AC> {
+    /// Like `exec_direct`, but passes the statement text as UTF-16 to `SQLExecDirectW`.
+    ///
+    /// The text does not need to be representable in the database encoding, so identifiers and
+    /// literals with characters outside of it (e.g. Japanese column names with an ANSI code page)
+    /// are passed on unchanged. Prefer this on Windows, if the driver supports Unicode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use odbc::*;
+    /// # fn doc() -> Result<()> {
+    /// let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    /// let conn = env.connect("TestDataSource", "", "")?;
+    /// let stmt = Statement::with_parent(&conn)?;
+    /// if let Data(mut stmt) = stmt.exec_direct_w("SELECT 'こんにちは'")? {
+    ///     let mut cursor = stmt.fetch()?.unwrap();
+    ///     println!("{:?}", cursor.get_data::<String>(1)?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn exec_direct_w(
+        mut self,
+        statement_text: &str,
+    ) -> Result<ResultSetState<'a, 'b, Executed, AC>> {
+        let _span = self.instrumentation.exec_direct(statement_text);
+        if self.raii.exec_direct_w(statement_text).into_result(&self.diag())? {
+            let num_cols = self.result_cols()?;
+            if num_cols > 0 {
+                Ok(ResultSetState::Data(self.into_state()))
+            } else {
+                Ok(ResultSetState::NoData(self.into_state()))
+            }
+        } else {
+            Ok(ResultSetState::NoData(self.into_state()))
+        }
+    }
+
+    /// Like `prepare`, but passes the statement text as UTF-16 to `SQLPrepareW`. See
+    /// `exec_direct_w`.
+    pub fn prepare_w(mut self, sql_text: &str) -> Result<Statement<'a, 'b, Prepared, NoResult, AC>> {
+        self.instrumentation.prepare(sql_text);
+        self.raii.prepare_w(sql_text).into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Like `tables_filtered`, but passes the arguments as UTF-16 to `SQLTablesW`. See
+    /// `exec_direct_w`.
+    pub fn tables_w(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        table_type: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .tables_w(catalog_name, schema_name, table_name, table_type)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+
+    /// Like `columns`, but passes the arguments as UTF-16 to `SQLColumnsW`. See `exec_direct_w`.
+    pub fn columns_w(
+        mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Result<Statement<'a, 'b, Executed, HasResult, AC>> {
+        self.raii
+            .columns_w(catalog_name, schema_name, table_name, column_name)
+            .into_result(&self.diag())?;
+        Ok(self.into_state())
+    }
+}
+
+impl<'p> Raii<'p, ffi::Stmt> {
+    fn exec_direct_w(&mut self, statement_text: &str) -> Return<bool> {
+        let chars = encode_wide(statement_text);
+        if chars.len() > ffi::SQLINTEGER::MAX as usize {
+            panic!("Statement text too long");
+        }
+        match poll_while_executing(|| unsafe {
+            ffi::SQLExecDirectW(self.handle(), chars.as_ptr(), chars.len() as ffi::SQLINTEGER)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(true),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(true),
+            ffi::SQL_ERROR => Return::Error,
+            ffi::SQL_NEED_DATA => Return::NeedData,
+            ffi::SQL_NO_DATA => Return::Success(false),
+            r => panic!("SQLExecDirectW returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn prepare_w(&mut self, sql_text: &str) -> Return<()> {
+        let chars = encode_wide(sql_text);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLPrepareW(self.handle(), chars.as_ptr(), chars.len() as ffi::SQLINTEGER)
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLPrepareW returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn tables_w(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        table_type: Option<&str>,
+    ) -> Return<()> {
+        let catalog = WideArg::new(catalog_name);
+        let schema = WideArg::new(schema_name);
+        let table = WideArg::new(table_name);
+        let table_type = WideArg::new(table_type);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLTablesW(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                table_type.ptr(),
+                table_type.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLTablesW returned unexpected result: {:?}", r),
+        }
+    }
+
+    fn columns_w(
+        &mut self,
+        catalog_name: Option<&str>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+        column_name: Option<&str>,
+    ) -> Return<()> {
+        let catalog = WideArg::new(catalog_name);
+        let schema = WideArg::new(schema_name);
+        let table = WideArg::new(table_name);
+        let column = WideArg::new(column_name);
+        match poll_while_executing(|| unsafe {
+            ffi::SQLColumnsW(
+                self.handle(),
+                catalog.ptr(),
+                catalog.len(),
+                schema.ptr(),
+                schema.len(),
+                table.ptr(),
+                table.len(),
+                column.ptr(),
+                column.len(),
+            )
+        }) {
+            ffi::SQL_SUCCESS => Return::Success(()),
+            ffi::SQL_SUCCESS_WITH_INFO => Return::SuccessWithInfo(()),
+            ffi::SQL_ERROR => Return::Error,
+            r => panic!("SQLColumnsW returned unexpected result: {:?}", r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wide_arg_length_counts_utf16_units() {
+        let arg = WideArg::new(Some("表𝄞"));
+        assert_eq!(arg.len(), 3);
+        assert!(!arg.ptr().is_null());
+        let arg = WideArg::new(None);
+        assert_eq!(arg.len(), 0);
+        assert!(arg.ptr().is_null());
+    }
+}
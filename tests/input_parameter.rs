@@ -123,3 +123,15 @@ fn _f64() {
     let param: f64 = 3.14;
     test_type!(C, &param)
 }
+
+#[test]
+fn _option_str() {
+    let param = Some("Hello, World!");
+    test_type!(A, &param)
+}
+
+#[test]
+fn _option_string() {
+    let param = Some(String::from("Hello, World!"));
+    test_type!(A, &param)
+}
@@ -27,96 +27,1499 @@ fn list_tables() {
     ds.disconnect().unwrap();
 }
 
+#[test]
+fn list_tables_filtered() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let mut stmt = stmt.tables_filtered(None, None, Some("MOVIES"), None).unwrap();
+    {
+        let mut cur = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cur.get_data::<String>(3).unwrap(), Some("MOVIES".to_owned()));
+    }
+    assert!(stmt.fetch().unwrap().is_none());
+}
+
+#[test]
+fn count_tables() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    assert!(conn.tables_count(None).unwrap() >= 1);
+}
+
+#[test]
+fn list_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let mut stmt = stmt.columns(None, None, Some("MOVIES"), None).unwrap();
+    let names: Vec<String> = stmt
+        .rows(|cursor| Ok(cursor.get_data(4)?.unwrap()))
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(names, ["TITLE", "YEAR"]);
+}
+
+#[test]
+fn list_primary_keys() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.primary_keys(None, None, "MOVIES").unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 6);
+}
+
+#[test]
+fn list_statistics() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.statistics(None, None, "MOVIES", false, false).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 13);
+}
+
+#[test]
+fn list_type_info() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let mut stmt = stmt.type_info(Some(ffi::SQL_INTEGER)).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 19);
+    let mut cursor = stmt.fetch().unwrap().expect("SQL_INTEGER is supported");
+    assert_eq!(cursor.get_data::<i16>(2).unwrap(), Some(ffi::SQL_INTEGER as i16));
+}
+
+#[test]
+fn list_procedures() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.procedures(None, None, None).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 8);
+}
+
+#[test]
+fn list_procedure_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.procedure_columns(None, None, None, None).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 19);
+}
+
+#[test]
+fn list_special_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt
+        .special_columns(
+            SpecialColumnType::BestRowId,
+            None,
+            None,
+            "MOVIES",
+            RowIdScope::CurrentRow,
+            NullableOption::Nullable,
+        )
+        .unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 8);
+}
+
+#[test]
+fn list_table_privileges() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.table_privileges(None, None, Some("MOVIES")).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 7);
+}
+
+#[test]
+fn list_column_privileges() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.column_privileges(None, None, "MOVIES", None).unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 8);
+}
+
+#[test]
+fn list_columns_w() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let mut stmt = stmt.columns_w(None, None, Some("MOVIES"), None).unwrap();
+    let cols = stmt.num_result_cols().unwrap();
+    assert_eq!(cols, 18);
+    let mut names = Vec::new();
+    while let Some(mut cursor) = stmt.fetch().unwrap() {
+        names.push(cursor.get_data::<String>(4).unwrap().unwrap());
+    }
+    assert_eq!(names, ["TITLE", "YEAR"]);
+}
+
 #[test]
 fn not_read_only() {
 
     let env = create_environment_v3().unwrap();
-    let mut conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut conn = env.connect("TestDataSource", "", "").unwrap();
+
+    assert!(!conn.is_read_only().unwrap());
+    conn.disconnect().unwrap();
+}
+
+#[test]
+fn connection_builder() {
+    let env = create_environment_v3().unwrap();
+    let conn = ConnectionBuilder::new(&env)
+        .dsn("TestDataSource")
+        .login_timeout(5)
+        .access_mode(AccessMode::ReadWrite)
+        .build()
+        .unwrap();
+    assert_eq!(
+        conn.get_attr(ConnectAttrKey::AccessMode).unwrap(),
+        Some(ConnectAttr::AccessMode(AccessMode::ReadWrite))
+    );
+    let conn = ConnectionBuilder::new(&env)
+        .connection_string("DSN=TestDataSource;")
+        .build()
+        .unwrap();
+    assert!(!conn.is_dead().unwrap());
+}
+
+#[test]
+fn connection_builder_debug_hides_password() {
+    let env = create_environment_v3().unwrap();
+    let builder = ConnectionBuilder::new(&env)
+        .dsn("TestDataSource")
+        .pwd("secret")
+        .connection_string("DSN=TestDataSource;PWD=secret;");
+    let debug = format!("{:?}", builder);
+    assert!(!debug.contains("secret"), "{}", debug);
+    assert!(debug.contains("PWD=***"), "{}", debug);
+}
+
+#[test]
+fn connect_with_pre_connection_attrs() {
+    let env = create_environment_v3().unwrap();
+    let attrs = [
+        ConnectAttr::LoginTimeout(5),
+        ConnectAttr::AccessMode(AccessMode::ReadWrite),
+    ];
+    let conn = DisconnectedConnection::with_parent_and_attrs(&env, &attrs)
+        .unwrap()
+        .connect("TestDataSource", "", "")
+        .unwrap();
+    assert_eq!(
+        conn.get_attr(ConnectAttrKey::AccessMode).unwrap(),
+        Some(ConnectAttr::AccessMode(AccessMode::ReadWrite))
+    );
+}
+
+#[test]
+fn environment_v3_8() {
+    let env = create_environment_v3_8().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    match stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        Data(mut stmt) => assert!(stmt.fetch().unwrap().is_some()),
+        NoData(_) => panic!("SELECT statement did not return a result set"),
+    };
+}
+
+#[test]
+fn reconnect() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    assert!(!conn.is_dead().unwrap());
+    let conn = conn.reconnect("DSN=TestDataSource;").unwrap();
+    assert!(!conn.is_dead().unwrap());
+}
+
+#[test]
+fn disconnect_with_open_transaction() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let conn = conn.disable_autocommit().ok().unwrap();
+    {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        stmt.exec_direct("UPDATE MOVIES SET YEAR = YEAR").unwrap();
+    }
+    // Some drivers refuse to disconnect within a transaction, others roll it back
+    let result = conn.disconnect();
+    if let Err((mut still_connected, _)) = result {
+        still_connected.rollback().unwrap();
+        still_connected.disconnect().unwrap();
+    }
+}
+
+#[test]
+fn reconnect_after_disconnect() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let disconnected = conn.disconnect().unwrap();
+    let conn = disconnected.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    match stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        Data(mut stmt) => assert!(stmt.fetch().unwrap().is_some()),
+        NoData(_) => panic!("SELECT statement did not return a result set"),
+    };
+}
+
+#[test]
+fn implicit_disconnect() {
+
+    let env = create_environment_v3().unwrap();
+    env.connect("TestDataSource", "", "").unwrap();
+
+    // if there would be no implicit disconnect, all the drops would panic with function sequence
+    // error
+}
+
+#[test]
+fn invalid_connection_string() {
+
+    let expected = if cfg!(target_os = "windows") {
+        "State: IM002, Native error: 0, Message: [Microsoft][ODBC Driver Manager] Data source \
+            name not found and no default driver specified"
+    } else {
+        "State: IM002, Native error: 0, Message: [unixODBC][Driver Manager]Data source name not \
+            found, and no default driver specified"
+    };
+
+    let environment = create_environment_v3().unwrap();
+    let result = environment.connect_with_connection_string("bla");
+    let message = format!("{}", result.err().unwrap());
+    assert_eq!(expected, message);
+}
+
+#[test]
+fn environment_new_v3() {
+    let env = Environment::new_v3().unwrap();
+    env.connect("TestDataSource", "", "").unwrap();
+}
+
+#[test]
+fn environment_connection_pooling() {
+    set_connection_pooling(ConnectionPooling::OnePerHenv).unwrap();
+    let env = create_environment_v3()
+        .unwrap()
+        .set_cp_match(CpMatch::Strict)
+        .unwrap();
+    for _ in 0..2 {
+        let conn = env.connect("TestDataSource", "", "").unwrap();
+        assert!(!conn.is_dead().unwrap());
+    }
+}
+
+#[test]
+fn environment_rollback_all() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE ROLLBACK_ALL (A INTEGER)")
+        .unwrap();
+    {
+        let tx = env.connect("TestDataSource", "", "").unwrap();
+        let tx = tx.disable_autocommit().ok().expect("autocommit could not be disabled");
+        Statement::with_parent(&tx)
+            .unwrap()
+            .exec_direct("INSERT INTO ROLLBACK_ALL (A) VALUES (1)")
+            .unwrap();
+        env.rollback_all().unwrap();
+    }
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct("SELECT COUNT(*) FROM ROLLBACK_ALL").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(0));
+    } else {
+        panic!("SELECT did not return result set");
+    };
+    Statement::with_parent(&conn).unwrap().exec_direct("DROP TABLE ROLLBACK_ALL").unwrap();
+}
+
+#[test]
+fn environment_output_nts() {
+    let env = create_environment_v3().unwrap().set_output_nts(true).unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(
+            cursor.get_data::<String>(1).unwrap(),
+            Some("2001: A Space Odyssey".to_owned())
+        );
+    } else {
+        panic!("SELECT did not return result set");
+    };
+}
+
+#[test]
+fn test_connection_string() {
+
+    let environment = create_environment_v3().unwrap();
+    let conn = environment
+        .connect_with_connection_string("dsn=TestDataSource;Uid=;Pwd=;")
+        .unwrap();
+    conn.disconnect().unwrap();
+}
+
+#[test]
+fn driver_connect() {
+    let environment = create_environment_v3().unwrap();
+    let (conn, completed) = environment
+        .driver_connect("DSN=TestDataSource;")
+        .unwrap();
+    assert!(completed.to_uppercase().contains("DSN=TESTDATASOURCE"));
+    conn.disconnect().unwrap();
+}
+
+#[test]
+fn test_direct_select() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    let mut stmt = match stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES ORDER BY YEAR")
+        .unwrap() {
+        Data(stmt) => stmt,
+        NoData(_) => panic!("SELECT statement did not return result set!"),
+    };
+
+    assert_eq!(stmt.num_result_cols().unwrap(), 2);
+
+    #[derive(PartialEq, Debug)]
+    struct Movie {
+        title: String,
+        year: i16,
+    }
+
+    let mut actual = Vec::new();
+    while let Some(mut cursor) = stmt.fetch().unwrap() {
+        actual.push(Movie {
+            title: cursor.get_data(1).unwrap().unwrap(),
+            year: cursor.get_data(2).unwrap().unwrap(),
+        })
+    }
+
+    let check = actual ==
+        vec![
+            Movie {
+                title: "2001: A Space Odyssey".to_owned(),
+                year: 1968,
+            },
+            Movie {
+                title: "Jurassic Park".to_owned(),
+                year: 1993,
+            },
+        ];
+
+    println!("test_direct_select query result: {:?}", actual);
+
+    assert!(check);
+}
+
+#[test]
+fn describe_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES").unwrap() {
+        let title = stmt.describe_col(1).unwrap();
+        assert_eq!(title.name, "TITLE");
+        assert_eq!(title.nullable, Some(false));
+        let year = stmt.describe_col(2).unwrap();
+        assert_eq!(year.name, "YEAR");
+        assert_eq!(year.data_type, ffi::SQL_INTEGER);
+        assert_eq!(year.nullable, Some(true));
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn cursor_column_names() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES").unwrap() {
+        let cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.column_count().unwrap(), 2);
+        assert_eq!(cursor.column_name(1).unwrap(), "TITLE");
+        assert_eq!(cursor.column_name(2).unwrap(), "YEAR");
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn owned_rows() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let mut rows = Vec::new();
+    let sql = "SELECT TITLE, YEAR FROM MOVIES ORDER BY YEAR";
+    if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+        while let Some(cursor) = stmt.fetch().unwrap() {
+            rows.push(cursor.into_row().unwrap());
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][0], Some(OdbcValue::String("2001: A Space Odyssey".to_owned())));
+    assert_eq!(rows[1]["YEAR"], Some(OdbcValue::Int(1993)));
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_from_row() {
+    #[derive(Debug, PartialEq, FromRow)]
+    struct Movie {
+        title: String,
+        #[odbc(rename = "YEAR")]
+        released: Option<i32>,
+        #[odbc(skip)]
+        watched: bool,
+    }
+
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    // Columns in a different order than the fields
+    let sql = "SELECT YEAR, TITLE FROM MOVIES ORDER BY YEAR";
+    if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(
+            Movie::from_cursor(&mut cursor).unwrap(),
+            Movie {
+                title: "2001: A Space Odyssey".to_owned(),
+                released: Some(1968),
+                watched: false,
+            }
+        );
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn affected_row_count() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    let stmt = match stmt.exec_direct("CREATE TABLE AFFECTED_ROW_COUNT (A INTEGER);").unwrap() {
+        Data(stmt) => stmt.close_cursor().unwrap(),
+        NoData(stmt) => stmt,
+    };
+    let stmt = stmt.prepare("INSERT INTO AFFECTED_ROW_COUNT (A) VALUES (1), (2), (3)").unwrap();
+    let stmt = match stmt.execute().unwrap() {
+        Data(_) => panic!("INSERT statement returned result set"),
+        NoData(stmt) => stmt,
+    };
+    assert_eq!(stmt.affected_row_count().unwrap(), 3);
+    assert_eq!(stmt.affected_rows().unwrap(), Some(3));
+
+    let stmt = Statement::with_parent(&conn).unwrap();
+    stmt.exec_direct("DROP TABLE AFFECTED_ROW_COUNT").unwrap();
+}
+
+#[test]
+fn rollback_transaction() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE ROLLBACK_TRANSACTION (A INTEGER);")
+        .unwrap();
+
+    let mut conn = conn.disable_autocommit().ok().unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("INSERT INTO ROLLBACK_TRANSACTION (A) VALUES (1)")
+        .unwrap();
+    conn.rollback().unwrap();
+
+    let count = match Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("SELECT COUNT(*) FROM ROLLBACK_TRANSACTION")
+        .unwrap()
+    {
+        Data(mut stmt) => stmt.fetch().unwrap().unwrap().get_data::<i32>(1).unwrap(),
+        NoData(_) => panic!("SELECT statement did not return result set!"),
+    };
+    assert_eq!(count, Some(0));
+
+    let conn = conn.enable_autocommit().ok().unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE ROLLBACK_TRANSACTION")
+        .unwrap();
+}
+
+#[test]
+fn in_clause_with_parameter_slice() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let years = [1968, 1993, 2017];
+    let sql = expand_in_params(
+        "SELECT TITLE FROM MOVIES WHERE YEAR IN (:years) AND TITLE <> ? ORDER BY YEAR",
+        ":years",
+        years.len(),
+    );
+    let title = "Jurassic Park";
+    let stmt = Statement::with_parent(&conn)
+        .unwrap()
+        .bind_parameter_slice(1, &years)
+        .unwrap()
+        .bind_parameter(4, &title)
+        .unwrap();
+    if let Data(mut stmt) = stmt.exec_direct(&sql).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<&str>(1).unwrap(), Some("2001: A Space Odyssey"));
+        assert!(stmt.fetch().unwrap().is_none());
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn query_builder() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let title = String::from("Jurassic Park");
+    let query = Query::new("SELECT YEAR, ? FROM MOVIES WHERE TITLE = ? AND YEAR > ?")
+        .bind(None::<i32>)
+        .bind(title.clone())
+        .bind(1970);
+    drop(title);
+    if let Data(mut stmt) = query.execute(stmt).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(1993));
+        assert_eq!(cursor.get_data::<i32>(2).unwrap(), None);
+        assert!(stmt.fetch().unwrap().is_none());
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn param_status() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    assert_eq!(
+        stmt.params_processed().unwrap_err().get_raw_state(),
+        b"HY010\0"
+    );
+    stmt.exec_direct("CREATE TABLE PARAM_STATUS (A INTEGER)").unwrap();
+
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.enable_param_status().unwrap();
+    // Replaces the status array, the driver must not write to the previous one
+    stmt.enable_param_status().unwrap();
+    let value = 42;
+    let stmt = stmt.bind_parameter(1, &value).unwrap();
+    match stmt.exec_direct("INSERT INTO PARAM_STATUS (A) VALUES (?)").unwrap() {
+        NoData(stmt) => {
+            assert_eq!(stmt.params_processed().unwrap(), 1);
+            assert_eq!(stmt.param_status_array().unwrap(), vec![ParamStatus::Success]);
+        }
+        Data(_) => panic!("INSERT statement returned a result set"),
+    };
+
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE PARAM_STATUS")
+        .unwrap();
+}
+
+#[test]
+fn migration() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut conn = conn.disable_autocommit().ok().unwrap();
+    let version = Migration::new(&mut conn)
+        .add(2, "INSERT INTO MIGRATION (A) VALUES (1)")
+        .add(1, "CREATE TABLE MIGRATION (A INTEGER)")
+        .run()
+        .unwrap();
+    assert_eq!(version, 2);
+
+    // Already applied migrations are skipped, a failing one is rolled back
+    let result = Migration::new(&mut conn)
+        .add(1, "CREATE TABLE MIGRATION (A INTEGER)")
+        .add(2, "INSERT INTO MIGRATION (A) VALUES (1)")
+        .add(3, "INSERT INTO MIGRATION (A) VALUES (2)")
+        .add(4, "INSERT INTO NO_SUCH_TABLE (A) VALUES (3)")
+        .run();
+    assert!(result.is_err());
+    let version = Migration::new(&mut conn).run().unwrap();
+    assert_eq!(version, 3);
+
+    let count = match Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("SELECT COUNT(*) FROM MIGRATION")
+        .unwrap()
+    {
+        Data(mut stmt) => stmt.fetch().unwrap().unwrap().get_data::<i32>(1).unwrap(),
+        NoData(_) => panic!("SELECT statement did not return result set!"),
+    };
+    assert_eq!(count, Some(2));
+
+    let conn = conn.enable_autocommit().ok().unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE MIGRATION")
+        .unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE __schema_migrations")
+        .unwrap();
+}
+
+#[test]
+fn connection_attributes() {
+    let env = create_environment_v3().unwrap();
+    let mut conn = env.connect("TestDataSource", "", "").unwrap();
+    conn.set_attr(ConnectAttr::AccessMode(AccessMode::ReadOnly)).unwrap();
+    assert_eq!(
+        conn.get_attr(ConnectAttrKey::AccessMode).unwrap(),
+        Some(ConnectAttr::AccessMode(AccessMode::ReadOnly))
+    );
+}
+
+#[test]
+fn current_catalog() {
+    let env = create_environment_v3().unwrap();
+    let mut conn = env.connect("TestDataSource", "", "").unwrap();
+    // Not every driver supports catalogs
+    match conn.current_catalog() {
+        Ok(Some(catalog)) => {
+            conn.set_current_catalog(&catalog).unwrap();
+            assert_eq!(conn.current_catalog().unwrap(), Some(catalog));
+        }
+        Ok(None) => (),
+        Err(ref e) if e.is_unsupported() => (),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[test]
+fn packet_size() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    // SQLite does not talk to a server over the network
+    match conn.packet_size() {
+        Ok(Some(size)) => assert!(size > 0),
+        Ok(None) => (),
+        Err(ref e) if e.is_unsupported() => (),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[test]
+fn native_sql() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let sql = "SELECT TITLE FROM MOVIES";
+    assert_eq!(conn.native_sql(sql).unwrap(), sql);
+}
+
+#[test]
+fn statement_warnings() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        // Every warning is returned only once
+        stmt.take_warnings();
+        assert!(stmt.take_warnings().is_empty());
+    } else {
+        panic!("SELECT did not return result set");
+    };
+}
+
+#[test]
+fn cursor_name() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_cursor_name("movies_cursor").unwrap();
+    assert_eq!(stmt.cursor_name().unwrap(), "movies_cursor");
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        assert_eq!(stmt.cursor_name().unwrap(), "movies_cursor");
+    } else {
+        panic!("SELECT did not return result set");
+    };
+}
+
+#[test]
+fn close_statement() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        stmt.close().unwrap();
+    } else {
+        panic!("SELECT did not return result set");
+    };
+    let year = 1968;
+    let stmt = Statement::with_parent(&conn).unwrap().bind_parameter(1, &year).unwrap();
+    stmt.free_params_and_close().unwrap();
+}
+
+#[test]
+fn statement_native_sql() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let sql = "SELECT TITLE FROM MOVIES";
+    assert_eq!(stmt.native_sql(sql).unwrap(), sql);
+}
+
+#[test]
+fn connection_info() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    assert_eq!(
+        conn.get_info(InfoType::DbmsName).unwrap(),
+        InfoValue::String("SQLite".to_owned())
+    );
+    match conn.get_info(InfoType::MaxTableNameLength).unwrap() {
+        InfoValue::U16(_) => (),
+        other => panic!("unexpected value: {:?}", other),
+    }
+    match conn.get_info(InfoType::MaxStatementLength).unwrap() {
+        InfoValue::U32(_) => (),
+        other => panic!("unexpected value: {:?}", other),
+    }
+}
+
+#[test]
+fn connection_info_getters() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    assert_eq!(conn.dbms_name().unwrap(), "SQLite");
+    assert!(!conn.dbms_version().unwrap().is_empty());
+    assert!(!conn.driver_name().unwrap().is_empty());
+    conn.server_name().unwrap();
+    conn.user_name().unwrap();
+}
+
+#[test]
+fn statement_attributes() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::MaxRows(1)).unwrap();
+    assert_eq!(
+        stmt.get_attr(StatementAttrKey::MaxRows).unwrap(),
+        StatementAttr::MaxRows(1)
+    );
+    assert_eq!(
+        stmt.get_attr(StatementAttrKey::CursorType).unwrap(),
+        StatementAttr::CursorType(CursorType::ForwardOnly)
+    );
+}
+
+#[test]
+fn timeouts() {
+    let env = create_environment_v3().unwrap();
+    let conn = env
+        .connect_with_login_timeout("TestDataSource", "", "", 5)
+        .unwrap()
+        .with_connection_timeout(10)
+        .unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap().with_query_timeout(30).unwrap();
+    assert_eq!(
+        stmt.get_attr(StatementAttrKey::QueryTimeout).unwrap(),
+        StatementAttr::QueryTimeout(30)
+    );
+}
+
+#[test]
+fn scrollable_cursor() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::CursorType(CursorType::Static)).unwrap();
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut title_at = |orientation| -> Option<String> {
+            stmt.fetch_scroll(orientation)
+                .unwrap()
+                .map(|mut cursor| cursor.get_data(1).unwrap().unwrap())
+        };
+        assert_eq!(title_at(FetchOrientation::Last), Some("Jurassic Park".to_owned()));
+        assert_eq!(title_at(FetchOrientation::Prior), Some("2001: A Space Odyssey".to_owned()));
+        assert_eq!(title_at(FetchOrientation::Absolute(2)), Some("Jurassic Park".to_owned()));
+        assert_eq!(title_at(FetchOrientation::Relative(1)), None);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn row_number() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_cursor_type(CursorType::Static).unwrap();
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        assert!(stmt.fetch_scroll(FetchOrientation::Last).unwrap().is_some());
+        if let Some(row) = stmt.row_number().unwrap() {
+            assert_eq!(row, 2);
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn async_execution() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    // Drivers may not support asynchronous execution, in which case statements are executed
+    // synchronously as usual
+    match stmt.set_attr(StatementAttr::AsyncEnable(true)) {
+        Ok(()) => (),
+        Err(ref e) if e.is_unsupported() => (),
+        Err(e) => panic!("{}", e),
+    }
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let title: String = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(title, "2001: A Space Odyssey");
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn fetch_async() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = match Statement::with_parent(&conn).unwrap().enable_async() {
+        Ok(stmt) => stmt,
+        Err(ref e) if e.is_unsupported() => return,
+        Err(e) => panic!("{}", e),
+    };
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut titles = Vec::new();
+        loop {
+            let mut future = stmt.fetch_async();
+            let mut context = Context::from_waker(Waker::noop());
+            let cursor = loop {
+                if let Poll::Ready(cursor) = Pin::new(&mut future).poll(&mut context) {
+                    break cursor.unwrap();
+                }
+            };
+            match cursor {
+                Some(mut cursor) => titles.push(cursor.get_data::<String>(1).unwrap().unwrap()),
+                None => break,
+            }
+        }
+        assert_eq!(titles, ["2001: A Space Odyssey", "Jurassic Park"]);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn drop_pending_fetch_future() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Waker};
+
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = match Statement::with_parent(&conn).unwrap().enable_async() {
+        Ok(stmt) => stmt,
+        Err(ref e) if e.is_unsupported() => return,
+        Err(e) => panic!("{}", e),
+    };
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        {
+            // Either completes right away or is canceled once dropped
+            let mut future = stmt.fetch_async();
+            let mut context = Context::from_waker(Waker::noop());
+            let _ = Pin::new(&mut future).poll(&mut context);
+        }
+        // The statement is usable again, either way
+        stmt.close_cursor().unwrap();
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn set_cursor_position() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::CursorType(CursorType::Static)).unwrap();
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        assert!(stmt.fetch().unwrap().is_some());
+        stmt.set_pos(1, SetPosOperation::Position, LockType::NoChange).unwrap();
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn set_pos_update() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE SET_POS_UPDATE (A INTEGER)")
+        .unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("INSERT INTO SET_POS_UPDATE (A) VALUES (1)")
+        .unwrap();
+
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::CursorType(CursorType::Static)).unwrap();
+    stmt.set_attr(StatementAttr::Concurrency(Concurrency::Lock)).unwrap();
+    let updated = if let Data(stmt) = stmt.exec_direct("SELECT A FROM SET_POS_UPDATE").unwrap() {
+        let mut a = 0i32;
+        let mut stmt = stmt.into_bound().bind_col(1, &mut a).unwrap();
+        assert!(stmt.fetch_bound().unwrap());
+        stmt.set(1, Some(2i32));
+        assert_eq!(stmt.get::<i32>(1), Some(&2));
+        match stmt.set_pos(1, SetPosOperation::Update, LockType::NoChange) {
+            Ok(()) => true,
+            Err(ref e) if e.is_unsupported() => false,
+            Err(e) => panic!("{}", e),
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+
+    if updated {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        if let Data(mut stmt) = stmt.exec_direct("SELECT A FROM SET_POS_UPDATE").unwrap() {
+            let mut cursor = stmt.fetch().unwrap().unwrap();
+            assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(2));
+        } else {
+            panic!("SELECT statement returned no result set")
+        };
+    }
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE SET_POS_UPDATE")
+        .unwrap();
+}
+
+#[test]
+fn bulk_add() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE BULK_ADD (A INTEGER, B VARCHAR(10))")
+        .unwrap();
+
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::CursorType(CursorType::Static)).unwrap();
+    stmt.set_attr(StatementAttr::Concurrency(Concurrency::Lock)).unwrap();
+    let added = if let Data(stmt) = stmt.exec_direct("SELECT A, B FROM BULK_ADD").unwrap() {
+        let mut stmt = stmt
+            .into_rowset(2)
+            .unwrap()
+            .bind_col::<i32>(1)
+            .unwrap()
+            .bind_text_col(2, 10)
+            .unwrap();
+        stmt.set(0, 1, Some(1i32));
+        stmt.set_text(0, 2, Some("one"));
+        stmt.set::<i32>(1, 1, None);
+        stmt.set_text(1, 2, Some("two"));
+        match stmt.bulk_operations(BulkOperation::AddByBookmark) {
+            Ok(()) => true,
+            Err(ref e) if e.is_unsupported() => false,
+            Err(e) => panic!("{}", e),
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+
+    if added {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        let sql = "SELECT A, B FROM BULK_ADD ORDER BY B";
+        if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+            let mut cursor = stmt.fetch().unwrap().unwrap();
+            assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(1));
+            assert_eq!(cursor.get_data::<String>(2).unwrap(), Some("one".to_owned()));
+            let mut cursor = stmt.fetch().unwrap().unwrap();
+            assert_eq!(cursor.get_data::<i32>(1).unwrap(), None);
+            assert_eq!(cursor.get_data::<String>(2).unwrap(), Some("two".to_owned()));
+            assert!(stmt.fetch().unwrap().is_none());
+        } else {
+            panic!("SELECT statement returned no result set")
+        };
+    }
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE BULK_ADD")
+        .unwrap();
+}
+
+#[test]
+fn bulk_delete_by_bookmark() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE BULK_DELETE (A INTEGER)")
+        .unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("INSERT INTO BULK_DELETE (A) VALUES (1), (2)")
+        .unwrap();
+
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    stmt.set_attr(StatementAttr::CursorType(CursorType::Static)).unwrap();
+    stmt.set_attr(StatementAttr::Concurrency(Concurrency::Lock)).unwrap();
+    let deleted = match stmt.set_attr(StatementAttr::UseBookmarks(true)) {
+        Ok(()) => {
+            let sql = "SELECT A FROM BULK_DELETE ORDER BY A";
+            if let Data(stmt) = stmt.exec_direct(sql).unwrap() {
+                let mut stmt = stmt
+                    .into_rowset(1)
+                    .unwrap()
+                    .bind_bookmarks(64)
+                    .unwrap()
+                    .bind_col::<i32>(1)
+                    .unwrap();
+                let bookmark = {
+                    let rowset = stmt.fetch_rowset().unwrap().unwrap();
+                    let row = rowset.row(0);
+                    assert_eq!(row.get::<i32>(1), Some(&1));
+                    row.bookmark().expect("driver provided no bookmark").to_vec()
+                };
+                // Fetching the next row overwrites the bookmark buffer
+                assert!(stmt.fetch_rowset().unwrap().is_some());
+                stmt.set_bookmark(0, &bookmark);
+                match stmt.bulk_operations(BulkOperation::DeleteByBookmark) {
+                    Ok(()) => true,
+                    Err(ref e) if e.is_unsupported() => false,
+                    Err(e) => panic!("{}", e),
+                }
+            } else {
+                panic!("SELECT statement returned no result set")
+            }
+        }
+        Err(ref e) if e.is_unsupported() => false,
+        Err(e) => panic!("{}", e),
+    };
+
+    if deleted {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        if let Data(mut stmt) = stmt.exec_direct("SELECT A FROM BULK_DELETE").unwrap() {
+            let mut cursor = stmt.fetch().unwrap().unwrap();
+            assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(2));
+            assert!(stmt.fetch().unwrap().is_none());
+        } else {
+            panic!("SELECT statement returned no result set")
+        };
+    }
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE BULK_DELETE")
+        .unwrap();
+}
+
+#[test]
+fn no_more_results() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        assert!(stmt.more_results().unwrap().is_none());
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn exec_direct_batch() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    // Drivers differ in how many statements of a batch they execute, so only the first result
+    // is checked
+    let sql = "SELECT TITLE FROM MOVIES ORDER BY YEAR; SELECT YEAR FROM MOVIES";
+    let mut batch = stmt.exec_direct_batch(sql).unwrap();
+    match batch.next_result().unwrap() {
+        Some(BatchResult::Data(stmt)) => {
+            let mut cursor = stmt.fetch().unwrap().unwrap();
+            let title: String = cursor.get_data(1).unwrap().unwrap();
+            assert_eq!(title, "2001: A Space Odyssey");
+        }
+        other => panic!("unexpected first result of batch: {:?}", other),
+    }
+    while batch.next_result().unwrap().is_some() {}
+    assert!(batch.next_result().unwrap().is_none());
+    let stmt = batch.close().unwrap();
+    stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap();
+}
+
+#[test]
+fn read_large_binary() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT zeroblob(3000), NULL").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let blob: Vec<u8> = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(blob, vec![0; 3000]);
+        let null: Option<Vec<u8>> = cursor.get_data(2).unwrap();
+        assert_eq!(null, None);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn fixed_size_binary() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let hash = [0xde, 0xad, 0xbe, 0xef];
+    let stmt = stmt.bind_parameter(1, &hash).unwrap();
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT ?, X'0102'").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<[u8; 4]>(1).unwrap(), Some(hash));
+        // Values of another length are not padded
+        let error = cursor.get_data::<[u8; 4]>(2).unwrap_err();
+        assert_eq!(&error.get_raw_state()[..5], b"07006");
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn get_data_of_null_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    let sql = "SELECT 0, NULL, NULL, NULL, NULL, NULL";
+    if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        // Zero must not be mistaken for NULL, nor NULL reported as zero initialized value
+        assert_eq!(cursor.get_data::<i32>(1).unwrap(), Some(0));
+        assert_eq!(cursor.get_data::<i32>(2).unwrap(), None);
+        assert_eq!(cursor.get_data::<f64>(3).unwrap(), None);
+        assert_eq!(cursor.get_data::<String>(4).unwrap(), None);
+        assert_eq!(cursor.get_data::<bool>(5).unwrap(), None);
+        assert_eq!(cursor.get_data::<SqlDate>(6).unwrap(), None);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn read_long_text() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+
+    let sql_text = "SELECT replace(hex(zeroblob(2500)), '00', 'ab')";
+    if let Data(mut stmt) = stmt.exec_direct(sql_text).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let text: String = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(text, "ab".repeat(2500));
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn small_buffer_size() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap().with_buffer_size(4);
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let title: String = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(title, "2001: A Space Odyssey");
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn buffer_smaller_than_fixed_size_type() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap().with_buffer_size(1);
+
+    if let Data(mut stmt) = stmt.exec_direct("SELECT YEAR, YEAR FROM MOVIES ORDER BY YEAR").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let year: i64 = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(year, 1968);
+        let year: f64 = cursor.get_data(2).unwrap().unwrap();
+        assert_eq!(year, 1968.0);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn bind_columns() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
 
-    assert!(!conn.is_read_only().unwrap());
-    conn.disconnect().unwrap();
+    let sql_text = "SELECT YEAR, NULL FROM MOVIES ORDER BY YEAR";
+    if let Data(stmt) = stmt.exec_direct(sql_text).unwrap() {
+        let mut year = 0i32;
+        let mut null = 0i32;
+        let mut stmt = stmt
+            .into_bound()
+            .bind_col(1, &mut year)
+            .unwrap()
+            .bind_col(2, &mut null)
+            .unwrap();
+        let mut years = Vec::new();
+        while stmt.fetch_bound().unwrap() {
+            years.push(*stmt.get::<i32>(1).unwrap());
+            assert_eq!(stmt.get::<i32>(2), None);
+            assert!(!stmt.is_null(1));
+            assert!(stmt.is_null(2));
+        }
+        assert_eq!(years, [1968, 1993]);
+        stmt.unbind().unwrap();
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
 }
 
 #[test]
-fn implicit_disconnect() {
-
+fn data_at_execution() {
     let env = create_environment_v3().unwrap();
-    env.connect("TestDataSource", "", "").unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("CREATE TABLE DATA_AT_EXECUTION (A BLOB);")
+        .unwrap();
 
-    // if there would be no implicit disconnect, all the drops would panic with function sequence
-    // error
+    let blob = vec![7u8; 3000];
+    let stmt = Statement::with_parent(&conn)
+        .unwrap()
+        .bind_parameter_at_exec::<&[u8]>(1, Some(blob.len()))
+        .unwrap();
+    let mut stmt = match stmt
+        .exec_direct_at_exec("INSERT INTO DATA_AT_EXECUTION (A) VALUES (?)")
+        .unwrap()
+    {
+        ExecAtExecState::NeedData(stmt) => stmt,
+        ExecAtExecState::Done(_) => panic!("Statement executed without data for its parameter"),
+    };
+    assert_eq!(stmt.param_data().unwrap(), ParamDataResult::NeedData(1));
+    for chunk in blob.chunks(1000) {
+        stmt.put_data(&chunk).unwrap();
+    }
+    assert_eq!(stmt.param_data().unwrap(), ParamDataResult::Done);
+    stmt.finish().unwrap();
+
+    let read = match Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("SELECT A FROM DATA_AT_EXECUTION")
+        .unwrap()
+    {
+        Data(mut stmt) => stmt.fetch().unwrap().unwrap().get_data::<Vec<u8>>(1).unwrap(),
+        NoData(_) => panic!("SELECT statement did not return result set!"),
+    };
+    assert_eq!(read, Some(blob));
+
+    Statement::with_parent(&conn)
+        .unwrap()
+        .exec_direct("DROP TABLE DATA_AT_EXECUTION")
+        .unwrap();
 }
 
 #[test]
-fn invalid_connection_string() {
-
-    let expected = if cfg!(target_os = "windows") {
-        "State: IM002, Native error: 0, Message: [Microsoft][ODBC Driver Manager] Data source \
-            name not found and no default driver specified"
-    } else {
-        "State: IM002, Native error: 0, Message: [unixODBC][Driver Manager]Data source name not \
-            found, and no default driver specified"
+fn need_data_without_at_exec() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn)
+        .unwrap()
+        .bind_parameter_at_exec::<i32>(1, None)
+        .unwrap();
+    match stmt.exec_direct("SELECT TITLE FROM MOVIES WHERE YEAR = ?") {
+        Err(e) => assert!(e.is_need_data()),
+        Ok(_) => panic!("Statement executed without data for its parameter"),
     };
-
-    let environment = create_environment_v3().unwrap();
-    let result = environment.connect_with_connection_string("bla");
-    let message = format!("{}", result.err().unwrap());
-    assert_eq!(expected, message);
 }
 
 #[test]
-fn test_connection_string() {
+fn debug_format() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    assert!(format!("{:?}", stmt).starts_with("Statement { handle: "));
+    let state = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap();
+    assert!(format!("{:?}", state).starts_with("Data(Statement { "));
+    if let Data(mut stmt) = state {
+        let cursor = stmt.fetch().unwrap().unwrap();
+        assert!(format!("{:?}", cursor).ends_with("buffer_len: 512 }"));
+    }
+}
 
-    let environment = create_environment_v3().unwrap();
-    let conn = environment
-        .connect_with_connection_string("dsn=TestDataSource;Uid=;Pwd=;")
-        .unwrap();
-    conn.disconnect().unwrap();
+#[test]
+fn describe_param() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.prepare("SELECT TITLE FROM MOVIES WHERE YEAR = ?").unwrap();
+    match stmt.describe_param(1) {
+        Ok(desc) => println!("{:?}", desc),
+        Err(e) => assert!(e.is_unsupported(), "{}", e),
+    }
 }
 
 #[test]
-fn test_direct_select() {
+fn num_params() {
     let env = create_environment_v3().unwrap();
     let conn = env.connect("TestDataSource", "", "").unwrap();
     let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.prepare("SELECT TITLE FROM MOVIES WHERE YEAR BETWEEN ? AND ?").unwrap();
+    assert_eq!(stmt.num_params().unwrap(), 2);
+}
 
-    let mut stmt = match stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES ORDER BY YEAR")
-        .unwrap() {
-        Data(stmt) => stmt,
-        NoData(_) => panic!("SELECT statement did not return result set!"),
+#[test]
+fn fetch_rowset() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let sql = "SELECT TITLE, YEAR FROM MOVIES ORDER BY YEAR";
+    if let Data(stmt) = stmt.exec_direct(sql).unwrap() {
+        let mut stmt = stmt
+            .into_rowset(10)
+            .unwrap()
+            .bind_text_col(1, 255)
+            .unwrap()
+            .bind_col::<i32>(2)
+            .unwrap();
+        {
+            let rowset = stmt.fetch_rowset().unwrap().unwrap();
+            assert_eq!(rowset.len(), 2);
+            let row = rowset.row(1);
+            assert_eq!(row.status(), RowStatus::Success);
+            assert_eq!(row.get_text(1).unwrap(), "Jurassic Park");
+            assert_eq!(row.get::<i32>(2), Some(&1993));
+            let years: Vec<_> = rowset.iter().map(|row| *row.get::<i32>(2).unwrap()).collect();
+            assert_eq!(years, [1968, 1993]);
+        }
+        assert!(stmt.fetch_rowset().unwrap().is_none());
+        // With a row array size of `1` each call to `fetch` yields a single row again
+        let stmt = stmt.unbind().unwrap().close_cursor().unwrap();
+        let mut rows = 0;
+        if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+            while let Some(mut cursor) = stmt.fetch().unwrap() {
+                assert!(cursor.get_data::<String>(1).unwrap().is_some());
+                rows += 1;
+            }
+        }
+        assert_eq!(rows, 2);
+    } else {
+        panic!("SELECT statement returned no result set")
     };
+}
 
-    assert_eq!(stmt.num_result_cols().unwrap(), 2);
-
-    #[derive(PartialEq, Debug)]
-    struct Movie {
-        title: String,
-        year: i16,
-    }
+#[test]
+fn exec_direct_w() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.prepare_w("SELECT TITLE FROM MOVIES WHERE YEAR = 1968").unwrap();
+    assert_eq!(stmt.num_result_cols().unwrap(), 1);
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct_w("SELECT 'Grüße, 世界'").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<String>(1).unwrap().unwrap(), "Grüße, 世界");
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
 
-    let mut actual = Vec::new();
-    while let Some(mut cursor) = stmt.fetch().unwrap() {
-        actual.push(Movie {
-            title: cursor.get_data(1).unwrap().unwrap(),
-            year: cursor.get_data(2).unwrap().unwrap(),
-        })
-    }
+#[test]
+fn get_wide_string() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct_w("SELECT 'Grüße, 世界', NULL").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(
+            cursor.get_data::<WideString>(1).unwrap(),
+            Some(WideString("Grüße, 世界".to_owned()))
+        );
+        assert_eq!(cursor.get_data::<WideString>(2).unwrap(), None);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
 
-    let check = actual ==
-        vec![
-            Movie {
-                title: "2001: A Space Odyssey".to_owned(),
-                year: 1968,
-            },
-            Movie {
-                title: "Jurassic Park".to_owned(),
-                year: 1993,
-            },
-        ];
+#[test]
+fn get_decimal() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let sql = "SELECT CAST(YEAR AS DECIMAL(10,2)) FROM MOVIES ORDER BY YEAR";
+    if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        let year: Decimal = cursor.get_data(1).unwrap().unwrap();
+        assert_eq!(year.to_f64(), 1968.0);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
 
-    println!("test_direct_select query result: {:?}", actual);
+#[test]
+fn iterate_rows() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
 
-    assert!(check);
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE, YEAR FROM MOVIES ORDER BY YEAR")
+        .unwrap()
+    {
+        let actual: Vec<(String, i16)> = stmt
+            .rows(|cursor| Ok((cursor.get_data(1)?.unwrap(), cursor.get_data(2)?.unwrap())))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            actual,
+            vec![
+                ("2001: A Space Odyssey".to_owned(), 1968),
+                ("Jurassic Park".to_owned(), 1993),
+            ]
+        );
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
 }
 
 #[test]
@@ -169,6 +1572,25 @@ fn execution_with_parameter() {
     };
 }
 
+#[test]
+fn execution_with_nullable_parameter() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let sql = "SELECT COUNT(*) FROM MOVIES WHERE YEAR = ? OR (? IS NULL AND YEAR > 1990)";
+
+    let count = |year: Option<i32>| {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        let stmt = stmt.bind_parameter(1, &year).unwrap();
+        let stmt = stmt.bind_parameter(2, &year).unwrap();
+        match stmt.exec_direct(sql).unwrap() {
+            Data(mut stmt) => stmt.fetch().unwrap().unwrap().get_data::<i32>(1).unwrap(),
+            NoData(_) => panic!("SELECT statement returned no result set"),
+        }
+    };
+    assert_eq!(count(Some(1968)), Some(1));
+    assert_eq!(count(None), Some(1));
+}
+
 #[test]
 fn prepared_execution() {
     let env = create_environment_v3().unwrap();
@@ -199,6 +1621,31 @@ fn prepared_execution() {
     execute_query(1993, "Jurassic Park", stmt).unwrap();
 }
 
+#[test]
+fn prepared_execution_with_same_parameters() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let year = 1993;
+    let mut stmt = stmt
+        .prepare("SELECT TITLE FROM MOVIES WHERE YEAR = ?")
+        .unwrap()
+        .bind_parameter(1, &year)
+        .unwrap();
+
+    for _ in 0..2 {
+        stmt = if let Data(mut stmt) = stmt.execute().unwrap() {
+            {
+                let mut cursor = stmt.fetch().unwrap().unwrap();
+                assert_eq!(cursor.get_data::<String>(1).unwrap().unwrap(), "Jurassic Park");
+            }
+            stmt.close_cursor().unwrap()
+        } else {
+            panic!("SELECT statement returned no result set");
+        };
+    }
+}
+
 // These tests query the results of catalog functions. These results are only likely to match the
 // expectation on the travis ci build on linux. Therefore we limit compilation and execution of
 // these tests to this platform.
@@ -272,6 +1719,63 @@ fn list_system_data_sources() {
     assert!(sources.iter().eq(expected.iter()));
 }
 
+#[test]
+fn iterate_data_sources() {
+    let mut environment = create_environment_v3().unwrap();
+    let expected = environment.data_sources().unwrap();
+    let actual: Vec<_> = environment
+        .data_source_iter(DataSourceDirection::All)
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn move_connection_and_statement_to_other_thread() {
+    let env: &'static Environment<Version3> = Box::leak(Box::new(create_environment_v3().unwrap()));
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let year = std::thread::spawn(move || {
+        let stmt = Statement::with_parent(&conn).unwrap();
+        let sql = "SELECT YEAR FROM MOVIES ORDER BY YEAR";
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(move || match stmt.exec_direct(sql).unwrap() {
+                Data(mut stmt) => stmt.fetch().unwrap().unwrap().get_data::<u32>(1).unwrap(),
+                NoData(_) => panic!("SELECT did not return a result set"),
+            });
+            worker.join().unwrap()
+        })
+    })
+    .join()
+    .unwrap();
+    assert_eq!(year, Some(1968));
+}
+
+#[test]
+fn connection_pool() {
+    let env = create_environment_v3().unwrap();
+    let pool = Pool::new(|| env.connect("TestDataSource", "", ""), 2)
+        .with_validation(Validation::Query("SELECT 1".to_owned()));
+    {
+        let first = pool.get().unwrap();
+        let _second = pool.get().unwrap();
+        assert!(!first.is_dead().unwrap());
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.idle(), 0);
+    }
+    assert_eq!(pool.idle(), 2);
+    // Connections are shared by threads, without opening new ones
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let conn = pool.get().unwrap();
+                let stmt = Statement::with_parent(&*conn).unwrap();
+                stmt.exec_direct("SELECT * FROM MOVIES").unwrap();
+            });
+        }
+    });
+    assert_eq!(pool.size(), 2);
+}
+
 #[test]
 fn read_big_string() {
     let env = create_environment_v3().unwrap();
@@ -351,3 +1855,127 @@ fn zero_truncation_bug() {
         panic!("SELECT statement returned no result set")
     };
 }
+
+#[test]
+fn column_attrs() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE AS NAME FROM MOVIES").unwrap() {
+        let attrs = stmt.column_attrs(1).unwrap();
+        assert_eq!(attrs.label, "NAME");
+        assert!(attrs.base_column_name.is_empty() || attrs.base_column_name == "TITLE");
+        assert!(attrs.base_table_name.is_empty() || attrs.base_table_name == "MOVIES");
+        assert!(!attrs.auto_unique_value);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn col_attribute() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE AS NAME, YEAR FROM MOVIES").unwrap() {
+        assert_eq!(
+            stmt.col_attribute(1, ColAttr::Label).unwrap(),
+            ColAttrValue::String("NAME".to_owned())
+        );
+        match stmt.col_attribute(2, ColAttr::DisplaySize).unwrap() {
+            ColAttrValue::Integer(size) => assert!(size > 0),
+            other => panic!("expected integer display size, got {:?}", other),
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn get_data_as_str_lossy() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let sql = "SELECT TITLE, CAST(X'48E96C6C6F' AS TEXT), NULL FROM MOVIES ORDER BY YEAR";
+    if let Data(mut stmt) = stmt.exec_direct(sql).unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(
+            cursor.get_data_as_str_lossy(1).unwrap(),
+            Some("2001: A Space Odyssey".to_owned())
+        );
+        assert_eq!(
+            cursor.get_data_as_str_lossy(2).unwrap(),
+            Some("H\u{FFFD}llo".to_owned())
+        );
+        assert_eq!(cursor.get_data_as_str_lossy(3).unwrap(), None);
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn cursor_row_count() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    match stmt.set_cursor_type(CursorType::Static) {
+        Ok(()) => (),
+        Err(ref e) if e.is_unsupported() => return,
+        Err(e) => panic!("{}", e),
+    }
+    if let Data(stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES").unwrap() {
+        if let Some(count) = stmt.cursor_row_count() {
+            assert!(count <= 2);
+        }
+    } else {
+        panic!("SELECT statement returned no result set")
+    };
+}
+
+#[test]
+fn cursor_type_and_concurrency() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let mut stmt = Statement::with_parent(&conn).unwrap();
+    match stmt.set_cursor_type(CursorType::Static) {
+        Ok(()) => assert_eq!(
+            stmt.get_attr(StatementAttrKey::CursorType).unwrap(),
+            StatementAttr::CursorType(CursorType::Static)
+        ),
+        Err(ref e) if e.is_unsupported() => (),
+        Err(e) => panic!("{}", e),
+    }
+    match stmt.set_concurrency(Concurrency::ReadOnly) {
+        Ok(()) => assert_eq!(
+            stmt.get_attr(StatementAttrKey::Concurrency).unwrap(),
+            StatementAttr::Concurrency(Concurrency::ReadOnly)
+        ),
+        Err(ref e) if e.is_unsupported() => (),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[test]
+fn copy_descriptor() {
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let year = 1968;
+    let template = Statement::with_parent(&conn).unwrap().bind_parameter(1, &year).unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    // Not every driver supports copying descriptors
+    let stmt = match stmt.copy_descriptor_from(&template, DescType::AppParamDesc) {
+        Ok(stmt) => stmt,
+        Err(ref e) if e.is_unsupported() => return,
+        Err(e) => panic!("{}", e),
+    };
+    let stmt = stmt.copy_descriptor_from(&template, DescType::ImpParamDesc).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct("SELECT TITLE FROM MOVIES WHERE YEAR = ?").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(
+            cursor.get_data::<String>(1).unwrap(),
+            Some("2001: A Space Odyssey".to_owned())
+        );
+    } else {
+        panic!("SELECT did not return result set");
+    };
+}
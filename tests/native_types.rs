@@ -3,6 +3,12 @@
 //! These tests assume there is a Stage table with a Varchar in 'A', an Integer in 'B' and a Real
 //! in 'C'
 extern crate odbc;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
 use odbc::*;
 use std::ffi::CString;
 
@@ -105,3 +111,123 @@ fn _f32() {
 fn _f64() {
     test_type!(f64, C, 3.14)
 }
+
+#[test]
+fn _bool() {
+    test_type!(bool, "SELECT 1;", true);
+    test_type!(bool, "SELECT 0;", false)
+}
+
+#[test]
+fn _odbc_date() {
+    test_type!(OdbcDate, "SELECT '2019-08-16';", OdbcDate { year: 2019, month: 8, day: 16 })
+}
+
+#[test]
+fn _odbc_timestamp() {
+    test_type!(
+        OdbcTimestamp,
+        "SELECT '2019-08-16 10:20:30';",
+        OdbcTimestamp { year: 2019, month: 8, day: 16, hour: 10, minute: 20, second: 30, fraction: 0 }
+    )
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn _naive_date() {
+    use chrono::NaiveDate;
+    test_type!(NaiveDate, "SELECT '2019-08-16';", NaiveDate::from_ymd_opt(2019, 8, 16).unwrap())
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn _naive_time() {
+    use chrono::NaiveTime;
+    test_type!(NaiveTime, "SELECT '10:20:30';", NaiveTime::from_hms_opt(10, 20, 30).unwrap())
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn _naive_date_time() {
+    use chrono::NaiveDate;
+    test_type!(
+        chrono::NaiveDateTime,
+        "SELECT '2019-08-16 10:20:30';",
+        NaiveDate::from_ymd_opt(2019, 8, 16).unwrap().and_hms_opt(10, 20, 30).unwrap()
+    )
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn _time_date() {
+    use time::{Date, Month};
+    test_type!(Date, "SELECT '2019-08-16';", Date::from_calendar_date(2019, Month::August, 16).unwrap())
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn _time_time() {
+    use time::Time;
+    test_type!(Time, "SELECT '10:20:30';", Time::from_hms(10, 20, 30).unwrap())
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn _time_primitive_date_time() {
+    use time::{Date, Month, PrimitiveDateTime, Time};
+    test_type!(
+        PrimitiveDateTime,
+        "SELECT '2019-08-16 10:20:30';",
+        PrimitiveDateTime::new(
+            Date::from_calendar_date(2019, Month::August, 16).unwrap(),
+            Time::from_hms(10, 20, 30).unwrap()
+        )
+    )
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn _time_offset_date_time_falls_back_to_utc() {
+    use time::{Date, Month, OffsetDateTime, Time};
+    test_type!(
+        OffsetDateTime,
+        "SELECT '2019-08-16 10:20:30';",
+        Date::from_calendar_date(2019, Month::August, 16)
+            .unwrap()
+            .with_time(Time::from_hms(10, 20, 30).unwrap())
+            .assume_utc()
+    )
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn _time_date_parameter() {
+    use time::{Date, Month};
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let date = Date::from_calendar_date(2019, Month::August, 16).unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap().bind_parameter(1, &date).unwrap();
+    if let Data(mut stmt) = stmt.exec_direct("SELECT ?").unwrap() {
+        let mut cursor = stmt.fetch().unwrap().unwrap();
+        assert_eq!(cursor.get_data::<Date>(1).unwrap(), Some(date));
+    } else {
+        panic!("SELECT did not return result set");
+    };
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn _rust_decimal_round_trip() {
+    use rust_decimal::Decimal;
+    let env = create_environment_v3().unwrap();
+    let conn = env.connect("TestDataSource", "", "").unwrap();
+    let stmt = Statement::with_parent(&conn).unwrap();
+    stmt.exec_direct("CREATE TABLE RUST_DECIMALS (PRICE DECIMAL(10,2))").unwrap();
+    let price = Decimal::new(12345, 2);
+    let stmt = Statement::with_parent(&conn).unwrap();
+    let stmt = stmt.bind_parameter(1, &price).unwrap();
+    stmt.exec_direct("INSERT INTO RUST_DECIMALS (PRICE) VALUES (?)").unwrap();
+    test_type!(Decimal, "SELECT PRICE FROM RUST_DECIMALS", price);
+    let stmt = Statement::with_parent(&conn).unwrap();
+    stmt.exec_direct("DROP TABLE RUST_DECIMALS").unwrap();
+}